@@ -22,6 +22,10 @@ pub trait ArmorMaterial {
     fn get_texture_type(slot: PlayerArmorSlot) -> Option<PlayerPartTextureType> {
         None
     }
+
+    fn has_glint(&self) -> bool {
+        false
+    }
 }
 
 impl ArmorMaterial for () {}
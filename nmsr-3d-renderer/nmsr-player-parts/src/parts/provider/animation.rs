@@ -0,0 +1,364 @@
+use std::f32::consts::PI;
+
+use glam::Vec3;
+
+use crate::types::PlayerBodyPartType;
+
+use super::pose::{PlayerBodyPartPose, PlayerBodyPose};
+
+/// A keyframed animation of the player's body parts, sampled into a [`PlayerBodyPose`].
+///
+/// Every value is expressed the same way as in [`PlayerBodyPartPose`], using Minecraft's model axes,
+/// with rotations in degrees and offsets in pixels.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PlayerAnimation {
+    /// The length of the animation, in seconds.
+    pub length: f32,
+    /// The time (in seconds) the animation goes back to once it ends, if it loops.
+    pub loop_start: Option<f32>,
+    pub channels: Vec<PlayerAnimationChannel>,
+}
+
+/// The part of the player that an animation channel moves.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum PlayerAnimationTarget {
+    BodyPart(PlayerBodyPartType),
+    /// The whole player.
+    Model,
+}
+
+impl PlayerAnimationTarget {
+    /// Maps the name of a bone (from a Bedrock geometry or an emote) to the part it moves.
+    ///
+    /// Bones of the outer layers map to the body part they're on, since layers already follow their body part.
+    pub fn from_bone_name(name: &str) -> Option<Self> {
+        let name = name.to_ascii_lowercase().replace('_', "");
+
+        let body_part = match name.as_str() {
+            "head" | "hat" => PlayerBodyPartType::Head,
+            "body" | "torso" | "jacket" => PlayerBodyPartType::Body,
+            "leftarm" | "leftsleeve" => PlayerBodyPartType::LeftArm,
+            "rightarm" | "rightsleeve" => PlayerBodyPartType::RightArm,
+            "leftleg" | "leftpants" => PlayerBodyPartType::LeftLeg,
+            "rightleg" | "rightpants" => PlayerBodyPartType::RightLeg,
+            "root" | "waist" | "player" => return Some(Self::Model),
+            _ => return None,
+        };
+
+        Some(Self::BodyPart(body_part))
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum PlayerAnimationProperty {
+    RotationX,
+    RotationY,
+    RotationZ,
+    OffsetX,
+    OffsetY,
+    OffsetZ,
+}
+
+impl PlayerAnimationProperty {
+    fn get_mut(self, pose: &mut PlayerBodyPartPose) -> &mut f32 {
+        match self {
+            Self::RotationX => &mut pose.rotation.x,
+            Self::RotationY => &mut pose.rotation.y,
+            Self::RotationZ => &mut pose.rotation.z,
+            Self::OffsetX => &mut pose.offset.x,
+            Self::OffsetY => &mut pose.offset.y,
+            Self::OffsetZ => &mut pose.offset.z,
+        }
+    }
+}
+
+/// The keyframes of a single value of a part of the player.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlayerAnimationChannel {
+    pub target: PlayerAnimationTarget,
+    pub property: PlayerAnimationProperty,
+    /// The keyframes of this channel, sorted by their time.
+    pub keyframes: Vec<PlayerAnimationKeyframe>,
+}
+
+impl PlayerAnimationChannel {
+    fn sample(&self, time: f32) -> f32 {
+        let next_index = self.keyframes.partition_point(|k| k.time <= time);
+
+        match (
+            next_index.checked_sub(1).map(|i| &self.keyframes[i]),
+            self.keyframes.get(next_index),
+        ) {
+            (Some(previous), Some(next)) => {
+                let progress = (time - previous.time) / (next.time - previous.time);
+                let progress = next.easing.apply(progress.clamp(0.0, 1.0));
+
+                let target = next.value_before();
+
+                previous.value + (target - previous.value) * progress
+            }
+            (Some(previous), None) => previous.value,
+            (None, Some(next)) => next.value_before(),
+            (None, None) => 0.0,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct PlayerAnimationKeyframe {
+    /// The time of this keyframe, in seconds.
+    pub time: f32,
+    /// The value from this keyframe onwards.
+    pub value: f32,
+    /// The value blended into when coming from the previous keyframe, if it jumps to [`Self::value`] once reached.
+    pub pre_value: Option<f32>,
+    /// How the value is eased when coming from the previous keyframe.
+    pub easing: PlayerAnimationEasing,
+}
+
+impl PlayerAnimationKeyframe {
+    fn value_before(&self) -> f32 {
+        self.pre_value.unwrap_or(self.value)
+    }
+}
+
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub enum PlayerAnimationEasing {
+    #[default]
+    Linear,
+    /// Keep the value of the previous keyframe until this keyframe is reached.
+    Constant,
+    In(PlayerAnimationEasingFunction),
+    Out(PlayerAnimationEasingFunction),
+    InOut(PlayerAnimationEasingFunction),
+}
+
+/// The easing functions from <https://easings.net>, which are the ones supported by Emotecraft.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum PlayerAnimationEasingFunction {
+    Sine,
+    Quad,
+    Cubic,
+    Quart,
+    Quint,
+    Expo,
+    Circ,
+    Back,
+    Elastic,
+    Bounce,
+}
+
+impl PlayerAnimationEasingFunction {
+    fn ease_in(self, t: f32) -> f32 {
+        match self {
+            Self::Sine => 1.0 - (t * PI / 2.0).cos(),
+            Self::Quad => t.powi(2),
+            Self::Cubic => t.powi(3),
+            Self::Quart => t.powi(4),
+            Self::Quint => t.powi(5),
+            Self::Expo if t == 0.0 => 0.0,
+            Self::Expo => 2f32.powf(10.0 * t - 10.0),
+            Self::Circ => 1.0 - (1.0 - t.powi(2)).sqrt(),
+            Self::Back => 2.70158 * t.powi(3) - 1.70158 * t.powi(2),
+            Self::Elastic if t == 0.0 || t == 1.0 => t,
+            Self::Elastic => {
+                -(2f32.powf(10.0 * t - 10.0)) * ((t * 10.0 - 10.75) * (2.0 * PI / 3.0)).sin()
+            }
+            Self::Bounce => 1.0 - Self::bounce_out(1.0 - t),
+        }
+    }
+
+    fn bounce_out(t: f32) -> f32 {
+        const N: f32 = 7.5625;
+        const D: f32 = 2.75;
+
+        if t < 1.0 / D {
+            N * t * t
+        } else if t < 2.0 / D {
+            let t = t - 1.5 / D;
+            N * t * t + 0.75
+        } else if t < 2.5 / D {
+            let t = t - 2.25 / D;
+            N * t * t + 0.9375
+        } else {
+            let t = t - 2.625 / D;
+            N * t * t + 0.984375
+        }
+    }
+}
+
+impl PlayerAnimationEasing {
+    /// Parses an easing name, like Emotecraft's `EASEINOUTQUAD` or `LINEAR`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.to_ascii_lowercase().replace('_', "");
+
+        match name.as_str() {
+            "linear" => return Some(Self::Linear),
+            "constant" | "step" => return Some(Self::Constant),
+            _ => {}
+        }
+
+        let name = name.strip_prefix("ease").unwrap_or(&name);
+
+        let (constructor, function): (fn(_) -> Self, _) =
+            if let Some(f) = name.strip_prefix("inout") {
+                (Self::InOut, f)
+            } else if let Some(f) = name.strip_prefix("in") {
+                (Self::In, f)
+            } else if let Some(f) = name.strip_prefix("out") {
+                (Self::Out, f)
+            } else {
+                return None;
+            };
+
+        let function = match function {
+            "sine" => PlayerAnimationEasingFunction::Sine,
+            "quad" => PlayerAnimationEasingFunction::Quad,
+            "cubic" => PlayerAnimationEasingFunction::Cubic,
+            "quart" => PlayerAnimationEasingFunction::Quart,
+            "quint" => PlayerAnimationEasingFunction::Quint,
+            "expo" => PlayerAnimationEasingFunction::Expo,
+            "circ" => PlayerAnimationEasingFunction::Circ,
+            "back" => PlayerAnimationEasingFunction::Back,
+            "elastic" => PlayerAnimationEasingFunction::Elastic,
+            "bounce" => PlayerAnimationEasingFunction::Bounce,
+            _ => return None,
+        };
+
+        Some(constructor(function))
+    }
+
+    pub fn apply(self, t: f32) -> f32 {
+        match self {
+            Self::Linear => t,
+            Self::Constant => 0.0,
+            Self::In(f) => f.ease_in(t),
+            Self::Out(f) => 1.0 - f.ease_in(1.0 - t),
+            Self::InOut(f) if t < 0.5 => f.ease_in(t * 2.0) / 2.0,
+            Self::InOut(f) => 1.0 - f.ease_in(2.0 - t * 2.0) / 2.0,
+        }
+    }
+}
+
+impl PlayerAnimation {
+    /// Maps a time (in seconds) to the time inside of the animation, taking looping into account.
+    pub fn get_animation_time(&self, time: f32) -> f32 {
+        match self.loop_start {
+            Some(loop_start) if time > self.length && self.length > loop_start => {
+                loop_start + (time - loop_start) % (self.length - loop_start)
+            }
+            _ => time.min(self.length),
+        }
+    }
+
+    /// Samples every channel of the animation at the given time (in seconds).
+    ///
+    /// Parts that aren't animated are left in their default position.
+    pub fn sample(&self, time: f32) -> PlayerBodyPose {
+        let time = self.get_animation_time(time);
+
+        let mut pose = PlayerBodyPose::default();
+        let mut model_pose = PlayerBodyPartPose::default();
+
+        for channel in &self.channels {
+            let part_pose = match channel.target {
+                PlayerAnimationTarget::BodyPart(body_part) => pose.get_part_pose_mut(body_part),
+                PlayerAnimationTarget::Model => &mut model_pose,
+            };
+
+            *channel.property.get_mut(part_pose) = channel.sample(time);
+        }
+
+        // The whole player is moved using our axes instead of Minecraft's
+        let PlayerBodyPartPose { rotation, offset } = model_pose;
+
+        pose.model_rotation = Vec3::new(-rotation.x, -rotation.y, rotation.z);
+        pose.model_offset = Vec3::new(-offset.x, -offset.y, offset.z);
+
+        pose
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sample_interpolates_between_keyframes() {
+        let keyframe = |time, value| PlayerAnimationKeyframe {
+            time,
+            value,
+            pre_value: None,
+            easing: PlayerAnimationEasing::Linear,
+        };
+
+        let animation = PlayerAnimation {
+            length: 2.0,
+            loop_start: Some(0.0),
+            channels: vec![PlayerAnimationChannel {
+                target: PlayerAnimationTarget::from_bone_name("rightArm").unwrap(),
+                property: PlayerAnimationProperty::RotationX,
+                keyframes: vec![keyframe(0.0, 0.0), keyframe(1.0, -90.0), keyframe(2.0, 0.0)],
+            }],
+        };
+
+        assert_eq!(animation.sample(0.5).right_arm.rotation.x, -45.0);
+        assert_eq!(animation.sample(1.0).right_arm.rotation.x, -90.0);
+        assert_eq!(animation.sample(2.5).right_arm.rotation.x, -45.0);
+        assert_eq!(
+            animation.sample(0.5).left_arm,
+            PlayerBodyPartPose::default()
+        );
+    }
+
+    #[test]
+    fn sample_blends_into_pre_value_and_leaves_from_value() {
+        let animation = PlayerAnimation {
+            length: 2.0,
+            loop_start: None,
+            channels: vec![PlayerAnimationChannel {
+                target: PlayerAnimationTarget::BodyPart(PlayerBodyPartType::Head),
+                property: PlayerAnimationProperty::RotationY,
+                keyframes: vec![
+                    PlayerAnimationKeyframe {
+                        time: 0.0,
+                        value: 0.0,
+                        pre_value: None,
+                        easing: PlayerAnimationEasing::Linear,
+                    },
+                    PlayerAnimationKeyframe {
+                        time: 1.0,
+                        value: 90.0,
+                        pre_value: Some(30.0),
+                        easing: PlayerAnimationEasing::Linear,
+                    },
+                    PlayerAnimationKeyframe {
+                        time: 2.0,
+                        value: 0.0,
+                        pre_value: None,
+                        easing: PlayerAnimationEasing::Linear,
+                    },
+                ],
+            }],
+        };
+
+        assert_eq!(animation.sample(0.5).head.rotation.y, 15.0);
+        assert_eq!(animation.sample(1.0).head.rotation.y, 90.0);
+        assert_eq!(animation.sample(1.5).head.rotation.y, 45.0);
+    }
+
+    #[test]
+    fn easing_from_name() {
+        assert_eq!(
+            PlayerAnimationEasing::from_name("EASEINOUTQUAD"),
+            Some(PlayerAnimationEasing::InOut(
+                PlayerAnimationEasingFunction::Quad
+            ))
+        );
+        assert_eq!(
+            PlayerAnimationEasing::from_name("linear"),
+            Some(PlayerAnimationEasing::Linear)
+        );
+        assert_eq!(PlayerAnimationEasing::from_name("wobbly"), None);
+    }
+}
@@ -0,0 +1,118 @@
+use std::marker::PhantomData;
+
+use glam::Vec3;
+
+use crate::{
+    model::ArmorMaterial,
+    parts::{
+        part::{Part, PartAnchorInfo},
+        provider::{PartsProvider, PlayerPartProviderContext},
+        uv::{box_uv, CubeFaceUvs},
+    },
+    types::{PlayerBodyPartType, PlayerPartTextureType},
+};
+
+#[cfg(feature = "part_tracker")]
+use crate::parts::provider::minecraft::get_part_group_name;
+
+/// Provides the elytra worn on the player's back, replacing the cape.
+///
+/// The wings are textured using the elytra region of a 64x32 cape (or elytra) texture.
+pub struct ElytraPlayerPartsProvider<M>(PhantomData<M>);
+
+impl<M> Default for ElytraPlayerPartsProvider<M> {
+    fn default() -> Self {
+        Self(Default::default())
+    }
+}
+
+/// The rotation of the left wing (in degrees), the right wing mirrors it.
+#[derive(Debug, Copy, Clone)]
+struct ElytraWingRotation {
+    x: f32,
+    z: f32,
+}
+
+impl ElytraWingRotation {
+    /// The wings are folded against the player's back.
+    const FOLDED: Self = Self { x: 15.0, z: -15.0 };
+    /// The wings are spread out to the sides.
+    const GLIDING: Self = Self { x: 20.0, z: -90.0 };
+}
+
+impl<M: ArmorMaterial> PartsProvider<M> for ElytraPlayerPartsProvider<M> {
+    fn get_parts(
+        &self,
+        context: &PlayerPartProviderContext<M>,
+        body_part: PlayerBodyPartType,
+    ) -> Vec<Part> {
+        if !context.has_elytra || body_part != PlayerBodyPartType::Body {
+            return vec![];
+        }
+
+        let rotation = if context.movement.is_gliding {
+            ElytraWingRotation::GLIDING
+        } else {
+            ElytraWingRotation::FOLDED
+        };
+
+        vec![
+            create_wing(false, rotation, "Left Elytra Wing"),
+            create_wing(true, rotation, "Right Elytra Wing"),
+        ]
+    }
+}
+
+#[cfg_attr(not(feature = "part_tracker"), allow(unused_variables))]
+fn create_wing(is_right_wing: bool, rotation: ElytraWingRotation, name: &str) -> Part {
+    let mut uvs = box_uv(24, 2, [10, 20, 2]);
+
+    if is_right_wing {
+        uvs = mirror_uvs(uvs);
+    }
+
+    // Both wings start out overlapping behind the body, they're only spread apart by their rotation.
+    let mut wing = Part::new_cube(
+        PlayerPartTextureType::Elytra,
+        [-5, 4, 2],
+        [10, 20, 2],
+        uvs,
+        #[cfg(feature = "part_tracker")]
+        Some(name.to_string()),
+    )
+    .expand_splat(1.0);
+
+    let (pivot_x, z_rotation) = if is_right_wing {
+        (5.0, -rotation.z)
+    } else {
+        (-5.0, rotation.z)
+    };
+
+    let anchor = Some(PartAnchorInfo::new_rotation_anchor_position(Vec3::new(
+        pivot_x, 24.0, 2.0,
+    )));
+
+    // The game applies the X rotation before the Z rotation, so we do the same here.
+    wing.rotate(Vec3::new(-rotation.x, 0.0, 0.0), anchor);
+    wing.rotate(Vec3::new(0.0, 0.0, z_rotation), anchor);
+
+    #[cfg(feature = "part_tracker")]
+    {
+        wing.with_group(get_part_group_name(PlayerBodyPartType::Body))
+    }
+    #[cfg(not(feature = "part_tracker"))]
+    {
+        wing
+    }
+}
+
+fn mirror_uvs(uvs: CubeFaceUvs) -> CubeFaceUvs {
+    CubeFaceUvs {
+        north: uvs.north.flip_horizontally(),
+        south: uvs.south.flip_horizontally(),
+        east: uvs.west.flip_horizontally(),
+        west: uvs.east.flip_horizontally(),
+        up: uvs.up.flip_horizontally(),
+        down: uvs.down.flip_horizontally(),
+    }
+}
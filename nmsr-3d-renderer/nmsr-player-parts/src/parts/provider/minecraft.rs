@@ -5,9 +5,11 @@ use itertools::Itertools;
 
 use crate::model::{ArmorMaterial, PlayerArmorSlot, PlayerArmorSlots};
 use crate::parts::part::{Part, PartAnchorInfo};
+use crate::parts::provider::elytra::ElytraPlayerPartsProvider;
+use crate::parts::provider::skull::PlayerSkullPlacement;
 use crate::parts::provider::{PartsProvider, PlayerPartProviderContext};
-use crate::types::PlayerBodyPartType;
 use crate::types::PlayerBodyPartType::*;
+use crate::types::{PlayerBodyPartType, PlayerPartTextureType};
 
 pub struct MinecraftPlayerPartsProvider<M>(PhantomData<[M; 4]>);
 
@@ -68,7 +70,11 @@ impl<M: ArmorMaterial> PartsProvider<M> for MinecraftPlayerPartsProvider<M> {
         let part = compute_base_part(non_layer_body_part_type, context.model.is_slim_arms());
 
         if body_part.is_layer() || body_part.is_hat_layer() {
-            let expand_offset = get_layer_expand_offset(non_layer_body_part_type);
+            let expand_offset = if context.skull.is_some() && body_part.is_hat_layer() {
+                PlayerSkullPlacement::HAT_LAYER_EXPAND_OFFSET
+            } else {
+                get_layer_expand_offset(non_layer_body_part_type)
+            };
             let box_uv_offset: (i32, i32) = get_body_part_layer_uv_offset(non_layer_body_part_type);
 
             let parts = vec![expand_player_body_part(
@@ -87,7 +93,9 @@ impl<M: ArmorMaterial> PartsProvider<M> for MinecraftPlayerPartsProvider<M> {
 
         let mut result = vec![part];
 
-        if body_part == Body && context.has_cape {
+        if body_part == Body && context.has_elytra {
+            result.extend(ElytraPlayerPartsProvider::default().get_parts(context, body_part));
+        } else if body_part == Body && context.has_cape {
             append_cape_part(&mut result);
         }
 
@@ -118,6 +126,13 @@ impl<M: ArmorMaterial> PartsProvider<M> for MinecraftPlayerPartsProvider<M> {
                         }
 
                         armor_part.set_texture(texture);
+
+                        if armor_slot.has_glint() {
+                            let mut glint_part = armor_part.clone();
+                            glint_part.set_texture(PlayerPartTextureType::Glint);
+                            result.push(glint_part);
+                        }
+
                         result.push(armor_part);
                     }
                 }
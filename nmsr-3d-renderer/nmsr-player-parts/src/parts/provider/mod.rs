@@ -5,7 +5,10 @@ use crate::model::{ArmorMaterial, PlayerArmorSlots, PlayerModel};
 use crate::parts::provider::minecraft::misc_part_set_origin;
 use crate::types::PlayerBodyPartType;
 use crate::{
-    parts::{part::Part, uv::uv_from_pos_and_size},
+    parts::{
+        part::{Part, PartAnchorInfo},
+        uv::uv_from_pos_and_size,
+    },
     types::PlayerPartTextureType,
 };
 #[cfg(feature = "ears")]
@@ -17,8 +20,15 @@ use strum::IntoEnumIterator;
 #[cfg(feature = "ears")]
 pub mod ears;
 
+pub mod animation;
+pub mod elytra;
+pub mod pose;
+pub mod skull;
+
 #[cfg(all(feature = "ears"))]
 use self::ears::EarsPlayerPartsProvider;
+use self::pose::{PlayerBodyPartRotations, PlayerBodyPose, PlayerPose};
+use self::skull::PlayerSkullPlacement;
 
 pub mod minecraft;
 
@@ -39,11 +49,14 @@ where
     pub has_hat_layer: bool,
     pub has_layers: bool,
     pub has_cape: bool,
+    pub has_elytra: bool,
     pub has_deadmau5_ears: bool,
     pub is_flipped_upside_down: bool,
     pub custom_arm_rotation_z: Option<f32>,
     pub shadow_y_pos: Option<f32>,
     pub shadow_is_square: bool,
+    /// Renders the head as a skull block placed this way, instead of as part of the player.
+    pub skull: Option<PlayerSkullPlacement>,
     pub armor_slots: Option<PlayerArmorSlots<M>>,
     pub movement: PlayerMovementContext,
 
@@ -67,6 +80,25 @@ pub struct PlayerMovementContext {
     pub is_creative_flying: bool,
     pub is_gliding: bool,
     pub is_swimming: bool,
+    /// Whether the player is crawling, which lays them down like swimming does but on the ground.
+    pub is_crawling: bool,
+
+    pub pose: PlayerPose,
+    pub body_part_rotations: PlayerBodyPartRotations,
+    /// A pose sampled from an animation, used instead of [`Self::pose`] when present.
+    pub animation_pose: Option<PlayerBodyPose>,
+}
+
+impl PlayerMovementContext {
+    /// Computes how every body part is posed, taking the custom body part rotations into account.
+    pub fn get_body_pose(&self) -> PlayerBodyPose {
+        let mut pose = self
+            .animation_pose
+            .unwrap_or_else(|| self.pose.get_body_pose(self));
+        self.body_part_rotations.apply_to_pose(&mut pose);
+
+        pose
+    }
 }
 
 impl<M> PlayerPartProviderContext<M>
@@ -79,22 +111,67 @@ where
 
     const MAX_PLAYER_HEIGHT: f32 = 32.0;
 
+    /// The angle (in degrees) the head is tilted back by while gliding, so that the player looks ahead.
+    const GLIDING_HEAD_PITCH: f32 = 45.0;
+
     pub fn get_parts(
         &self,
         providers: &[PlayerPartsProvider],
         body_parts: &[PlayerBodyPartType],
     ) -> Vec<Part> {
+        self.get_parts_by_body_part(providers, body_parts)
+            .into_iter()
+            .map(|(_, part)| part)
+            .collect()
+    }
+
+    /// Like [`Self::get_parts`], but also returns which body part each part belongs to
+    /// (the shadow doesn't belong to any body part).
+    pub fn get_parts_by_body_part(
+        &self,
+        providers: &[PlayerPartsProvider],
+        body_parts: &[PlayerBodyPartType],
+    ) -> Vec<(Option<PlayerBodyPartType>, Part)> {
         let mut parts = providers
             .iter()
             .flat_map(|provider| {
-                body_parts
-                    .iter()
-                    .flat_map(|part| provider.get_parts(self, *part))
+                body_parts.iter().flat_map(|&body_part| {
+                    provider
+                        .get_parts(self, body_part)
+                        .into_iter()
+                        .map(move |part| (Some(body_part), part))
+                })
             })
             .collect::<Vec<_>>();
 
+        let pose = self.movement.get_body_pose();
+
+        for (_, part) in &mut parts {
+            pose.apply_to_model(part, Self::MAX_PLAYER_HEIGHT);
+        }
+
+        if let Some(skull) = self.skull {
+            for (_, part) in &mut parts {
+                skull.apply_to_part(part);
+            }
+        }
+
+        if self.movement.is_gliding {
+            // Lay the player down face first, like the game does while gliding.
+            // We rotate around the middle of the player instead of the feet so that the player stays in frame.
+            let anchor = PartAnchorInfo::new_rotation_anchor_position(Vec3::new(
+                0.0,
+                Self::MAX_PLAYER_HEIGHT / 2.0,
+                0.0,
+            ));
+
+            for (_, part) in &mut parts {
+                part.rotate(Vec3::new(-90.0, 0.0, 0.0), Some(anchor));
+            }
+        }
+
         if self.is_flipped_upside_down {
-            for part in &mut parts {
+            for (_, part) in &mut parts {
                 part.rotate(Vec3::new(0.0, 0.0, 180.0), None);
                 part.translate(Vec3::new(0.0, Self::MAX_PLAYER_HEIGHT, 0.0));
             }
@@ -110,9 +187,9 @@ where
                 #[cfg(feature = "part_tracker")]
                 Some("Shadow".to_string()),
             );
-            // TODO: Expand shadow if there's armor on the feet
+            // The renderer replaces this quad with the shadow cast by the other parts, it only marks where the ground is
 
-            parts.push(shadow);
+            parts.push((None, shadow));
         }
 
         return parts;
@@ -155,7 +232,20 @@ impl<M: ArmorMaterial> PartsProvider<M> for PlayerPartsProvider {
                 .get_parts(context, body_part),
         };
 
+        let pose = context.movement.get_body_pose();
+
         for part in &mut parts {
+            if context.movement.is_gliding
+                && body_part.get_non_layer_part() == PlayerBodyPartType::Head
+            {
+                part.rotate(
+                    Vec3::new(PlayerPartProviderContext::<M>::GLIDING_HEAD_PITCH, 0.0, 0.0),
+                    Some(PartAnchorInfo::new_rotation_anchor_position(Vec3::new(
+                        0.0, 24.0, 0.0,
+                    ))),
+                );
+            }
+
             if body_part.is_arm() {
                 perform_arm_part_rotation(
                     body_part.get_non_layer_part(),
@@ -165,6 +255,8 @@ impl<M: ArmorMaterial> PartsProvider<M> for PlayerPartsProvider {
                 );
             }
 
+            pose.apply_to_part(body_part, part);
+
             #[cfg(feature = "part_tracker")]
             {
                 misc_part_set_origin(body_part.get_non_layer_part(), part);
@@ -0,0 +1,271 @@
+use std::f32::consts::PI;
+
+use glam::Vec3;
+use strum::{Display, EnumIter, EnumString};
+
+use crate::{
+    parts::part::{Part, PartAnchorInfo},
+    types::PlayerBodyPartType,
+};
+
+use super::PlayerMovementContext;
+
+/// A named pose the player can be rendered in.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash, EnumString, EnumIter, Display)]
+#[strum(serialize_all = "snake_case", ascii_case_insensitive)]
+pub enum PlayerPose {
+    #[default]
+    #[strum(to_string = "standing", serialize = "default")]
+    Standing,
+    #[strum(to_string = "walking", serialize = "walk")]
+    Walking,
+    #[strum(to_string = "running", serialize = "run", serialize = "sprinting")]
+    Running,
+    #[strum(to_string = "sneaking", serialize = "sneak", serialize = "crouching")]
+    Sneaking,
+    #[strum(to_string = "sitting", serialize = "sit")]
+    Sitting,
+    #[strum(to_string = "waving", serialize = "wave")]
+    Waving,
+    #[strum(to_string = "swimming", serialize = "swim")]
+    Swimming,
+    #[strum(to_string = "crawling", serialize = "crawl")]
+    Crawling,
+    #[strum(to_string = "t_pose", serialize = "tpose")]
+    TPose,
+}
+
+impl PlayerPose {
+    /// Whether the player is lying down in this pose.
+    pub const fn is_horizontal(self) -> bool {
+        matches!(self, Self::Swimming | Self::Crawling)
+    }
+
+    /// Whether this pose places the arms itself, instead of leaving them hanging by the sides.
+    pub const fn has_arm_pose(self) -> bool {
+        !matches!(self, Self::Standing)
+    }
+
+    /// Computes how every body part is posed, mirroring what the game does in `HumanoidModel#setupAnim`.
+    pub fn get_body_pose(self, movement: &PlayerMovementContext) -> PlayerBodyPose {
+        let mut pose = PlayerBodyPose::default();
+
+        match self {
+            Self::Standing => {}
+            Self::Walking | Self::Running => {
+                // How far the limbs swing, toned down from what the game uses (up to 1.0) so that the
+                // legs don't end up nearly horizontal in a still image
+                let amount = if self == Self::Running { 0.75 } else { 0.4 };
+                // While gliding, the game divides the swing by the cube of the player's speed, which keeps
+                // the limbs straight along the body
+                let amount = if movement.is_gliding { 0.0 } else { amount };
+                let phase = movement.limb_swing * 0.6662;
+
+                pose.right_arm.rotation.x = ((phase + PI).cos() * amount).to_degrees();
+                pose.left_arm.rotation.x = (phase.cos() * amount).to_degrees();
+                pose.right_leg.rotation.x = (phase.cos() * 1.4 * amount).to_degrees();
+                pose.left_leg.rotation.x = ((phase + PI).cos() * 1.4 * amount).to_degrees();
+            }
+            Self::Sneaking => {
+                pose.body.rotation.x = 0.5f32.to_degrees();
+                pose.body.offset.y = 3.2;
+
+                pose.head.offset.y = 4.2;
+
+                for arm in [&mut pose.left_arm, &mut pose.right_arm] {
+                    arm.rotation.x = 0.4f32.to_degrees();
+                    arm.offset.y = 3.2;
+                }
+
+                for leg in [&mut pose.left_leg, &mut pose.right_leg] {
+                    leg.offset = Vec3::new(0.0, 0.2, 4.0);
+                }
+            }
+            Self::Sitting => {
+                pose.right_arm.rotation.x = (-PI / 5.0).to_degrees();
+                pose.left_arm.rotation.x = (-PI / 5.0).to_degrees();
+
+                pose.right_leg.rotation =
+                    Vec3::new(-1.4137167, PI / 10.0, 0.07853982) * (180.0 / PI);
+                pose.left_leg.rotation =
+                    Vec3::new(-1.4137167, -PI / 10.0, -0.07853982) * (180.0 / PI);
+            }
+            Self::Waving => {
+                pose.head.rotation = Vec3::new(-5.0, -10.0, 0.0);
+                pose.right_arm.rotation = Vec3::new(-10.0, 0.0, 145.0);
+            }
+            Self::Swimming | Self::Crawling => {
+                // Look ahead instead of at the ground
+                pose.head.rotation.x = -45.0;
+
+                let kick = (movement.limb_swing * 0.33333334).cos() * 0.3;
+
+                if self == Self::Swimming {
+                    pose.right_arm.rotation = Vec3::new(180.0, 0.0, 10.0);
+                    pose.left_arm.rotation = Vec3::new(180.0, 0.0, -10.0);
+                } else {
+                    // Reach forward to the ground
+                    pose.right_arm.rotation = Vec3::new(-160.0, 0.0, 15.0);
+                    pose.left_arm.rotation = Vec3::new(-160.0, 0.0, -15.0);
+
+                    // Lie on the ground instead of floating in the middle of the frame
+                    pose.model_offset.y = -10.5;
+                }
+
+                pose.right_leg.rotation.x = kick.to_degrees();
+                pose.left_leg.rotation.x = (-kick).to_degrees();
+
+                pose.model_rotation.x = -90.0;
+            }
+            Self::TPose => {
+                pose.right_arm.rotation.z = 90.0;
+                pose.left_arm.rotation.z = -90.0;
+            }
+        }
+
+        pose
+    }
+}
+
+/// Rotations (in degrees, using Minecraft's model axes) that replace the rotation of a body part given by the pose.
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+pub struct PlayerBodyPartRotations {
+    pub head: Option<Vec3>,
+    pub body: Option<Vec3>,
+    pub left_arm: Option<Vec3>,
+    pub right_arm: Option<Vec3>,
+    pub left_leg: Option<Vec3>,
+    pub right_leg: Option<Vec3>,
+}
+
+impl PlayerBodyPartRotations {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    pub const fn has_arm_rotations(&self) -> bool {
+        self.left_arm.is_some() || self.right_arm.is_some()
+    }
+
+    pub(crate) fn apply_to_pose(&self, pose: &mut PlayerBodyPose) {
+        let rotations = [
+            (self.head, &mut pose.head),
+            (self.body, &mut pose.body),
+            (self.left_arm, &mut pose.left_arm),
+            (self.right_arm, &mut pose.right_arm),
+            (self.left_leg, &mut pose.left_leg),
+            (self.right_leg, &mut pose.right_leg),
+        ];
+
+        for (rotation, part_pose) in rotations {
+            if let Some(rotation) = rotation {
+                part_pose.rotation = rotation;
+            }
+        }
+    }
+}
+
+/// How a single body part is posed, relative to its pivot.
+///
+/// Both the rotation (in degrees) and the offset are expressed using Minecraft's model axes (where Y points down),
+/// so that they match what the game does.
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+pub struct PlayerBodyPartPose {
+    pub rotation: Vec3,
+    pub offset: Vec3,
+}
+
+impl PlayerBodyPartPose {
+    pub fn is_identity(&self) -> bool {
+        self.rotation == Vec3::ZERO && self.offset == Vec3::ZERO
+    }
+}
+
+/// How every body part of the player is posed.
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+pub struct PlayerBodyPose {
+    pub head: PlayerBodyPartPose,
+    pub body: PlayerBodyPartPose,
+    pub left_arm: PlayerBodyPartPose,
+    pub right_arm: PlayerBodyPartPose,
+    pub left_leg: PlayerBodyPartPose,
+    pub right_leg: PlayerBodyPartPose,
+
+    /// The rotation (in degrees) of the whole player, around the middle of the player.
+    pub model_rotation: Vec3,
+    /// The offset of the whole player, applied after [`Self::model_rotation`].
+    pub model_offset: Vec3,
+}
+
+impl PlayerBodyPose {
+    pub fn get_part_pose(&self, body_part: PlayerBodyPartType) -> &PlayerBodyPartPose {
+        match body_part.get_non_layer_part() {
+            PlayerBodyPartType::Head => &self.head,
+            PlayerBodyPartType::Body => &self.body,
+            PlayerBodyPartType::LeftArm => &self.left_arm,
+            PlayerBodyPartType::RightArm => &self.right_arm,
+            PlayerBodyPartType::LeftLeg => &self.left_leg,
+            _ => &self.right_leg,
+        }
+    }
+
+    pub fn get_part_pose_mut(&mut self, body_part: PlayerBodyPartType) -> &mut PlayerBodyPartPose {
+        match body_part.get_non_layer_part() {
+            PlayerBodyPartType::Head => &mut self.head,
+            PlayerBodyPartType::Body => &mut self.body,
+            PlayerBodyPartType::LeftArm => &mut self.left_arm,
+            PlayerBodyPartType::RightArm => &mut self.right_arm,
+            PlayerBodyPartType::LeftLeg => &mut self.left_leg,
+            _ => &mut self.right_leg,
+        }
+    }
+
+    /// Poses a part belonging to the given body part around that body part's pivot.
+    pub fn apply_to_part(&self, body_part: PlayerBodyPartType, part: &mut Part) {
+        let non_layer_body_part = body_part.get_non_layer_part();
+        let part_pose = self.get_part_pose(non_layer_body_part);
+
+        if part_pose.is_identity() {
+            return;
+        }
+
+        let anchor = Some(PartAnchorInfo::new_rotation_anchor_position(get_pivot(
+            non_layer_body_part,
+        )));
+
+        // Minecraft applies the X rotation first, then Y, then Z, and its X and Y axes are flipped compared to ours.
+        let PlayerBodyPartPose { rotation, offset } = *part_pose;
+
+        part.rotate(Vec3::new(-rotation.x, 0.0, 0.0), anchor);
+        part.rotate(Vec3::new(0.0, -rotation.y, 0.0), anchor);
+        part.rotate(Vec3::new(0.0, 0.0, rotation.z), anchor);
+
+        part.translate(Vec3::new(-offset.x, -offset.y, offset.z));
+    }
+
+    /// Moves a part along with the whole player.
+    pub fn apply_to_model(&self, part: &mut Part, player_height: f32) {
+        if self.model_rotation != Vec3::ZERO {
+            let anchor = PartAnchorInfo::new_rotation_anchor_position(Vec3::new(
+                0.0,
+                player_height / 2.0,
+                0.0,
+            ));
+
+            part.rotate(self.model_rotation, Some(anchor));
+        }
+
+        part.translate(self.model_offset);
+    }
+}
+
+/// Returns the point a body part rotates around, the same as in the game.
+fn get_pivot(non_layer_body_part: PlayerBodyPartType) -> Vec3 {
+    match non_layer_body_part {
+        PlayerBodyPartType::Head | PlayerBodyPartType::Body => Vec3::new(0.0, 24.0, 0.0),
+        PlayerBodyPartType::LeftArm => Vec3::new(-5.0, 22.0, 0.0),
+        PlayerBodyPartType::RightArm => Vec3::new(5.0, 22.0, 0.0),
+        PlayerBodyPartType::LeftLeg => Vec3::new(-1.9, 12.0, 0.0),
+        _ => Vec3::new(1.9, 12.0, 0.0),
+    }
+}
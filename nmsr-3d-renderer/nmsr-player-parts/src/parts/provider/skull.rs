@@ -0,0 +1,54 @@
+use glam::Vec3;
+
+use crate::parts::part::{Part, PartAnchorInfo};
+
+/// How the player's head is placed when it's rendered as a skull block.
+///
+/// The head keeps its usual position, so the block it's placed in spans from y = 24 to y = 40.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum PlayerSkullPlacement {
+    /// Standing on the floor, rotated in sixteenths of a full turn like the game does (from 0 to 15).
+    Floor { rotation: u8 },
+    /// Mounted on the wall behind the head.
+    Wall,
+}
+
+impl Default for PlayerSkullPlacement {
+    fn default() -> Self {
+        Self::Floor { rotation: 0 }
+    }
+}
+
+impl PlayerSkullPlacement {
+    /// The skull's hat layer hugs the head closer than the player's hat layer does.
+    pub const HAT_LAYER_EXPAND_OFFSET: f32 = 0.25;
+
+    /// The middle of the head, when standing on the floor.
+    const HEAD_CENTER: Vec3 = Vec3::new(0.0, 28.0, 0.0);
+
+    /// Where a wall-mounted head is moved to, up and against the wall.
+    const WALL_OFFSET: Vec3 = Vec3::new(0.0, 4.0, 4.0);
+
+    /// Returns the middle of the placed head, which is what cameras should look at.
+    pub fn get_head_center(self) -> Vec3 {
+        match self {
+            Self::Floor { .. } => Self::HEAD_CENTER,
+            Self::Wall => Self::HEAD_CENTER + Self::WALL_OFFSET,
+        }
+    }
+
+    pub fn apply_to_part(self, part: &mut Part) {
+        match self {
+            Self::Floor { rotation: 0 } => {}
+            Self::Floor { rotation } => {
+                let anchor = PartAnchorInfo::new_rotation_anchor_position(Self::HEAD_CENTER);
+
+                part.rotate(
+                    Vec3::new(0.0, -f32::from(rotation % 16) * 22.5, 0.0),
+                    Some(anchor),
+                );
+            }
+            Self::Wall => part.translate(Self::WALL_OFFSET),
+        }
+    }
+}
@@ -1,6 +1,7 @@
 use strum::{EnumIter, IntoStaticStr};
 
-#[derive(Debug, Copy, Clone, EnumIter, Eq, PartialEq, Hash, PartialOrd, Ord)]
+#[derive(Debug, Copy, Clone, EnumIter, IntoStaticStr, Eq, PartialEq, Hash, PartialOrd, Ord)]
+#[strum(serialize_all = "snake_case")]
 pub enum PlayerBodyPartType {
     // Normal body parts
     Head,
@@ -73,12 +74,20 @@ impl PlayerBodyPartType {
 pub enum PlayerPartTextureType {
     Shadow,
     Cape,
+    /// The elytra texture, which shares the layout of the cape texture.
+    Elytra,
     Skin,
     Custom {
         key: &'static str,
         size: (u32, u32),
         is_emissive: bool,
     },
+    /// The enchantment glint overlay drawn on top of enchanted armor.
+    /// Shares the UV space of the armor textures, the glint itself is scrolled by the renderer.
+    Glint,
+    /// The name plate drawn above the player, which isn't affected by the lighting.
+    /// The plate is drawn at the top left corner of the texture, and only covers as much of it as it needs.
+    Nametag,
 }
 
 impl std::fmt::Display for PlayerPartTextureType {
@@ -86,8 +95,11 @@ impl std::fmt::Display for PlayerPartTextureType {
         match *self {
             PlayerPartTextureType::Shadow => f.pad("Shadow"),
             PlayerPartTextureType::Cape => f.pad("Cape"),
+            PlayerPartTextureType::Elytra => f.pad("Elytra"),
             PlayerPartTextureType::Skin => f.pad("Skin"),
             PlayerPartTextureType::Custom { key, .. } => f.pad(key),
+            PlayerPartTextureType::Glint => f.pad("Glint"),
+            PlayerPartTextureType::Nametag => f.pad("Nametag"),
         }
     }
 }
@@ -103,13 +115,23 @@ impl PlayerPartTextureType {
     pub fn get_texture_size(&self) -> (u32, u32) {
         match self {
             Self::Skin => (64, 64),
-            Self::Cape => (64, 32),
+            Self::Cape | Self::Elytra => (64, 32),
             Self::Custom { size, .. } => *size,
             Self::Shadow => (128, 128),
+            Self::Glint => (64, 64),
+            Self::Nametag => (512, 16),
         }
     }
 
     pub fn is_shadow(&self) -> bool {
         matches!(self, Self::Shadow)
     }
+
+    pub fn is_glint(&self) -> bool {
+        matches!(self, Self::Glint)
+    }
+
+    pub fn is_nametag(&self) -> bool {
+        matches!(self, Self::Nametag)
+    }
 }
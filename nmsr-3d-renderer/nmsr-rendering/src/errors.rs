@@ -20,6 +20,9 @@ pub enum NMSRRenderingError {
     #[cfg(feature = "pipeline")]
     #[error("SceneContext textures not initialized")]
     SceneContextTexturesNotInitialized,
+    #[cfg(feature = "pipeline")]
+    #[error("Tiles of {0} pixels are too small to fit their margin of {1} pixels")]
+    TileTooSmall(u32, u32),
     #[error("SceneContext texture not set: {0}")]
     SceneContextTextureNotSet(PlayerPartTextureType),
     #[cfg(feature = "pipeline")]
@@ -1,4 +1,4 @@
-use glam::{Mat4, Vec3};
+use glam::{Mat4, Vec2, Vec3};
 use std::mem;
 
 use crate::high_level::utils::{
@@ -197,6 +197,34 @@ impl CameraPositionParameters {
     }
 }
 
+/// A part of the frame of a camera, in pixels from its top left corner.
+///
+/// Tiles can go past the edges of the frame, in which case they show what's just outside of it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CameraTile {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl CameraTile {
+    /// Returns the matrix that turns normalized device coordinates of the whole frame into those of this tile.
+    fn get_crop_matrix(&self, frame: Size) -> Mat4 {
+        let frame = Vec2::new(frame.width as f32, frame.height as f32);
+        let size = Vec2::new(self.width as f32, self.height as f32);
+        let center = Vec2::new(self.x as f32, self.y as f32) + size / 2.0;
+
+        // Pixels go down while normalized device coordinates go up
+        let center = Vec2::new(
+            center.x / frame.x * 2.0 - 1.0,
+            1.0 - center.y / frame.y * 2.0,
+        );
+
+        Mat4::from_scale((frame / size).extend(1.0)) * Mat4::from_translation((-center).extend(0.0))
+    }
+}
+
 /// The camera used to view the scene
 #[derive(Clone, Copy, Debug)]
 pub struct Camera {
@@ -207,6 +235,8 @@ pub struct Camera {
     /// The aspect ratio of the camera
     size: Option<Size>,
     projection: ProjectionParameters,
+    /// The part of the frame to render, if not all of it
+    tile: Option<CameraTile>,
 
     dirty: bool,
     cached_view_projection_matrix: Mat4,
@@ -224,6 +254,7 @@ impl Camera {
             rotation,
             size,
             projection,
+            tile: None,
             dirty: true,
             cached_view_projection_matrix: Mat4::ZERO,
         }
@@ -241,6 +272,7 @@ impl Camera {
             rotation,
             size,
             projection,
+            tile: None,
             dirty: true,
             cached_view_projection_matrix: Mat4::ZERO,
         }
@@ -256,9 +288,20 @@ impl Camera {
         position_parameters: CameraPositionParameters,
         rotation: CameraRotation,
         size: Option<Size>,
-        projection: ProjectionParameters
+        projection: ProjectionParameters,
+        tile: Option<CameraTile>
     );
 
+    /// Returns the size of what the camera renders, which is the size of its tile if it has one.
+    pub fn get_render_size(&self) -> Option<Size> {
+        self.tile
+            .map(|tile| Size {
+                width: tile.width,
+                height: tile.height,
+            })
+            .or(self.size)
+    }
+
     camera_inner_getters_setters!(rotation, yaw, pitch, roll);
     camera_inner_getters_setters_opt!(projection, fov, aspect);
     camera_inner_getters_setters_opt!(position_parameters, position: Vec3, Vec3::ZERO);
@@ -268,6 +311,109 @@ impl Camera {
     camera_inner_getters_setters!(get_position(), position, x, y, z);
     camera_inner_getters_setters!(get_look_at(), look_at, x, y, z);
 
+    /// Moves an orbital camera so that every given point is in view, centered in the frame.
+    ///
+    /// The camera keeps its rotation, only its look at point and its distance (or its size, for orthographic cameras) change.
+    /// The padding is the fraction of the frame left empty on each side, from 0 to 0.5.
+    pub fn fit_to_points(&mut self, points: impl IntoIterator<Item = Vec3>, padding: f32) {
+        let CameraPositionParameters::Orbital { look_at, .. } = self.position_parameters else {
+            return;
+        };
+
+        // View the points from one pixel away, only their depth depends on the distance
+        let forward = look_from_yaw_pitch(self.rotation.yaw, self.rotation.pitch);
+        let view = Mat4::from_rotation_z(-self.rotation.roll.to_radians())
+            * Mat4::look_at_rh(look_at - forward, look_at, Vec3::Y);
+
+        let points = points
+            .into_iter()
+            .map(|point| view.transform_point3(point))
+            .collect::<Vec<_>>();
+
+        let Some((min, max)) = points
+            .iter()
+            .map(|point| (point.truncate(), point.truncate()))
+            .reduce(|(min, max), (other_min, other_max)| (min.min(other_min), max.max(other_max)))
+        else {
+            return;
+        };
+
+        // Aim at the middle of what we see
+        let center = (min + max) / 2.0;
+        self.set_look_at(look_at + view.inverse().transform_vector3(center.extend(0.0)));
+
+        let aspect_ratio = self.get_aspect_ratio();
+        let fill = (1.0 - padding.clamp(0.0, 0.49) * 2.0).max(f32::EPSILON);
+
+        match self.projection {
+            ProjectionParameters::Orthographic { .. } => {
+                let half_size = (max - center).max(center - min);
+                let half_height = half_size.y.max(half_size.x / aspect_ratio) / fill;
+
+                self.set_aspect(half_height.max(f32::EPSILON));
+            }
+            ProjectionParameters::Perspective { fov } => {
+                let tan_half_height = (fov.to_radians() / 2.0).tan() * fill;
+                let tan_half_width = tan_half_height * aspect_ratio;
+
+                // Each point needs the camera to be far enough for it to fit in both directions,
+                // and points closer to the camera than the look at point need it to be even farther
+                let distance = points
+                    .iter()
+                    .map(|point| {
+                        let offset = point.truncate() - center;
+                        let depth_behind_look_at = -point.z - 1.0;
+
+                        // Also keep the point a pixel away from the camera, past the near plane
+                        (offset.x.abs() / tan_half_width)
+                            .max(offset.y.abs() / tan_half_height)
+                            .max(1.0)
+                            - depth_behind_look_at
+                    })
+                    .fold(0.0f32, f32::max);
+
+                self.set_distance(distance);
+            }
+        }
+    }
+
+    /// Makes an orthographic orbital camera show every world unit (a skin texel) as exactly the given amount of pixels.
+    ///
+    /// The camera is also moved by less than a unit so that the origin lands on the corner of a block of that many pixels,
+    /// which puts the edges of every texel facing the camera on the pixel grid.
+    pub fn snap_to_pixel_scale(&mut self, pixels_per_unit: f32) {
+        let (ProjectionParameters::Orthographic { .. }, Some(size)) = (self.projection, self.size)
+        else {
+            return;
+        };
+
+        let CameraPositionParameters::Orbital { look_at, .. } = self.position_parameters else {
+            return;
+        };
+
+        // The frame is 2 * aspect units tall
+        self.set_aspect(size.height as f32 / (2.0 * pixels_per_unit));
+
+        let size = Vec2::new(size.width as f32, size.height as f32);
+        let origin = self.get_view_projection_matrix().project_point3(Vec3::ZERO);
+        let origin = Vec2::new(origin.x + 1.0, 1.0 - origin.y) * 0.5 * size;
+
+        let snapped = (origin / pixels_per_unit).round() * pixels_per_unit;
+        let offset = (snapped - origin) / pixels_per_unit;
+
+        // Moving the camera one way moves the scene the other way (and pixels go down while the view goes up)
+        let forward = look_from_yaw_pitch(self.rotation.yaw, self.rotation.pitch);
+        let view = Mat4::from_rotation_z(-self.rotation.roll.to_radians())
+            * Mat4::look_at_rh(-forward, Vec3::ZERO, Vec3::Y);
+
+        self.set_look_at(
+            look_at
+                + view
+                    .inverse()
+                    .transform_vector3(Vec3::new(-offset.x, offset.y, 0.0)),
+        );
+    }
+
     pub fn get_view_projection_matrix(&mut self) -> Mat4 {
         if self.dirty {
             self.cached_view_projection_matrix = self.compute_view_projection_matrix()
@@ -306,6 +452,161 @@ impl Camera {
             }
         };
 
+        let projection = match (self.tile, self.size) {
+            (Some(tile), Some(size)) => tile.get_crop_matrix(size) * projection,
+            _ => projection,
+        };
+
         projection * view_position
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const POINTS: [Vec3; 4] = [
+        Vec3::new(-8.0, 0.0, -2.0),
+        Vec3::new(8.0, 0.0, 2.0),
+        Vec3::new(-4.0, 40.0, -2.0),
+        Vec3::new(4.0, 40.0, 2.0),
+    ];
+
+    fn assert_fits(mut camera: Camera, padding: f32) {
+        camera.fit_to_points(POINTS, padding);
+
+        let view_projection = camera.get_view_projection_matrix();
+        let limit = 1.0 - padding * 2.0 + 1e-3;
+
+        for point in POINTS {
+            let projected = view_projection.project_point3(point);
+
+            assert!(
+                projected.x.abs() <= limit,
+                "{point} is out of view: {projected}"
+            );
+            assert!(
+                projected.y.abs() <= limit,
+                "{point} is out of view: {projected}"
+            );
+        }
+    }
+
+    fn rotation() -> CameraRotation {
+        CameraRotation {
+            yaw: 20.0,
+            pitch: 10.0,
+            roll: 0.0,
+        }
+    }
+
+    #[test]
+    fn fit_orthographic_camera() {
+        let camera = Camera::new_orbital(
+            Vec3::ZERO,
+            10.0,
+            rotation(),
+            ProjectionParameters::Orthographic { aspect: 1.0 },
+            Some(Size {
+                width: 300,
+                height: 200,
+            }),
+        );
+
+        assert_fits(camera, 0.1);
+    }
+
+    #[test]
+    fn snap_orthographic_camera_to_pixel_scale() {
+        let mut camera = Camera::new_orbital(
+            Vec3::new(0.3, 17.2, 0.0),
+            10.0,
+            CameraRotation {
+                yaw: 0.0,
+                pitch: 0.0,
+                roll: 0.0,
+            },
+            ProjectionParameters::Orthographic { aspect: 1.0 },
+            Some(Size {
+                width: 101,
+                height: 150,
+            }),
+        );
+
+        camera.snap_to_pixel_scale(4.0);
+
+        let view_projection = camera.get_view_projection_matrix();
+        let to_pixels = |point: Vec3| {
+            let projected = view_projection.project_point3(point);
+            Vec2::new(projected.x + 1.0, 1.0 - projected.y) * 0.5 * Vec2::new(101.0, 150.0)
+        };
+
+        for point in POINTS {
+            let pixels = to_pixels(point);
+
+            assert!(
+                (pixels / 4.0 - (pixels / 4.0).round()).abs().max_element() < 1e-3,
+                "{point} is off the pixel grid: {pixels}"
+            );
+        }
+
+        assert!(((to_pixels(Vec3::X) - to_pixels(Vec3::ZERO)).length() - 4.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn tiles_split_the_frame() {
+        let size = Size {
+            width: 300,
+            height: 200,
+        };
+
+        let mut camera = Camera::new_orbital(
+            Vec3::ZERO,
+            50.0,
+            rotation(),
+            ProjectionParameters::Perspective { fov: 45.0 },
+            Some(size),
+        );
+
+        let point = Vec3::new(3.0, 5.0, -2.0);
+        let frame = camera.get_view_projection_matrix().project_point3(point);
+        let pixel = Vec2::new(frame.x + 1.0, 1.0 - frame.y) * 0.5 * Vec2::new(300.0, 200.0);
+
+        let tile = CameraTile {
+            x: 100,
+            y: 50,
+            width: 120,
+            height: 80,
+        };
+        camera.set_tile(Some(tile));
+
+        let projected = camera.get_view_projection_matrix().project_point3(point);
+        let tile_pixel =
+            Vec2::new(projected.x + 1.0, 1.0 - projected.y) * 0.5 * Vec2::new(120.0, 80.0);
+
+        assert!((tile_pixel + Vec2::new(100.0, 50.0) - pixel).length() < 1e-2);
+        assert_eq!(
+            camera.get_render_size(),
+            Some(Size {
+                width: 120,
+                height: 80
+            })
+        );
+    }
+
+    #[test]
+    fn fit_perspective_camera() {
+        let camera = Camera::new_orbital(
+            Vec3::ZERO,
+            1.0,
+            rotation(),
+            ProjectionParameters::Perspective { fov: 45.0 },
+            Some(Size {
+                width: 200,
+                height: 300,
+            }),
+        );
+
+        assert_fits(camera, 0.05);
+    }
+}
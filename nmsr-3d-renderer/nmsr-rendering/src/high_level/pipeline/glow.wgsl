@@ -0,0 +1,50 @@
+struct GlowInformation {
+    // How far apart (in pixels) the samples of the blur are, and in which direction the blur goes
+    step: vec2<f32>,
+    strength: f32,
+}
+
+@group(0)
+@binding(0)
+var source: texture_2d<f32>;
+
+@group(0)
+@binding(1)
+var<uniform> glow: GlowInformation;
+
+// The weights of a gaussian blur (sigma = 2.5), from the center outwards
+const WEIGHTS: array<f32, 7> = array<f32, 7>(0.1610, 0.1486, 0.1169, 0.0784, 0.0448, 0.0217, 0.0090);
+
+@vertex
+fn vs_main(@builtin(vertex_index) index: u32) -> @builtin(position) vec4<f32> {
+    // A single triangle that covers the whole screen
+    var uv: vec2<f32> = vec2<f32>(f32((index << 1u) & 2u), f32(index & 2u));
+
+    return vec4<f32>(uv * 2.0 - 1.0, 0.0, 1.0);
+}
+
+fn load_clamped(coord: vec2<f32>, size: vec2<i32>) -> vec4<f32> {
+    return textureLoad(source, clamp(vec2<i32>(round(coord)), vec2<i32>(0), size - 1), 0);
+}
+
+// Blurs the glow along one direction, the glow is blurred horizontally first and then vertically onto the output
+@fragment
+fn fs_main(@builtin(position) position: vec4<f32>) -> @location(0) vec4<f32> {
+    var size: vec2<i32> = vec2<i32>(textureDimensions(source));
+    var coord: vec2<f32> = floor(position.xy);
+    var weights: array<f32, 7> = WEIGHTS;
+
+    var color: vec4<f32> = load_clamped(coord, size) * weights[0];
+
+    for (var i: i32 = 1; i < 7; i++) {
+        var offset: vec2<f32> = glow.step * f32(i);
+
+        color += load_clamped(coord + offset, size) * weights[i];
+        color += load_clamped(coord - offset, size) * weights[i];
+    }
+
+    // The glow is premultiplied, so keep it that way when making it stronger
+    var strengthened: vec4<f32> = color * glow.strength;
+
+    return vec4<f32>(strengthened.rgb, min(strengthened.a, 1.0));
+}
@@ -1,14 +1,20 @@
-use std::{borrow::Cow, env, mem, sync::Arc};
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    env, mem,
+    sync::{Arc, Mutex, PoisonError},
+};
 
 use deadpool::managed::{Object, Pool};
 use smaa::SmaaMode;
 use wgpu::{
     vertex_attr_array, BindGroupLayout, BindGroupLayoutDescriptor, BindGroupLayoutEntry,
-    BindingType, BufferAddress, BufferBindingType, BufferSize, ColorTargetState, ColorWrites,
-    CompareFunction, DepthStencilState, ExperimentalFeatures, FragmentState, FrontFace,
-    MultisampleState, PipelineLayoutDescriptor, PresentMode, PrimitiveState, RenderPipeline,
-    RenderPipelineDescriptor, SamplerBindingType, ShaderModuleDescriptor, ShaderStages,
-    TextureSampleType, TextureViewDimension, VertexBufferLayout, VertexState,
+    BindingType, BlendComponent, BlendFactor, BlendOperation, BufferAddress, BufferBindingType,
+    BufferSize, ColorTargetState, ColorWrites, CompareFunction, DepthStencilState,
+    ExperimentalFeatures, FragmentState, FrontFace, MultisampleState, PipelineLayoutDescriptor,
+    PresentMode, PrimitiveState, RenderPipeline, RenderPipelineDescriptor, SamplerBindingType,
+    ShaderModuleDescriptor, ShaderStages, TextureSampleType, TextureViewDimension,
+    VertexBufferLayout, VertexState,
 };
 pub use wgpu::{
     Adapter, Backends, BlendState, Device, Features, Instance, Limits, Queue, ShaderSource,
@@ -22,7 +28,10 @@ use crate::{
 
 use super::{
     pools::SceneContextPoolManager,
-    scene::{Size, SunInformation},
+    scene::{
+        AuxInformation, GlintInformation, GlowInformation, OutlineInformation, SceneAntiAliasing,
+        Size, SunInformation,
+    },
 };
 
 #[derive(Debug)]
@@ -35,12 +44,18 @@ pub struct GraphicsContext<'a> {
     pub texture_format: TextureFormat,
     pub adapter: Adapter,
 
-    pub pipeline: RenderPipeline,
+    pub pipelines: GraphicsContextSampledPipelines,
+    /// The same pipelines without multisampling, for scenes that turn it off (see [`SceneAntiAliasing`]).
+    /// This is only present when the pipelines above are multisampled.
+    pub single_sample_pipelines: Option<GraphicsContextSampledPipelines>,
+    pub aux_pipeline: RenderPipeline,
+    pub glow_blur_pipeline: RenderPipeline,
+    pub glow_composite_pipeline: RenderPipeline,
     pub layouts: GraphicsContextLayouts,
     pub multisampling_strategy: MultiSamplingStrategy,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Copy, Clone)]
 pub enum MultiSamplingStrategy {
     MSAA(u32),
     SMAA(SmaaMode),
@@ -63,34 +78,109 @@ impl MultiSamplingStrategy {
     }
 }
 
+/// The pipelines that draw into multisampled textures, which have to match their sample count.
+#[derive(Debug)]
+pub struct GraphicsContextSampledPipelines {
+    pub sample_count: u32,
+    pub pipeline: RenderPipeline,
+    pub opaque_pipeline: RenderPipeline,
+    pub translucent_pipeline: RenderPipeline,
+    pub glint_pipeline: RenderPipeline,
+    pub glow_extract_pipeline: RenderPipeline,
+    pub outline_pipeline: RenderPipeline,
+    pub outline_bind_group_layout: BindGroupLayout,
+}
+
 #[derive(Debug)]
 pub struct GraphicsContextLayouts {
     pub transform_bind_group_layout: BindGroupLayout,
     pub skin_sampler_bind_group_layout: BindGroupLayout,
     pub pipeline_layout: wgpu::PipelineLayout,
     pub sun_bind_group_layout: BindGroupLayout,
+    pub glint_bind_group_layout: BindGroupLayout,
+    pub glint_pipeline_layout: wgpu::PipelineLayout,
+    pub aux_bind_group_layout: BindGroupLayout,
+    pub aux_pipeline_layout: wgpu::PipelineLayout,
+    pub glow_bind_group_layout: BindGroupLayout,
+    pub glow_pipeline_layout: wgpu::PipelineLayout,
 }
 
 #[derive(Debug)]
 pub struct GraphicsContextPools<'a> {
+    context: Arc<GraphicsContext<'a>>,
     scene_context_pool: Pool<SceneContextPoolManager<'a>>,
+    /// Scene contexts with their own anti-aliasing, kept apart since their textures don't fit other scenes.
+    ///
+    /// These are only created once a scene with that anti-aliasing is first requested.
+    anti_aliased_scene_context_pools:
+        Mutex<HashMap<SceneAntiAliasing, Pool<SceneContextPoolManager<'a>>>>,
 }
 
 impl<'a> GraphicsContextPools<'a> {
     pub fn new(context: Arc<GraphicsContext<'a>>) -> Result<Self> {
-        let scene_context_pool = Pool::builder(SceneContextPoolManager::new(context)).build()?;
+        let scene_context_pool =
+            Pool::builder(SceneContextPoolManager::new(context.clone(), None)).build()?;
+
+        Ok(Self {
+            context,
+            scene_context_pool,
+            anti_aliased_scene_context_pools: Mutex::new(HashMap::new()),
+        })
+    }
+
+    fn get_anti_aliased_pool(
+        &self,
+        anti_aliasing: SceneAntiAliasing,
+    ) -> Result<Pool<SceneContextPoolManager<'a>>> {
+        let mut pools = self
+            .anti_aliased_scene_context_pools
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+
+        if let Some(pool) = pools.get(&anti_aliasing) {
+            return Ok(pool.clone());
+        }
 
-        Ok(Self { scene_context_pool })
+        let manager = SceneContextPoolManager::new(self.context.clone(), Some(anti_aliasing));
+        let pool = Pool::builder(manager).build()?;
+
+        pools.insert(anti_aliasing, pool.clone());
+
+        Ok(pool)
     }
 
     pub async fn create_scene_context(&self) -> Result<Object<SceneContextPoolManager<'a>>> {
-        Ok(self.scene_context_pool.get().await?)
+        self.create_scene_context_with_anti_aliasing(None).await
+    }
+
+    /// Gets a scene context that renders with the given anti-aliasing, or the one the graphics context was created with.
+    pub async fn create_scene_context_with_anti_aliasing(
+        &self,
+        anti_aliasing: Option<SceneAntiAliasing>,
+    ) -> Result<Object<SceneContextPoolManager<'a>>> {
+        let pool = match anti_aliasing {
+            Some(anti_aliasing) => self.get_anti_aliased_pool(anti_aliasing)?,
+            None => self.scene_context_pool.clone(),
+        };
+
+        Ok(pool.get().await?)
     }
 }
 
 impl<'a> GraphicsContext<'a> {
     pub fn get_pipeline(&self) -> &RenderPipeline {
-        &self.pipeline
+        &self.pipelines.pipeline
+    }
+
+    /// Returns the pipelines that draw into textures with the given sample count.
+    pub(crate) fn get_sampled_pipelines(
+        &self,
+        sample_count: u32,
+    ) -> &GraphicsContextSampledPipelines {
+        self.single_sample_pipelines
+            .as_ref()
+            .filter(|_| sample_count == 1)
+            .unwrap_or(&self.pipelines)
     }
 }
 
@@ -295,6 +385,34 @@ impl<'a> GraphicsContext<'a> {
             }],
         });
 
+        let glint_bind_group_layout = device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+            label: Some("Glint Bind Group"),
+            entries: &[BindGroupLayoutEntry {
+                binding: 0,
+                visibility: ShaderStages::FRAGMENT,
+                ty: BindingType::Buffer {
+                    ty: BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: BufferSize::new(mem::size_of::<GlintInformation>() as u64),
+                },
+                count: None,
+            }],
+        });
+
+        let aux_bind_group_layout = device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+            label: Some("Aux Bind Group"),
+            entries: &[BindGroupLayoutEntry {
+                binding: 0,
+                visibility: ShaderStages::FRAGMENT,
+                ty: BindingType::Buffer {
+                    ty: BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: BufferSize::new(mem::size_of::<AuxInformation>() as u64),
+                },
+                count: None,
+            }],
+        });
+
         // Create the pipeline layout
         let pipeline_layout = device.create_pipeline_layout(&PipelineLayoutDescriptor {
             label: Some("Scene Pipeline Layout"),
@@ -306,6 +424,30 @@ impl<'a> GraphicsContext<'a> {
             immediate_size: 0,
         });
 
+        // The glint pipeline needs the glint information on top of everything else
+        let glint_pipeline_layout = device.create_pipeline_layout(&PipelineLayoutDescriptor {
+            label: Some("Glint Pipeline Layout"),
+            bind_group_layouts: &[
+                Some(&transform_bind_group_layout),
+                Some(&skin_bind_group_layout),
+                Some(&sun_bind_group_layout),
+                Some(&glint_bind_group_layout),
+            ],
+            immediate_size: 0,
+        });
+
+        // The auxiliary passes need to know what they're drawing on top of everything else
+        let aux_pipeline_layout = device.create_pipeline_layout(&PipelineLayoutDescriptor {
+            label: Some("Aux Pipeline Layout"),
+            bind_group_layouts: &[
+                Some(&transform_bind_group_layout),
+                Some(&skin_bind_group_layout),
+                Some(&sun_bind_group_layout),
+                Some(&aux_bind_group_layout),
+            ],
+            immediate_size: 0,
+        });
+
         let shader = device.create_shader_module(ShaderModuleDescriptor {
             label: None,
             source: shader,
@@ -329,14 +471,58 @@ impl<'a> GraphicsContext<'a> {
             .blend_state
             .or(Some(BlendState::PREMULTIPLIED_ALPHA_BLENDING));
 
-        let pipeline = device.create_render_pipeline(&RenderPipelineDescriptor {
-            label: None,
-            layout: Some(&pipeline_layout),
+        let create_skin_pipeline =
+            |label: &str, entry_point: &str, depth_write_enabled: bool, sample_count: u32| {
+                device.create_render_pipeline(&RenderPipelineDescriptor {
+                    label: Some(label),
+                    layout: Some(&pipeline_layout),
+                    vertex: VertexState {
+                        module: &shader,
+                        entry_point: Option::Some("vs_main"),
+                        compilation_options: Default::default(),
+                        buffers: &[Some(vertex_buffer_layout.clone())],
+                    },
+                    primitive: PrimitiveState {
+                        cull_mode: None,
+                        front_face: FrontFace::Cw,
+                        ..Default::default()
+                    },
+                    depth_stencil: Some(DepthStencilState {
+                        format: Self::DEPTH_TEXTURE_FORMAT,
+                        depth_write_enabled: Some(depth_write_enabled),
+                        depth_compare: Some(CompareFunction::LessEqual),
+                        stencil: Default::default(),
+                        bias: Default::default(),
+                    }),
+                    multisample: MultisampleState {
+                        count: sample_count,
+                        alpha_to_coverage_enabled: false,
+                        ..Default::default()
+                    },
+                    fragment: Some(FragmentState {
+                        module: &shader,
+                        entry_point: Option::Some(entry_point),
+                        compilation_options: Default::default(),
+                        targets: &[Some(ColorTargetState {
+                            format: texture_format,
+                            blend,
+                            write_mask: ColorWrites::ALL,
+                        })],
+                    }),
+                    multiview_mask: None,
+                    cache: None,
+                })
+            };
+
+        // Auxiliary passes write data instead of colors, so they're never blended nor multisampled
+        let aux_pipeline = device.create_render_pipeline(&RenderPipelineDescriptor {
+            label: Some("Aux Pipeline"),
+            layout: Some(&aux_pipeline_layout),
             vertex: VertexState {
                 module: &shader,
                 entry_point: Option::Some("vs_main"),
                 compilation_options: Default::default(),
-                buffers: &[Some(vertex_buffer_layout)],
+                buffers: &[Some(vertex_buffer_layout.clone())],
             },
             primitive: PrimitiveState {
                 cull_mode: None,
@@ -350,18 +536,14 @@ impl<'a> GraphicsContext<'a> {
                 stencil: Default::default(),
                 bias: Default::default(),
             }),
-            multisample: MultisampleState {
-                count: sample_count,
-                alpha_to_coverage_enabled: false,
-                ..Default::default()
-            },
+            multisample: MultisampleState::default(),
             fragment: Some(FragmentState {
                 module: &shader,
-                entry_point: Option::Some("fs_main"),
+                entry_point: Option::Some("fs_aux"),
                 compilation_options: Default::default(),
                 targets: &[Some(ColorTargetState {
                     format: texture_format,
-                    blend,
+                    blend: None,
                     write_mask: ColorWrites::ALL,
                 })],
             }),
@@ -369,6 +551,267 @@ impl<'a> GraphicsContext<'a> {
             cache: None,
         });
 
+        // The glint is drawn additively on top of the armor it belongs to, so it only
+        // needs to be drawn where the armor itself was drawn (hence the equal depth test).
+        let glint_blend = BlendState {
+            color: BlendComponent {
+                src_factor: BlendFactor::Src,
+                dst_factor: BlendFactor::One,
+                operation: BlendOperation::Add,
+            },
+            alpha: BlendComponent {
+                src_factor: BlendFactor::Zero,
+                dst_factor: BlendFactor::One,
+                operation: BlendOperation::Add,
+            },
+        };
+
+        let create_sampled_pipelines = |sample_count: u32| {
+            let pipeline = create_skin_pipeline("Pipeline", "fs_main", true, sample_count);
+
+            // Textures with semi-transparent pixels draw their opaque pixels first, and then their semi-transparent
+            // ones back to front without writing depth, so that they blend against everything behind them.
+            let opaque_pipeline =
+                create_skin_pipeline("Opaque Pipeline", "fs_opaque", true, sample_count);
+            let translucent_pipeline = create_skin_pipeline(
+                "Translucent Pipeline",
+                "fs_translucent",
+                false,
+                sample_count,
+            );
+
+            // Emissive parts are drawn again into the glow texture, behind whatever covers them, so that they can be blurred
+            let glow_extract_pipeline =
+                create_skin_pipeline("Glow Extract Pipeline", "fs_main", false, sample_count);
+
+            let glint_pipeline = device.create_render_pipeline(&RenderPipelineDescriptor {
+                label: Some("Glint Pipeline"),
+                layout: Some(&glint_pipeline_layout),
+                vertex: VertexState {
+                    module: &shader,
+                    entry_point: Option::Some("vs_main"),
+                    compilation_options: Default::default(),
+                    buffers: &[Some(vertex_buffer_layout.clone())],
+                },
+                primitive: PrimitiveState {
+                    cull_mode: None,
+                    front_face: FrontFace::Cw,
+                    ..Default::default()
+                },
+                depth_stencil: Some(DepthStencilState {
+                    format: Self::DEPTH_TEXTURE_FORMAT,
+                    depth_write_enabled: Some(false),
+                    depth_compare: Some(CompareFunction::Equal),
+                    stencil: Default::default(),
+                    bias: Default::default(),
+                }),
+                multisample: MultisampleState {
+                    count: sample_count,
+                    alpha_to_coverage_enabled: false,
+                    ..Default::default()
+                },
+                fragment: Some(FragmentState {
+                    module: &shader,
+                    entry_point: Option::Some("fs_glint"),
+                    compilation_options: Default::default(),
+                    targets: &[Some(ColorTargetState {
+                        format: texture_format,
+                        blend: Some(glint_blend),
+                        write_mask: ColorWrites::ALL,
+                    })],
+                }),
+                multiview_mask: None,
+                cache: None,
+            });
+
+            // The outline is drawn over the whole output once everything else was rendered, reading the depth buffer to find the silhouette
+            let outline_bind_group_layout =
+                device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+                    label: Some("Outline Bind Group"),
+                    entries: &[
+                        BindGroupLayoutEntry {
+                            binding: 0,
+                            visibility: ShaderStages::FRAGMENT,
+                            ty: BindingType::Texture {
+                                multisampled: false,
+                                view_dimension: TextureViewDimension::D2,
+                                sample_type: TextureSampleType::Float { filterable: false },
+                            },
+                            count: None,
+                        },
+                        BindGroupLayoutEntry {
+                            binding: 1,
+                            visibility: ShaderStages::FRAGMENT,
+                            ty: BindingType::Texture {
+                                multisampled: sample_count > 1,
+                                view_dimension: TextureViewDimension::D2,
+                                sample_type: TextureSampleType::Depth,
+                            },
+                            count: None,
+                        },
+                        BindGroupLayoutEntry {
+                            binding: 2,
+                            visibility: ShaderStages::FRAGMENT,
+                            ty: BindingType::Buffer {
+                                ty: BufferBindingType::Uniform,
+                                has_dynamic_offset: false,
+                                min_binding_size: BufferSize::new(
+                                    mem::size_of::<OutlineInformation>() as u64,
+                                ),
+                            },
+                            count: None,
+                        },
+                    ],
+                });
+
+            let outline_pipeline_layout =
+                device.create_pipeline_layout(&PipelineLayoutDescriptor {
+                    label: Some("Outline Pipeline Layout"),
+                    bind_group_layouts: &[Some(&outline_bind_group_layout)],
+                    immediate_size: 0,
+                });
+
+            let depth_texture_type = if sample_count > 1 {
+                "texture_depth_multisampled_2d"
+            } else {
+                "texture_depth_2d"
+            };
+
+            let outline_shader = device.create_shader_module(ShaderModuleDescriptor {
+                label: Some("Outline Shader"),
+                source: ShaderSource::Wgsl(Cow::Owned(
+                    include_str!("outline.wgsl")
+                        .replace("{DEPTH_TEXTURE_TYPE}", depth_texture_type),
+                )),
+            });
+
+            let outline_pipeline = device.create_render_pipeline(&RenderPipelineDescriptor {
+                label: Some("Outline Pipeline"),
+                layout: Some(&outline_pipeline_layout),
+                vertex: VertexState {
+                    module: &outline_shader,
+                    entry_point: Option::Some("vs_main"),
+                    compilation_options: Default::default(),
+                    buffers: &[],
+                },
+                primitive: PrimitiveState::default(),
+                depth_stencil: None,
+                multisample: MultisampleState::default(),
+                fragment: Some(FragmentState {
+                    module: &outline_shader,
+                    entry_point: Option::Some("fs_main"),
+                    compilation_options: Default::default(),
+                    targets: &[Some(ColorTargetState {
+                        format: texture_format,
+                        blend: None,
+                        write_mask: ColorWrites::ALL,
+                    })],
+                }),
+                multiview_mask: None,
+                cache: None,
+            });
+
+            GraphicsContextSampledPipelines {
+                sample_count,
+                pipeline,
+                opaque_pipeline,
+                translucent_pipeline,
+                glint_pipeline,
+                glow_extract_pipeline,
+                outline_pipeline,
+                outline_bind_group_layout,
+            }
+        };
+
+        let pipelines = create_sampled_pipelines(sample_count);
+
+        // Scenes can turn multisampling off, so they need pipelines that don't use it
+        let single_sample_pipelines = (sample_count > 1).then(|| create_sampled_pipelines(1));
+
+        // The glow is blurred horizontally into a second texture, and then vertically on top of the rendered scene
+        let glow_bind_group_layout = device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+            label: Some("Glow Bind Group"),
+            entries: &[
+                BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Texture {
+                        multisampled: false,
+                        view_dimension: TextureViewDimension::D2,
+                        sample_type: TextureSampleType::Float { filterable: false },
+                    },
+                    count: None,
+                },
+                BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Buffer {
+                        ty: BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: BufferSize::new(mem::size_of::<GlowInformation>() as u64),
+                    },
+                    count: None,
+                },
+            ],
+        });
+
+        let glow_pipeline_layout = device.create_pipeline_layout(&PipelineLayoutDescriptor {
+            label: Some("Glow Pipeline Layout"),
+            bind_group_layouts: &[Some(&glow_bind_group_layout)],
+            immediate_size: 0,
+        });
+
+        let glow_shader = device.create_shader_module(ShaderModuleDescriptor {
+            label: Some("Glow Shader"),
+            source: ShaderSource::Wgsl(Cow::Borrowed(include_str!("glow.wgsl"))),
+        });
+
+        // The glow is added on top of the scene, making the transparent pixels it covers as opaque as it is
+        let glow_composite_blend = BlendState {
+            color: BlendComponent {
+                src_factor: BlendFactor::One,
+                dst_factor: BlendFactor::One,
+                operation: BlendOperation::Add,
+            },
+            alpha: BlendComponent {
+                src_factor: BlendFactor::One,
+                dst_factor: BlendFactor::OneMinusSrcAlpha,
+                operation: BlendOperation::Add,
+            },
+        };
+
+        let create_glow_pipeline = |label: &str, blend: Option<BlendState>| {
+            device.create_render_pipeline(&RenderPipelineDescriptor {
+                label: Some(label),
+                layout: Some(&glow_pipeline_layout),
+                vertex: VertexState {
+                    module: &glow_shader,
+                    entry_point: Option::Some("vs_main"),
+                    compilation_options: Default::default(),
+                    buffers: &[],
+                },
+                primitive: PrimitiveState::default(),
+                depth_stencil: None,
+                multisample: MultisampleState::default(),
+                fragment: Some(FragmentState {
+                    module: &glow_shader,
+                    entry_point: Option::Some("fs_main"),
+                    compilation_options: Default::default(),
+                    targets: &[Some(ColorTargetState {
+                        format: texture_format,
+                        blend,
+                        write_mask: ColorWrites::ALL,
+                    })],
+                }),
+                multiview_mask: None,
+                cache: None,
+            })
+        };
+
+        let glow_blur_pipeline = create_glow_pipeline("Glow Blur Pipeline", None);
+        let glow_composite_pipeline =
+            create_glow_pipeline("Glow Composite Pipeline", Some(glow_composite_blend));
+
         Ok(GraphicsContext {
             instance,
             device,
@@ -377,13 +820,23 @@ impl<'a> GraphicsContext<'a> {
             surface_config,
             texture_format,
             adapter,
-            pipeline,
+            pipelines,
+            single_sample_pipelines,
+            aux_pipeline,
+            glow_blur_pipeline,
+            glow_composite_pipeline,
             multisampling_strategy,
             layouts: GraphicsContextLayouts {
                 pipeline_layout,
                 transform_bind_group_layout,
                 skin_sampler_bind_group_layout: skin_bind_group_layout,
                 sun_bind_group_layout,
+                glint_bind_group_layout,
+                glint_pipeline_layout,
+                aux_bind_group_layout,
+                aux_pipeline_layout,
+                glow_bind_group_layout,
+                glow_pipeline_layout,
             },
         })
     }
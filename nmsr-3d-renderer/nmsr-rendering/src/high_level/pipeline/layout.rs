@@ -0,0 +1,119 @@
+use std::collections::BTreeMap;
+
+use glam::{Mat4, Vec2, Vec4};
+#[cfg(feature = "part_tracker")]
+use nmsr_player_parts::parts::provider::minecraft::get_part_group_name;
+use nmsr_player_parts::{parts::part::Part, types::PlayerBodyPartType};
+
+use super::scene::Size;
+use crate::{
+    high_level::utils::parts::primitive_convert,
+    low_level::primitives::part_primitive::PartPrimitive,
+};
+
+/// The area something covers in the output of a scene, in pixels from the top left corner.
+///
+/// Bounding boxes aren't clamped to the output, so parts that are partially out of view extend past its edges.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct SceneBoundingBox {
+    pub min: Vec2,
+    pub max: Vec2,
+}
+
+impl SceneBoundingBox {
+    pub fn width(&self) -> f32 {
+        self.max.x - self.min.x
+    }
+
+    pub fn height(&self) -> f32 {
+        self.max.y - self.min.y
+    }
+
+    fn union(self, other: Self) -> Self {
+        Self {
+            min: self.min.min(other.min),
+            max: self.max.max(other.max),
+        }
+    }
+}
+
+/// Where the parts of a player ended up in the output of a scene.
+#[derive(Debug, Clone, Default)]
+pub struct ScenePlayerLayout {
+    /// The bounding box of each body part, with its layer counted as part of it.
+    pub body_parts: BTreeMap<PlayerBodyPartType, SceneBoundingBox>,
+    /// The bounding box of each named part that isn't a whole body part, like Ears features or the cape.
+    #[cfg(feature = "part_tracker")]
+    pub named_parts: BTreeMap<String, SceneBoundingBox>,
+}
+
+impl ScenePlayerLayout {
+    /// Projects the given parts (and which body part they belong to) onto an output of the given size.
+    pub(crate) fn compute(
+        parts: &[Part],
+        body_part_types: &[Option<PlayerBodyPartType>],
+        view_projection: Mat4,
+        size: Size,
+    ) -> Self {
+        let mut layout = Self::default();
+
+        for (part, body_part) in parts.iter().zip(body_part_types) {
+            // Parts that don't belong to a body part (the shadow and the name tag) aren't part of the player
+            let Some(body_part) = body_part.map(|part| part.get_non_layer_part()) else {
+                continue;
+            };
+
+            if part.get_texture().is_glint() {
+                continue;
+            }
+
+            let Some(bounding_box) = Self::project(part, view_projection, size) else {
+                continue;
+            };
+
+            Self::merge(&mut layout.body_parts, body_part, bounding_box);
+
+            #[cfg(feature = "part_tracker")]
+            if let Some(name) = part
+                .get_name()
+                .filter(|&name| name != get_part_group_name(body_part))
+            {
+                Self::merge(&mut layout.named_parts, name.to_string(), bounding_box);
+            }
+        }
+
+        layout
+    }
+
+    fn merge<K: Ord>(
+        boxes: &mut BTreeMap<K, SceneBoundingBox>,
+        key: K,
+        bounding_box: SceneBoundingBox,
+    ) {
+        boxes
+            .entry(key)
+            .and_modify(|existing| *existing = existing.union(bounding_box))
+            .or_insert(bounding_box);
+    }
+
+    fn project(part: &Part, view_projection: Mat4, size: Size) -> Option<SceneBoundingBox> {
+        let size = Vec2::new(size.width as f32, size.height as f32);
+
+        primitive_convert(part)
+            .get_vertices()
+            .into_iter()
+            .map(|vertex| view_projection * vertex.position.extend(1.0))
+            // Vertices behind the camera can't be seen, so they don't take up any room
+            .filter(|clip: &Vec4| clip.w > f32::EPSILON)
+            .map(|clip| {
+                let ndc = clip.truncate().truncate() / clip.w;
+
+                Vec2::new(ndc.x + 1.0, 1.0 - ndc.y) * 0.5 * size
+            })
+            .map(|point| SceneBoundingBox {
+                min: point,
+                max: point,
+            })
+            .reduce(SceneBoundingBox::union)
+    }
+}
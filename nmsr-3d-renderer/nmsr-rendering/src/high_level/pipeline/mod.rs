@@ -1,8 +1,11 @@
 mod graphics_context;
+pub mod layout;
 pub mod pools;
 pub mod scene;
 mod scene_context;
+mod shadow;
 pub(crate) mod textures;
+pub mod vector;
 
 pub use graphics_context::*;
 pub use scene_context::*;
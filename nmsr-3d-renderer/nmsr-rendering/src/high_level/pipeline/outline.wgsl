@@ -0,0 +1,91 @@
+struct OutlineInformation {
+    inverse_view_projection: mat4x4<f32>,
+    color: vec4<f32>,
+    width: f32,
+    inner_edge_distance: f32,
+}
+
+@group(0)
+@binding(0)
+var source: texture_2d<f32>;
+
+// The type of the depth texture depends on whether we're multisampling, so it's filled in when the pipeline is created
+@group(0)
+@binding(1)
+var depth: {DEPTH_TEXTURE_TYPE};
+
+@group(0)
+@binding(2)
+var<uniform> outline: OutlineInformation;
+
+@vertex
+fn vs_main(@builtin(vertex_index) index: u32) -> @builtin(position) vec4<f32> {
+    // A single triangle that covers the whole screen
+    var uv: vec2<f32> = vec2<f32>(f32((index << 1u) & 2u), f32(index & 2u));
+
+    return vec4<f32>(uv * 2.0 - 1.0, 0.0, 1.0);
+}
+
+fn world_position(coord: vec2<i32>, size: vec2<i32>, depth_value: f32) -> vec3<f32> {
+    var ndc: vec2<f32> = (vec2<f32>(coord) + 0.5) / vec2<f32>(size) * 2.0 - 1.0;
+    var position: vec4<f32> = outline.inverse_view_projection * vec4<f32>(ndc.x, -ndc.y, depth_value, 1.0);
+
+    return position.xyz / position.w;
+}
+
+@fragment
+fn fs_main(@builtin(position) position: vec4<f32>) -> @location(0) vec4<f32> {
+    var size: vec2<i32> = vec2<i32>(textureDimensions(source));
+    var coord: vec2<i32> = vec2<i32>(position.xy);
+
+    var color: vec4<f32> = textureLoad(source, coord, 0);
+    var center_depth: f32 = textureLoad(depth, coord, 0);
+
+    // The silhouette is read from the depth buffer, since the shadow is drawn without writing to it
+    var is_covered: bool = center_depth < 1.0;
+    var center_position: vec3<f32> = world_position(coord, size, center_depth);
+
+    var radius: i32 = i32(ceil(outline.width));
+    var has_outline: bool = false;
+
+    for (var y: i32 = -radius; y <= radius; y++) {
+        for (var x: i32 = -radius; x <= radius; x++) {
+            var neighbor: vec2<i32> = coord + vec2<i32>(x, y);
+
+            if (f32(x * x + y * y) > outline.width * outline.width
+                || any(neighbor < vec2<i32>(0)) || any(neighbor >= size)) {
+                continue;
+            }
+
+            var neighbor_depth: f32 = textureLoad(depth, neighbor, 0);
+
+            if (neighbor_depth >= 1.0) {
+                continue;
+            }
+
+            if (!is_covered) {
+                // We're right outside of the silhouette
+                has_outline = true;
+            } else if (outline.inner_edge_distance > 0.0 && neighbor_depth < center_depth) {
+                // We're behind a part that stands far enough in front of us (measured along our view ray,
+                // so that how far apart the two pixels are doesn't count)
+                var neighbor_position: vec3<f32> = world_position(coord, size, neighbor_depth);
+
+                if (distance(center_position, neighbor_position) > outline.inner_edge_distance) {
+                    has_outline = true;
+                }
+            }
+        }
+    }
+
+    if (!has_outline) {
+        return color;
+    }
+
+    // Both colors are premultiplied, outlines go behind the model and inner edges go on top of it
+    if (is_covered) {
+        return outline.color + color * (1.0 - outline.color.a);
+    }
+
+    return color + outline.color * (1.0 - color.a);
+}
@@ -5,21 +5,30 @@ use tracing::trace;
 
 use crate::errors::NMSRRenderingError;
 
-use super::{scene_context::SceneContext, GraphicsContext};
+use super::{scene::SceneAntiAliasing, scene_context::SceneContext, GraphicsContext};
 
 pub struct SceneContextPoolManager<'a> {
     graphics_context: Arc<GraphicsContext<'a>>,
+    anti_aliasing: Option<SceneAntiAliasing>,
 }
 
 impl<'a> SceneContextPoolManager<'a> {
-    pub fn new(graphics_context: Arc<GraphicsContext<'a>>) -> Self {
-        Self { graphics_context }
+    pub fn new(
+        graphics_context: Arc<GraphicsContext<'a>>,
+        anti_aliasing: Option<SceneAntiAliasing>,
+    ) -> Self {
+        Self {
+            graphics_context,
+            anti_aliasing,
+        }
     }
 }
 
 impl<'a> std::fmt::Debug for SceneContextPoolManager<'a> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.debug_struct("SceneContextPoolManager").finish()
+        f.debug_struct("SceneContextPoolManager")
+            .field("anti_aliasing", &self.anti_aliasing)
+            .finish()
     }
 }
 
@@ -28,8 +37,11 @@ impl<'a> Manager for SceneContextPoolManager<'a> {
     type Error = Box<NMSRRenderingError>;
 
     async fn create(&self) -> Result<Self::Type, Self::Error> {
-        trace!("Creating new scene context");
-        Ok(SceneContext::new(&self.graphics_context))
+        trace!(anti_aliasing = ?self.anti_aliasing, "Creating new scene context");
+        Ok(SceneContext::new_with_anti_aliasing(
+            &self.graphics_context,
+            self.anti_aliasing,
+        ))
     }
 
     async fn recycle(
@@ -1,11 +1,26 @@
-use super::{textures::SceneTexture, GraphicsContext, SceneContextWrapper};
+use super::{
+    layout::ScenePlayerLayout,
+    shadow::GroundShadow,
+    textures::{
+        downsample, has_translucent_pixels, unmultiply_alpha, SceneContextTextures, SceneTexture,
+    },
+    vector::SceneVectorImage,
+    GraphicsContext, GraphicsContextSampledPipelines, MultiSamplingStrategy, SceneContextWrapper,
+};
 use crate::{
     errors::{NMSRRenderingError, Result},
-    high_level::{camera::Camera, pipeline::SceneContext, utils::parts::primitive_convert},
-    low_level::primitives::{mesh::Mesh, part_primitive::PartPrimitive},
+    high_level::{
+        camera::{Camera, CameraTile},
+        pipeline::SceneContext,
+        utils::parts::primitive_convert,
+    },
+    low_level::{
+        primitives::{mesh::Mesh, part_primitive::PartPrimitive, vertex::Vertex},
+        utils::look_from_yaw_pitch,
+    },
 };
 use bytemuck::{Pod, Zeroable};
-use glam::Vec3;
+use glam::{Affine3A, Mat3, Mat4, Vec2, Vec3, Vec4};
 use image::RgbaImage;
 use itertools::Itertools;
 use nmsr_player_parts::{
@@ -13,19 +28,22 @@ use nmsr_player_parts::{
     parts::{
         part::Part,
         provider::{PlayerPartProviderContext, PlayerPartsProvider},
+        uv::uv_from_pos_and_size,
     },
     types::{PlayerBodyPartType, PlayerPartTextureType},
 };
+use smaa::SmaaMode;
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     fmt::Debug,
     ops::{Deref, DerefMut},
 };
+use strum::{Display, EnumIter, EnumString};
 use tracing::{instrument, trace_span};
 use wgpu::{
     util::{BufferInitDescriptor, DeviceExt},
-    AddressMode, BindGroupDescriptor, BindGroupEntry, Color, CommandEncoder, CurrentSurfaceTexture,
-    Extent3d, FilterMode, IndexFormat, LoadOp, MipmapFilterMode, Operations,
+    AddressMode, BindGroup, BindGroupDescriptor, BindGroupEntry, Color, CommandEncoder,
+    CurrentSurfaceTexture, Extent3d, FilterMode, IndexFormat, LoadOp, MipmapFilterMode, Operations,
     RenderPassColorAttachment, RenderPassDepthStencilAttachment, SamplerDescriptor, StoreOp,
     TextureView,
 };
@@ -36,6 +54,78 @@ pub struct Size {
     pub height: u32,
 }
 
+impl Size {
+    fn scaled(self, factor: u32) -> Self {
+        Self {
+            width: self.width * factor,
+            height: self.height * factor,
+        }
+    }
+}
+
+/// A player placed in a scene.
+pub struct ScenePlayer<'a, M: ArmorMaterial> {
+    pub part_context: &'a PlayerPartProviderContext<M>,
+    pub body_parts: &'a [PlayerBodyPartType],
+    /// How far the player is moved from the origin.
+    pub offset: Vec3,
+    pub nametag: Option<SceneNametag>,
+}
+
+/// A name tag shown above a player, which always faces the camera like in game.
+///
+/// The name plate is read from the top left corner of the player's [`PlayerPartTextureType::Nametag`] texture.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct SceneNametag {
+    /// How wide the name plate is, in pixels of the name tag texture.
+    pub width: u32,
+}
+
+impl SceneNametag {
+    /// How tall the name plate is, in pixels of the name tag texture (a line of text and its background).
+    pub const HEIGHT: u32 = 10;
+
+    /// How big a pixel of the name plate is compared to a pixel of the skin.
+    const SCALE: f32 = 0.4;
+
+    /// How far above the top of the player the text starts, like in game.
+    const HEIGHT_OFFSET: f32 = 4.8;
+
+    fn create_part(self, player_top: f32, camera: &Camera) -> Part {
+        // The plate starts one pixel above the text, and ends right below it
+        let mut part = Part::new_quad(
+            PlayerPartTextureType::Nametag,
+            [-(self.width as f32) / 2.0, 1.0 - Self::HEIGHT as f32, 0.0],
+            [self.width, Self::HEIGHT, 0],
+            uv_from_pos_and_size(0, 0, self.width as u16, Self::HEIGHT as u16).flip_horizontally(),
+            Vec3::NEG_Z,
+            #[cfg(feature = "part_tracker")]
+            Some("Nametag".to_string()),
+        );
+
+        // Turn the plate so that it faces the camera, keeping its text upright
+        let forward = look_from_yaw_pitch(camera.get_yaw(), camera.get_pitch());
+        let right = look_from_yaw_pitch(camera.get_yaw(), 0.0).cross(Vec3::Y);
+        let up = right.cross(forward);
+
+        part.transform_affine(
+            Affine3A::from_translation(Vec3::new(0.0, player_top + Self::HEIGHT_OFFSET, 0.0))
+                * Affine3A::from_mat3(Mat3::from_cols(-right, up, forward))
+                * Affine3A::from_scale(Vec3::splat(Self::SCALE)),
+        );
+
+        part
+    }
+}
+
+/// Identifies a texture in a scene, since every player in a scene has their own set of textures.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct SceneTextureKey {
+    /// The index of the player this texture belongs to, in the order the players were added to the scene.
+    pub player: usize,
+    pub texture_type: PlayerPartTextureType,
+}
+
 pub struct Scene<T = SceneContextWrapper>
 where
     T: Deref<Target = SceneContext> + Send + Sync,
@@ -43,19 +133,232 @@ where
     camera: Camera,
     viewport_size: Size,
     scene_context: T,
-    textures: HashMap<PlayerPartTextureType, SceneTexture>,
-    computed_body_parts: Vec<Part>,
+    textures: HashMap<SceneTextureKey, SceneTexture>,
+    /// The images the textures were uploaded from, which vector renders read their texels from.
+    texture_images: HashMap<SceneTextureKey, RgbaImage>,
+    /// Whether to keep the images textures are uploaded from, which only vector renders need.
+    keep_texture_images: bool,
+    /// The textures that have semi-transparent pixels, which need to be drawn back to front.
+    translucent_textures: HashSet<SceneTextureKey>,
+    /// The parts of every player in the scene, indexed by player.
+    computed_body_parts: Vec<Vec<Part>>,
+    /// Which body part each of the computed parts belongs to, if any.
+    computed_body_part_types: Vec<Vec<Option<PlayerBodyPartType>>>,
+    player_offsets: Vec<Vec3>,
+    player_nametags: Vec<Option<SceneNametag>>,
     sun_information: SunInformation,
+    glint_information: GlintInformation,
+    outline: Option<SceneOutline>,
+    /// How strongly emissive textures glow, if they glow at all.
+    glow_strength: Option<f32>,
+    /// The auxiliary pass to render instead of the colors of the scene, if any.
+    aux_pass: Option<SceneAuxPass>,
+    /// The shadow textures computed since the last render, indexed by player.
+    /// These are uploaded when rendering, since the parts can be rebuilt without a graphics context.
+    pending_shadow_textures: HashMap<usize, RgbaImage>,
+}
+
+/// An outline drawn around the silhouette of everything in the scene, once it has been rendered.
+///
+/// Outlines are only drawn when rendering off-screen (i.e. not to a surface).
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct SceneOutline {
+    /// How thick the outline is, in pixels.
+    pub width: f32,
+    /// The color of the outline (not premultiplied).
+    pub color: Vec4,
+    /// Whether to also outline parts of the model standing in front of other parts.
+    pub inner_edges: bool,
+}
+
+impl SceneOutline {
+    /// How far apart two surfaces have to be (in skin pixels) to draw an edge between them.
+    const INNER_EDGE_DISTANCE: f32 = 1.5;
+}
+
+#[derive(Copy, Clone, Pod, Zeroable, Debug)]
+#[repr(C)]
+pub struct OutlineInformation {
+    pub inverse_view_projection: Mat4,
+    /// The premultiplied color of the outline.
+    pub color: Vec4,
+    pub width: f32,
+    /// How far apart two surfaces have to be to draw an edge between them, or zero to only outline the silhouette.
+    pub inner_edge_distance: f32,
+    _padding: [f32; 2],
+}
+
+impl OutlineInformation {
+    pub fn new(outline: SceneOutline, view_projection: Mat4) -> Self {
+        let color = outline.color;
+
+        Self {
+            inverse_view_projection: view_projection.inverse(),
+            color: (color.truncate() * color.w).extend(color.w),
+            width: outline.width,
+            inner_edge_distance: if outline.inner_edges {
+                SceneOutline::INNER_EDGE_DISTANCE
+            } else {
+                0.0
+            },
+            _padding: [0.0; 2],
+        }
+    }
+}
+
+/// The layout of this struct matches the `GlowInformation` uniform of the glow shader.
+#[derive(Copy, Clone, Pod, Zeroable, Debug)]
+#[repr(C)]
+pub struct GlowInformation {
+    /// How far apart (in pixels) the samples of the blur are, and in which direction the blur goes.
+    pub step: Vec2,
+    pub strength: f32,
+    _padding: f32,
+}
+
+impl GlowInformation {
+    /// How tall the output has to be for each sample of the blur to be a pixel apart, so that the glow grows with the output.
+    const PIXELS_PER_STEP: f32 = 256.0;
+
+    /// How many samples the blur takes on each side of a pixel, which matches the weights of the glow shader.
+    const SAMPLES_PER_SIDE: f32 = 6.0;
+
+    fn new(direction: Vec2, output_height: u32, strength: f32) -> Self {
+        Self {
+            step: direction * Self::get_spread(output_height),
+            strength,
+            _padding: 0.0,
+        }
+    }
+
+    fn get_spread(output_height: u32) -> f32 {
+        (output_height as f32 / Self::PIXELS_PER_STEP).max(1.0)
+    }
+
+    /// How far (in pixels) the glow reaches past the emissive parts.
+    fn get_radius(output_height: u32) -> f32 {
+        Self::get_spread(output_height) * Self::SAMPLES_PER_SIDE
+    }
+}
+
+/// How the edges of a scene are smoothed, instead of the multisampling the graphics context was created with.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, EnumString, EnumIter, Display)]
+#[strum(serialize_all = "snake_case", ascii_case_insensitive)]
+pub enum SceneAntiAliasing {
+    /// No smoothing at all, so that every pixel is exactly one color (e.g. for pixel art).
+    None,
+    /// Only SMAA, without multisampling.
+    Smaa,
+    /// Render at twice the size, and average every 2x2 block of pixels.
+    Ssaa2,
+    /// Render at four times the size, and average every 4x4 block of pixels.
+    Ssaa4,
+}
+
+impl SceneAntiAliasing {
+    pub fn get_multisampling_strategy(self) -> MultiSamplingStrategy {
+        match self {
+            Self::Smaa => MultiSamplingStrategy::SMAA(SmaaMode::Smaa1X),
+            Self::None | Self::Ssaa2 | Self::Ssaa4 => MultiSamplingStrategy::MSAA(1),
+        }
+    }
+
+    /// How many times bigger the scene is rendered on each axis, before being scaled back down.
+    pub fn get_supersampling_factor(self) -> u32 {
+        match self {
+            Self::Ssaa2 => 2,
+            Self::Ssaa4 => 4,
+            Self::None | Self::Smaa => 1,
+        }
+    }
+}
+
+/// A buffer that can be rendered instead of the colors of a scene.
+///
+/// Auxiliary passes are rendered without anti-aliasing, since blending the values of neighbouring pixels would make them meaningless.
+/// Every pixel that is covered by the scene is fully opaque, except with [`SceneAuxPass::Uv`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, EnumString, Display)]
+#[strum(serialize_all = "snake_case", ascii_case_insensitive)]
+#[repr(u32)]
+pub enum SceneAuxPass {
+    /// The depth of every pixel (from 0 at the near plane to 1 at the far plane), split across the red, green and blue channels
+    /// with the most significant bits in the red channel.
+    Depth = 0,
+    /// The direction every pixel faces, with each axis mapped from -1..1 to the red, green and blue channels.
+    Normal = 1,
+    /// The body part every pixel belongs to, in the red channel.
+    /// Body parts are numbered from 1 in the order of [`PlayerBodyPartType`], with 0 for parts that don't belong to any.
+    PartId = 2,
+    /// Which skin texel every pixel shows, packed like the UV maps of `nmsr-lib`
+    /// (6 bits for each of u and v, 8 bits of shading and 12 bits of depth, starting from the lowest bits of the red channel).
+    /// Pixels that show anything other than the skin are fully transparent.
+    Uv = 3,
+}
+
+/// The layout of this struct matches the `AuxInformation` uniform of the shader.
+#[derive(Copy, Clone, Pod, Zeroable, Debug)]
+#[repr(C)]
+pub struct AuxInformation {
+    pub pass: u32,
+    pub part_id: u32,
+    pub is_skin: u32,
+    _padding: u32,
+}
+
+impl AuxInformation {
+    fn new(
+        pass: SceneAuxPass,
+        body_part: Option<PlayerBodyPartType>,
+        texture: PlayerPartTextureType,
+    ) -> Self {
+        Self {
+            pass: pass as u32,
+            part_id: body_part.map_or(0, |body_part| body_part as u32 + 1),
+            is_skin: (texture == PlayerPartTextureType::Skin).into(),
+            _padding: 0,
+        }
+    }
+}
+
+/// How faces are lit by the lights of a scene.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash, EnumString, Display)]
+#[strum(serialize_all = "snake_case", ascii_case_insensitive)]
+#[repr(u32)]
+pub enum SceneShading {
+    /// Faces get brighter the more they face the key light.
+    #[default]
+    Lambert = 0,
+    /// Every face is fully lit, so only the color of the key light is used.
+    Flat = 1,
+    /// Like [`SceneShading::Lambert`], but the light is split into a few bands of brightness.
+    Toon = 2,
+    /// Faces are lit depending on which way they face, like blocks are lit in game (ignoring the lights' directions).
+    ///
+    /// Tops are fully lit, faces along the Z axis are at 80%, faces along the X axis are at 60% and bottoms are at 50%.
+    Minecraft = 3,
 }
 
+/// The lighting of the scene, made of a key light (the sun) and an optional fill light.
+///
+/// The layout of this struct matches the `SunInformation` uniform of the shader.
 #[derive(Copy, Clone, Pod, Zeroable, Debug)]
 #[repr(C)]
 pub struct SunInformation {
+    /// The direction the key light shines towards.
     pub direction: Vec3,
     pub intensity: f32,
+    /// The minimum amount of key light a face receives, even when it faces away from it.
     pub ambient: f32,
-    _padding_0: f32,
-    _padding_1: f64,
+    /// The [`SceneShading`] model used to light the scene.
+    pub shading: u32,
+    _padding_0: [f32; 2],
+    pub color: Vec3,
+    pub fill_intensity: f32,
+    /// The direction the fill light shines towards, which only brightens the faces it shines on.
+    pub fill_direction: Vec3,
+    _padding_1: f32,
+    pub fill_color: Vec3,
+    _padding_2: f32,
 }
 
 impl Default for SunInformation {
@@ -64,8 +367,14 @@ impl Default for SunInformation {
             direction: Vec3::ONE,
             intensity: 1.0,
             ambient: Self::DEFAULT_AMBIENT_LIGHT,
-            _padding_0: 0.0,
+            shading: SceneShading::Lambert as u32,
+            _padding_0: [0.0; 2],
+            color: Vec3::ONE,
+            fill_intensity: 0.0,
+            fill_direction: Vec3::ONE,
             _padding_1: 0.0,
+            fill_color: Vec3::ONE,
+            _padding_2: 0.0,
         }
     }
 }
@@ -81,6 +390,61 @@ impl SunInformation {
             ..Default::default()
         }
     }
+
+    /// Tints the key light (and the ambient light) with the given color.
+    #[must_use]
+    pub fn with_color(mut self, color: Vec3) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// Picks how faces are lit by the lights of the scene.
+    #[must_use]
+    pub fn with_shading(mut self, shading: SceneShading) -> Self {
+        self.shading = shading as u32;
+        self
+    }
+
+    /// Adds a fill light to the scene, on top of the key light.
+    #[must_use]
+    pub fn with_fill_light(mut self, direction: Vec3, intensity: f32, color: Vec3) -> Self {
+        self.fill_direction = direction;
+        self.fill_intensity = intensity;
+        self.fill_color = color;
+        self
+    }
+}
+
+#[derive(Copy, Clone, Pod, Zeroable, Debug)]
+#[repr(C)]
+pub struct GlintInformation {
+    pub texture_matrix: Mat4,
+}
+
+impl Default for GlintInformation {
+    fn default() -> Self {
+        Self::new(0.0)
+    }
+}
+
+impl GlintInformation {
+    const GLINT_SCALE: f32 = 0.16;
+    const GLINT_ROTATION: f32 = 10.0;
+
+    /// Computes the glint texture matrix for the given time (in ticks), mirroring how the game scrolls the glint texture.
+    pub fn new(time: f32) -> Self {
+        // The game scrolls the glint by 8 units per millisecond, and a tick lasts 50 milliseconds
+        let time = time * 50.0 * 8.0;
+
+        let x = (time % 110000.0) / 110000.0;
+        let y = (time % 30000.0) / 30000.0;
+
+        let texture_matrix = Mat4::from_translation(Vec3::new(-x, y, 0.0))
+            * Mat4::from_rotation_z(Self::GLINT_ROTATION.to_radians())
+            * Mat4::from_scale(Vec3::splat(Self::GLINT_SCALE));
+
+        Self { texture_matrix }
+    }
 }
 
 type ExtraRenderFunc<'a> =
@@ -93,6 +457,7 @@ where
     const RECTANGLE_SHADOW_BYTES: &'static [u8] = include_bytes!("shadow_rectangle.png");
     const SQUARE_SHADOW_BYTES: &'static [u8] = include_bytes!("shadow_square.png");
 
+    /// Returns the fixed shadow texture, for when the shadow can't be cast by the parts themselves (e.g. in exported models).
     pub fn get_shadow_bytes(is_square: bool) -> &'static [u8] {
         if is_square {
             Self::SQUARE_SHADOW_BYTES
@@ -103,51 +468,90 @@ where
 
     pub fn new<M: ArmorMaterial>(
         graphics_context: &GraphicsContext,
-        mut scene_context: T,
-        mut camera: Camera,
+        scene_context: T,
+        camera: Camera,
         sun: SunInformation,
         viewport_size: Size,
         part_context: &PlayerPartProviderContext<M>,
         body_parts: &[PlayerBodyPartType],
     ) -> Self {
+        let player = ScenePlayer {
+            part_context,
+            body_parts,
+            offset: Vec3::ZERO,
+            nametag: None,
+        };
+
+        Self::new_group(
+            graphics_context,
+            scene_context,
+            camera,
+            sun,
+            viewport_size,
+            &[player],
+        )
+    }
+
+    /// Creates a scene with several players in it, each with their own textures.
+    ///
+    /// The glint is animated using the time of the first player.
+    pub fn new_group<M: ArmorMaterial>(
+        graphics_context: &GraphicsContext,
+        mut scene_context: T,
+        mut camera: Camera,
+        sun: SunInformation,
+        viewport_size: Size,
+        players: &[ScenePlayer<M>],
+    ) -> Self {
+        let glint = GlintInformation::new(
+            players
+                .first()
+                .map_or(0.0, |player| player.part_context.movement.time),
+        );
+
+        // Supersampled scenes are rendered bigger, and scaled back down when copied out of the output
+        let supersampling_factor = scene_context.get_supersampling_factor();
+        let viewport_size = viewport_size.scaled(supersampling_factor);
+
+        if let Some(camera_size) = camera.get_size() {
+            camera.set_size(Some(camera_size.scaled(supersampling_factor)));
+        }
+
         // Initialize our camera with the viewport size
         Self::update_scene_context(
             &mut camera,
             &sun,
+            &glint,
             viewport_size,
             &mut scene_context,
             graphics_context,
         );
 
-        // Compute the body parts we need to render
-        let computed_body_parts = Self::collect_player_parts(part_context, body_parts);
-
         let mut scene = Self {
             camera,
             viewport_size,
             scene_context,
             textures: HashMap::new(),
-            computed_body_parts,
+            texture_images: HashMap::new(),
+            keep_texture_images: false,
+            translucent_textures: HashSet::new(),
+            computed_body_parts: Vec::with_capacity(players.len()),
+            computed_body_part_types: Vec::with_capacity(players.len()),
+            player_offsets: players.iter().map(|player| player.offset).collect(),
+            player_nametags: players.iter().map(|player| player.nametag).collect(),
             sun_information: sun,
+            glint_information: glint,
+            outline: None,
+            glow_strength: None,
+            aux_pass: None,
+            pending_shadow_textures: HashMap::new(),
         };
 
-        if part_context.shadow_y_pos.is_some() {
-            let shadow_bytes = Self::get_shadow_bytes(part_context.shadow_is_square);
-
-            // We need to render the shadow, so upload the shadow texture already
-            let shadow_image =
-                image::load_from_memory_with_format(shadow_bytes, image::ImageFormat::Png)
-                    .expect("Failed to load shadow texture");
-
-            let shadow_image = shadow_image
-                .as_rgba8()
-                .expect("Failed to convert shadow texture to RGBA8");
-
-            scene.set_texture(
-                graphics_context,
-                PlayerPartTextureType::Shadow,
-                shadow_image,
-            );
+        for (index, player) in players.iter().enumerate() {
+            // Compute the body parts we need to render
+            scene.computed_body_parts.push(vec![]);
+            scene.computed_body_part_types.push(vec![]);
+            scene.rebuild_player_parts(index, player.part_context, player.body_parts);
         }
 
         scene
@@ -161,44 +565,202 @@ where
         &mut self.sun_information
     }
 
+    pub fn glint_information_mut(&mut self) -> &mut GlintInformation {
+        &mut self.glint_information
+    }
+
+    pub fn outline_mut(&mut self) -> &mut Option<SceneOutline> {
+        &mut self.outline
+    }
+
+    pub fn glow_strength_mut(&mut self) -> &mut Option<f32> {
+        &mut self.glow_strength
+    }
+
+    pub fn aux_pass_mut(&mut self) -> &mut Option<SceneAuxPass> {
+        &mut self.aux_pass
+    }
+
+    /// Whether the textures set from now on are kept around for [`Scene::render_vector`], which needs them.
+    pub fn keep_texture_images_mut(&mut self) -> &mut bool {
+        &mut self.keep_texture_images
+    }
+
     pub fn viewport_size_mut(&mut self) -> &mut Size {
         &mut self.viewport_size
     }
 
+    /// Returns the parts of the first player in the scene.
     pub fn parts(&self) -> &[Part] {
-        &self.computed_body_parts
+        self.player_parts(0)
+    }
+
+    pub fn player_parts(&self, player: usize) -> &[Part] {
+        self.computed_body_parts
+            .get(player)
+            .map_or(&[], |parts| parts.as_slice())
+    }
+
+    /// Moves the camera so that every player in the scene is in view, see [`Camera::fit_to_points`].
+    pub fn fit_camera_to_parts(&mut self, graphics_context: &GraphicsContext, padding: f32) {
+        let points = self.get_fitted_points();
+
+        self.camera.fit_to_points(points, padding);
+        self.update(graphics_context);
+    }
+
+    /// Makes the camera show every skin texel as exactly `pixel_scale` output pixels, see [`Camera::snap_to_pixel_scale`].
+    ///
+    /// Returns whether every player in the scene is still fully in view at that scale.
+    #[must_use]
+    pub fn snap_camera_to_pixel_scale(
+        &mut self,
+        graphics_context: &GraphicsContext,
+        pixel_scale: u32,
+    ) -> bool {
+        // Supersampled scenes are scaled back down, so they need that many more pixels per texel
+        let pixels_per_unit = pixel_scale * self.scene_context.get_supersampling_factor();
+
+        self.camera.snap_to_pixel_scale(pixels_per_unit as f32);
+        self.update(graphics_context);
+
+        let view_projection = self.camera.get_view_projection_matrix();
+
+        self.get_fitted_points().into_iter().all(|point| {
+            let point = view_projection.project_point3(point);
+
+            // Allow for rounding errors on parts that end up exactly on the edges
+            point.x.abs() <= 1.0 + 1e-4 && point.y.abs() <= 1.0 + 1e-4
+        })
+    }
+
+    /// Returns the points that have to be in view for every player in the scene to be seen.
+    fn get_fitted_points(&self) -> Vec<Vec3> {
+        // The shadow fades out well before its edges, so it doesn't need to be in view
+        self.computed_body_parts
+            .iter()
+            .flatten()
+            .filter(|part| !part.get_texture().is_shadow())
+            .flat_map(|part| primitive_convert(part).get_vertices())
+            .map(|vertex| vertex.position)
+            .collect()
+    }
+
+    /// Returns where the parts of every player in the scene end up in the output, as seen from the current camera.
+    pub fn compute_layout(&mut self) -> Vec<ScenePlayerLayout> {
+        let view_projection = self.camera.get_view_projection_matrix();
+        let size = self.camera.get_size().unwrap_or(self.viewport_size);
+
+        // The layout is in output pixels, which supersampled scenes have fewer of than they rendered
+        let supersampling_factor = self.scene_context.get_supersampling_factor();
+        let size = Size {
+            width: size.width / supersampling_factor,
+            height: size.height / supersampling_factor,
+        };
+
+        self.computed_body_parts
+            .iter()
+            .zip(&self.computed_body_part_types)
+            .map(|(parts, body_part_types)| {
+                ScenePlayerLayout::compute(parts, body_part_types, view_projection, size)
+            })
+            .collect()
+    }
+
+    /// Renders the scene as flat polygons instead of pixels, see [`SceneVectorImage`].
+    ///
+    /// Only the textured parts are drawn, so there's no shadow, glint, outline or glow.
+    /// The textures have to be set after enabling [`Scene::keep_texture_images_mut`], otherwise nothing is drawn.
+    pub fn render_vector(&mut self) -> SceneVectorImage {
+        let view_projection = self.camera.get_view_projection_matrix();
+        let size = self.camera.get_size().unwrap_or(self.viewport_size);
+
+        // The polygons are in output pixels, which supersampled scenes have fewer of than they render
+        let supersampling_factor = self.scene_context.get_supersampling_factor();
+        let size = Size {
+            width: size.width / supersampling_factor,
+            height: size.height / supersampling_factor,
+        };
+
+        let parts = self
+            .computed_body_parts
+            .iter()
+            .enumerate()
+            .flat_map(|(player, parts)| parts.iter().map(move |part| (player, part)))
+            .filter(|(_, part)| !part.get_texture().is_shadow() && !part.get_texture().is_glint())
+            .filter_map(|(player, part)| {
+                let key = SceneTextureKey {
+                    player,
+                    texture_type: part.get_texture(),
+                };
+
+                self.texture_images.get(&key).map(|texture| (part, texture))
+            });
+
+        SceneVectorImage::compute(parts, view_projection, size, &self.sun_information)
     }
 
     pub fn has_texture(&self, texture_type: PlayerPartTextureType) -> Result<bool> {
-        Ok(self.textures.contains_key(&texture_type))
+        Ok(self.textures.contains_key(&SceneTextureKey {
+            player: 0,
+            texture_type,
+        }))
     }
 
+    /// Sets a texture of the first player in the scene.
     pub fn set_texture(
         &mut self,
         graphics_context: &GraphicsContext,
         texture_type: PlayerPartTextureType,
         texture: &RgbaImage,
     ) {
+        self.set_player_texture(graphics_context, 0, texture_type, texture);
+    }
+
+    pub fn set_player_texture(
+        &mut self,
+        graphics_context: &GraphicsContext,
+        player: usize,
+        texture_type: PlayerPartTextureType,
+        texture: &RgbaImage,
+    ) {
+        let key = SceneTextureKey {
+            player,
+            texture_type,
+        };
+
+        if has_translucent_pixels(texture) {
+            self.translucent_textures.insert(key);
+        } else {
+            self.translucent_textures.remove(&key);
+        }
+
+        if self.keep_texture_images {
+            self.texture_images.insert(key, texture.clone());
+        } else {
+            self.texture_images.remove(&key);
+        }
+
         let texture =
             SceneContext::upload_texture(graphics_context, texture, Some(texture_type.into()));
-        self.textures.insert(texture_type, texture);
+        self.textures.insert(key, texture);
     }
 
     #[instrument(skip(part_provider_context))]
     fn collect_player_parts<C: ArmorMaterial>(
         part_provider_context: &PlayerPartProviderContext<C>,
         body_parts: &[PlayerBodyPartType],
-    ) -> Vec<Part> {
+    ) -> Vec<(Option<PlayerBodyPartType>, Part)> {
         let providers = [
             PlayerPartsProvider::Minecraft,
             #[cfg(feature = "ears")]
             PlayerPartsProvider::Ears,
         ];
 
-        let mut parts = part_provider_context.get_parts(&providers, body_parts);
+        let mut parts = part_provider_context.get_parts_by_body_part(&providers, body_parts);
 
         // Sort the parts by texture. This allows us to render all parts with the same texture in one go.
-        parts.sort_by_key(|p| p.get_texture());
+        parts.sort_by_key(|(_, p)| p.get_texture());
 
         parts
     }
@@ -213,11 +775,44 @@ where
         graphics_context: &GraphicsContext,
         extra_rendering: Option<ExtraRenderFunc>,
     ) -> Result<()> {
-        let pipeline = &graphics_context.pipeline;
+        for (player, shadow_texture) in std::mem::take(&mut self.pending_shadow_textures) {
+            self.set_player_texture(
+                graphics_context,
+                player,
+                PlayerPartTextureType::Shadow,
+                &shadow_texture,
+            );
+        }
+
+        let render_size = self.camera.get_render_size().unwrap_or(self.viewport_size);
+        self.scene_context
+            .prepare_textures(graphics_context, render_size, self.viewport_size);
+
+        if let Some(aux_pass) = self.aux_pass {
+            return self.render_aux_pass(graphics_context, aux_pass);
+        }
+
+        let pipelines = self.get_sampled_pipelines(graphics_context);
+        let pipeline = &pipelines.pipeline;
+        let opaque_pipeline = &pipelines.opaque_pipeline;
+        let translucent_pipeline = &pipelines.translucent_pipeline;
+        let glint_pipeline = &pipelines.glint_pipeline;
         let device = &graphics_context.device;
         let queue = &graphics_context.queue;
         let smaa_target = self.scene_context.smaa_target.take();
 
+        let view_projection = self.camera.get_view_projection_matrix();
+        // Supersampled scenes are rendered bigger, so their outline has to be as well
+        let supersampling_factor = self.scene_context.get_supersampling_factor() as f32;
+        let outline = self.outline.map(|outline| {
+            let outline = SceneOutline {
+                width: outline.width * supersampling_factor,
+                ..outline
+            };
+
+            OutlineInformation::new(outline, view_projection)
+        });
+
         let mut smaa_target = match smaa_target {
             Some(target) => target,
             _ => unreachable!("SMAA target is always initialized"),
@@ -226,6 +821,7 @@ where
         let transform_bind_group = &self.scene_context.transform_bind_group;
         let sun_bind_group = &self.scene_context.sun_information_bind_group;
         let emissive_sun_bind_group = &self.scene_context.emissive_sun_information_bind_group;
+        let glint_bind_group = &self.scene_context.glint_information_bind_group;
 
         let textures = self
             .scene_context
@@ -265,57 +861,67 @@ where
         let (mut load_op, mut depth_load_opt) =
             (LoadOp::Clear(Color::TRANSPARENT), LoadOp::Clear(1.0));
 
-        for (texture, parts) in &self
+        // Render every player's skin before anyone's shadow or glint, just like with a single player
+        let mut scene_parts = self
             .computed_body_parts
             .iter()
-            .chunk_by(|p| p.get_texture())
-        {
-            let _pass_span =
-                trace_span!("render_pass", texture = Into::<&str>::into(texture)).entered();
+            .enumerate()
+            .flat_map(|(player, parts)| parts.iter().map(move |part| (player, part)))
+            .collect::<Vec<_>>();
+        scene_parts.sort_by_key(|(player, part)| (part.get_texture(), *player));
 
-            let texture_view = &self
-                .textures
-                .get(&texture)
-                .ok_or(NMSRRenderingError::SceneContextTextureNotSet(texture))?
-                .view;
+        // Shadows and glints are blended on their own, so they're never drawn in the translucent pass
+        let translucent_parts = scene_parts
+            .iter()
+            .map(|&(player, part)| {
+                let key = SceneTextureKey {
+                    player,
+                    texture_type: part.get_texture(),
+                };
+
+                (key, part)
+            })
+            .filter(|(key, _)| {
+                !key.texture_type.is_shadow()
+                    && !key.texture_type.is_glint()
+                    && self.translucent_textures.contains(key)
+            })
+            .collect::<Vec<_>>();
+
+        // Emissive parts are drawn a second time into the glow texture, which is blurred on top of the scene
+        let glow_parts = match self.glow_strength {
+            Some(strength) if strength > 0.0 => scene_parts
+                .iter()
+                .filter(|(_, part)| part.get_texture().is_emissive())
+                .map(|&(player, part)| {
+                    let key = SceneTextureKey {
+                        player,
+                        texture_type: part.get_texture(),
+                    };
+
+                    (key, part)
+                })
+                .collect::<Vec<_>>(),
+            _ => vec![],
+        };
 
-            let (filter, mipmap_filter) = if texture.is_shadow() {
-                (FilterMode::Linear, MipmapFilterMode::Linear)
-            } else {
-                (FilterMode::Nearest, MipmapFilterMode::Nearest)
-            };
+        for (texture_key, parts) in
+            &scene_parts
+                .into_iter()
+                .chunk_by(|(player, part)| SceneTextureKey {
+                    player: *player,
+                    texture_type: part.get_texture(),
+                })
+        {
+            let texture = texture_key.texture_type;
 
-            let texture_sampler = device.create_sampler(&SamplerDescriptor {
-                label: Some(texture.into()),
-                address_mode_u: AddressMode::ClampToEdge,
-                address_mode_v: AddressMode::ClampToEdge,
-                address_mode_w: AddressMode::ClampToEdge,
-                mag_filter: filter,
-                min_filter: filter,
-                mipmap_filter: mipmap_filter,
-                lod_min_clamp: 0.0,
-                lod_max_clamp: 0.0,
-                compare: None,
-                anisotropy_clamp: 1,
-                border_color: None,
-            });
+            let _pass_span =
+                trace_span!("render_pass", texture = Into::<&str>::into(texture)).entered();
 
-            let texture_sampler_bind_group = device.create_bind_group(&BindGroupDescriptor {
-                layout: &graphics_context.layouts.skin_sampler_bind_group_layout,
-                entries: &[
-                    BindGroupEntry {
-                        binding: 0,
-                        resource: wgpu::BindingResource::TextureView(texture_view),
-                    },
-                    BindGroupEntry {
-                        binding: 1,
-                        resource: wgpu::BindingResource::Sampler(&texture_sampler),
-                    },
-                ],
-                label: Some(texture.into()),
-            });
+            let texture_sampler_bind_group =
+                self.create_texture_bind_group(graphics_context, texture_key)?;
 
-            let parts = parts.collect::<Vec<&Part>>();
+            let parts = parts.map(|(_, part)| part).collect::<Vec<&Part>>();
 
             let to_render: Vec<_> = trace_span!("part_convert")
                 .in_scope(|| parts.iter().map(|&p| primitive_convert(p)).collect());
@@ -370,16 +976,29 @@ where
                 multiview_mask: None,
             });
 
-            rpass.set_pipeline(pipeline);
+            if texture.is_glint() {
+                rpass.set_pipeline(glint_pipeline);
+            } else if !texture.is_shadow() && self.translucent_textures.contains(&texture_key) {
+                // The semi-transparent pixels are drawn later on, once everything opaque is there
+                rpass.set_pipeline(opaque_pipeline);
+            } else {
+                rpass.set_pipeline(pipeline);
+            }
+
             rpass.set_bind_group(0, transform_bind_group, &[]);
             rpass.set_bind_group(1, &texture_sampler_bind_group, &[]);
 
-            if !texture.is_emissive() {
+            // Name tags aren't lit, just like emissive textures
+            if !texture.is_emissive() && !texture.is_nametag() {
                 rpass.set_bind_group(2, sun_bind_group, &[]);
             } else {
                 rpass.set_bind_group(2, emissive_sun_bind_group, &[]);
             }
 
+            if texture.is_glint() {
+                rpass.set_bind_group(3, glint_bind_group, &[]);
+            }
+
             rpass.set_index_buffer(index_buf.slice(..), IndexFormat::Uint16);
             rpass.set_vertex_buffer(0, vertex_buf.slice(..));
             rpass.draw_indexed(0..(index_data.len() as u32), 0, 0..1);
@@ -390,64 +1009,135 @@ where
             }
         }
 
-        {
-            let _pass_span = trace_span!("render_encoder_submit").entered();
-            queue.submit(Some(encoder.finish()));
-        }
+        if !translucent_parts.is_empty() {
+            let _pass_span = trace_span!("translucent_render_pass").entered();
 
-        {
-            let _pass_span = trace_span!("smaa_frame_drop").entered();
+            // Sort every triangle from back to front, so that each semi-transparent pixel blends against what's behind it
+            let triangles = trace_span!("translucent_sort").in_scope(|| {
+                let triangles = translucent_parts.iter().flat_map(|&(key, part)| {
+                    primitive_convert(part)
+                        .get_vertices_grouped()
+                        .into_iter()
+                        .map(move |triangle| (key, triangle))
+                });
 
-            // Explicitly drop the smaa frame so that it is resolved before we copy it to the output buffer.
-            drop(smaa_frame);
-        }
+                sort_back_to_front(triangles, view_projection)
+            });
 
-        {
-            let _pass_span = trace_span!("copy_texture_to_buffer").entered();
+            let vertex_data = triangles
+                .iter()
+                .flat_map(|(_, triangle)| *triangle)
+                .collect::<Vec<_>>();
 
-            let mut encoder =
-                device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+            let vertex_buf = trace_span!("vertex_buffer_create").in_scope(|| {
+                device.create_buffer_init(&BufferInitDescriptor {
+                    label: Some("Translucent Vertex Buffer"),
+                    contents: bytemuck::cast_slice(&vertex_data),
+                    usage: wgpu::BufferUsages::VERTEX,
+                })
+            });
 
-            encoder.copy_texture_to_buffer(
-                wgpu::TexelCopyTextureInfo {
-                    aspect: wgpu::TextureAspect::All,
-                    texture: &textures.output_texture.texture,
-                    mip_level: 0,
-                    origin: wgpu::Origin3d::ZERO,
-                },
-                wgpu::TexelCopyBufferInfo {
-                    buffer: &textures.texture_output_buffer,
-                    layout: wgpu::TexelCopyBufferLayout {
-                        offset: 0,
-                        bytes_per_row: Some(
-                            textures
-                                .texture_output_buffer_dimensions
-                                .padded_bytes_per_row,
-                        ),
-                        rows_per_image: None,
-                    },
-                },
-                Extent3d {
-                    width: textures.viewport_size.width,
-                    height: textures.viewport_size.height,
-                    depth_or_array_layers: 1,
-                },
-            );
-            {
-                let _pass_span = trace_span!("copy_output_encoder_submit").entered();
-                queue.submit(Some(encoder.finish()));
-            }
-        }
+            // Consecutive triangles with the same texture are drawn together
+            let mut batches = vec![];
+            let mut first_vertex = 0;
 
-        if let Some(extra_rendering) = extra_rendering {
-            let mut extra_encoder =
-                device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+            for (texture_key, batch) in &triangles.iter().chunk_by(|(key, _)| *key) {
+                let vertex_count = batch.count() as u32 * 3;
+                let bind_group = self.create_texture_bind_group(graphics_context, texture_key)?;
 
-            extra_rendering(
-                final_view,
-                &mut extra_encoder,
-                &mut self.camera,
-                &mut self.sun_information,
+                batches.push((
+                    texture_key.texture_type,
+                    bind_group,
+                    first_vertex..(first_vertex + vertex_count),
+                ));
+                first_vertex += vertex_count;
+            }
+
+            let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("Translucent render pass"),
+                color_attachments: &[Some(RenderPassColorAttachment {
+                    view: attachment,
+                    resolve_target,
+                    ops: Operations {
+                        load: load_op,
+                        store: wgpu::StoreOp::Store,
+                    },
+                    depth_slice: None,
+                })],
+                depth_stencil_attachment: Some(RenderPassDepthStencilAttachment {
+                    view: &textures.depth_texture.view,
+                    depth_ops: Some(Operations {
+                        load: depth_load_opt,
+                        store: StoreOp::Store,
+                    }),
+                    stencil_ops: None,
+                }),
+                timestamp_writes: None,
+                occlusion_query_set: None,
+                multiview_mask: None,
+            });
+
+            rpass.set_pipeline(translucent_pipeline);
+            rpass.set_bind_group(0, transform_bind_group, &[]);
+            rpass.set_vertex_buffer(0, vertex_buf.slice(..));
+
+            for (texture, bind_group, vertices) in &batches {
+                rpass.set_bind_group(1, bind_group, &[]);
+
+                if !texture.is_emissive() && !texture.is_nametag() {
+                    rpass.set_bind_group(2, sun_bind_group, &[]);
+                } else {
+                    rpass.set_bind_group(2, emissive_sun_bind_group, &[]);
+                }
+
+                rpass.draw(vertices.clone(), 0..1);
+            }
+        }
+
+        if let (Some(strength), false) = (self.glow_strength, glow_parts.is_empty()) {
+            let _pass_span = trace_span!("glow_pass").entered();
+
+            self.render_glow(
+                graphics_context,
+                &mut encoder,
+                textures,
+                &glow_parts,
+                depth_load_opt,
+                &*smaa_frame,
+                strength,
+            )?;
+        }
+
+        {
+            let _pass_span = trace_span!("render_encoder_submit").entered();
+            queue.submit(Some(encoder.finish()));
+        }
+
+        {
+            let _pass_span = trace_span!("smaa_frame_drop").entered();
+
+            // Explicitly drop the smaa frame so that it is resolved before we copy it to the output buffer.
+            drop(smaa_frame);
+        }
+
+        // Surfaces can't be read from, so post-processing only happens when rendering off-screen
+        if let (Some(outline), None) = (outline, &surface_texture) {
+            let _pass_span = trace_span!("outline_pass").entered();
+
+            Self::render_outline(graphics_context, pipelines, textures, &outline);
+        }
+
+        Self::copy_output_to_buffer(graphics_context, textures);
+
+        if let Some(extra_rendering) = extra_rendering {
+            let mut extra_encoder =
+                device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+
+            extra_rendering(
+                final_view,
+                &mut extra_encoder,
+                &mut self.camera,
+                &mut self.sun_information,
             );
 
             queue.submit(Some(extra_encoder.finish()));
@@ -463,19 +1153,606 @@ where
         Ok(())
     }
 
+    /// Renders the given auxiliary pass of the scene straight into the output texture.
+    fn render_aux_pass(
+        &self,
+        graphics_context: &GraphicsContext,
+        aux_pass: SceneAuxPass,
+    ) -> Result<()> {
+        let device = &graphics_context.device;
+
+        let textures = self
+            .scene_context
+            .textures
+            .as_ref()
+            .ok_or(NMSRRenderingError::SceneContextTexturesNotInitialized)?;
+
+        let depth_texture = textures
+            .aux_depth_texture
+            .as_ref()
+            .unwrap_or(&textures.depth_texture);
+
+        // Shadows, glints and name tags aren't part of the model, so they aren't drawn
+        let mut aux_parts =
+            self.computed_body_parts
+                .iter()
+                .zip(&self.computed_body_part_types)
+                .enumerate()
+                .flat_map(|(player, (parts, body_part_types))| {
+                    parts.iter().zip(body_part_types.iter().copied()).map(
+                        move |(part, body_part)| {
+                            let key = SceneTextureKey {
+                                player,
+                                texture_type: part.get_texture(),
+                            };
+
+                            ((key, body_part), part)
+                        },
+                    )
+                })
+                .filter(|((key, _), _)| {
+                    let texture = key.texture_type;
+                    !texture.is_shadow() && !texture.is_glint() && !texture.is_nametag()
+                })
+                .collect::<Vec<_>>();
+        aux_parts.sort_by_key(|(key, _)| *key);
+
+        let mut meshes = vec![];
+
+        for ((texture_key, body_part), parts) in &aux_parts.into_iter().chunk_by(|(key, _)| *key) {
+            let texture_bind_group =
+                self.create_texture_bind_group(graphics_context, texture_key)?;
+
+            let aux = AuxInformation::new(aux_pass, body_part, texture_key.texture_type);
+
+            let aux_buf = device.create_buffer_init(&BufferInitDescriptor {
+                label: Some("Aux Buffer"),
+                contents: bytemuck::cast_slice(&[aux]),
+                usage: wgpu::BufferUsages::UNIFORM,
+            });
+
+            let aux_bind_group = device.create_bind_group(&BindGroupDescriptor {
+                layout: &graphics_context.layouts.aux_bind_group_layout,
+                entries: &[BindGroupEntry {
+                    binding: 0,
+                    resource: aux_buf.as_entire_binding(),
+                }],
+                label: Some("Aux"),
+            });
+
+            let mesh = Mesh::new(parts.map(|(_, part)| primitive_convert(part)).collect());
+            let index_data = mesh.get_indices();
+
+            let vertex_buf = device.create_buffer_init(&BufferInitDescriptor {
+                label: Some("Aux Vertex Buffer"),
+                contents: bytemuck::cast_slice(&mesh.get_vertices()),
+                usage: wgpu::BufferUsages::VERTEX,
+            });
+
+            let index_buf = device.create_buffer_init(&BufferInitDescriptor {
+                label: Some("Aux Index Buffer"),
+                contents: bytemuck::cast_slice(&index_data),
+                usage: wgpu::BufferUsages::INDEX,
+            });
+
+            meshes.push((
+                texture_bind_group,
+                aux_bind_group,
+                vertex_buf,
+                index_buf,
+                index_data.len() as u32,
+            ));
+        }
+
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Aux pass rendering (NMSR)"),
+        });
+
+        {
+            let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some(format!("Aux render pass for {}", aux_pass).as_str()),
+                color_attachments: &[Some(RenderPassColorAttachment {
+                    view: &textures.output_texture.view,
+                    resolve_target: None,
+                    ops: Operations {
+                        load: LoadOp::Clear(Color::TRANSPARENT),
+                        store: StoreOp::Store,
+                    },
+                    depth_slice: None,
+                })],
+                depth_stencil_attachment: Some(RenderPassDepthStencilAttachment {
+                    view: &depth_texture.view,
+                    depth_ops: Some(Operations {
+                        load: LoadOp::Clear(1.0),
+                        store: StoreOp::Discard,
+                    }),
+                    stencil_ops: None,
+                }),
+                timestamp_writes: None,
+                occlusion_query_set: None,
+                multiview_mask: None,
+            });
+
+            rpass.set_pipeline(&graphics_context.aux_pipeline);
+            rpass.set_bind_group(0, &self.scene_context.transform_bind_group, &[]);
+            rpass.set_bind_group(2, &self.scene_context.sun_information_bind_group, &[]);
+
+            for (texture_bind_group, aux_bind_group, vertex_buf, index_buf, index_count) in &meshes
+            {
+                rpass.set_bind_group(1, texture_bind_group, &[]);
+                rpass.set_bind_group(3, aux_bind_group, &[]);
+                rpass.set_index_buffer(index_buf.slice(..), IndexFormat::Uint16);
+                rpass.set_vertex_buffer(0, vertex_buf.slice(..));
+                rpass.draw_indexed(0..*index_count, 0, 0..1);
+            }
+        }
+
+        graphics_context.queue.submit(Some(encoder.finish()));
+
+        Self::copy_output_to_buffer(graphics_context, textures);
+
+        Ok(())
+    }
+
+    /// Draws the given emissive parts into the glow texture, then blurs it on top of the rendered scene.
+    #[allow(clippy::too_many_arguments)]
+    fn render_glow(
+        &self,
+        graphics_context: &GraphicsContext,
+        encoder: &mut CommandEncoder,
+        textures: &SceneContextTextures,
+        glow_parts: &[(SceneTextureKey, &Part)],
+        depth_load_op: LoadOp<f32>,
+        output: &TextureView,
+        strength: f32,
+    ) -> Result<()> {
+        let device = &graphics_context.device;
+
+        let (attachment, resolve_target) =
+            if let Some(multisampled_glow) = &textures.multisampled_glow_texture {
+                (&multisampled_glow.view, Some(&textures.glow_texture.view))
+            } else {
+                (&textures.glow_texture.view, None)
+            };
+
+        let mut meshes = vec![];
+
+        for (texture_key, parts) in &glow_parts.iter().chunk_by(|(key, _)| *key) {
+            let bind_group = self.create_texture_bind_group(graphics_context, texture_key)?;
+
+            let mesh = Mesh::new(parts.map(|&(_, part)| primitive_convert(part)).collect());
+            let index_data = mesh.get_indices();
+
+            let vertex_buf = device.create_buffer_init(&BufferInitDescriptor {
+                label: Some("Glow Vertex Buffer"),
+                contents: bytemuck::cast_slice(&mesh.get_vertices()),
+                usage: wgpu::BufferUsages::VERTEX,
+            });
+
+            let index_buf = device.create_buffer_init(&BufferInitDescriptor {
+                label: Some("Glow Index Buffer"),
+                contents: bytemuck::cast_slice(&index_data),
+                usage: wgpu::BufferUsages::INDEX,
+            });
+
+            meshes.push((bind_group, vertex_buf, index_buf, index_data.len() as u32));
+        }
+
+        {
+            // Whatever covers the emissive parts also covers their glow, so the depth of the scene is kept
+            let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("Glow extract render pass"),
+                color_attachments: &[Some(RenderPassColorAttachment {
+                    view: attachment,
+                    resolve_target,
+                    ops: Operations {
+                        load: LoadOp::Clear(Color::TRANSPARENT),
+                        store: StoreOp::Store,
+                    },
+                    depth_slice: None,
+                })],
+                depth_stencil_attachment: Some(RenderPassDepthStencilAttachment {
+                    view: &textures.depth_texture.view,
+                    depth_ops: Some(Operations {
+                        load: depth_load_op,
+                        store: StoreOp::Store,
+                    }),
+                    stencil_ops: None,
+                }),
+                timestamp_writes: None,
+                occlusion_query_set: None,
+                multiview_mask: None,
+            });
+
+            rpass.set_pipeline(
+                &self
+                    .get_sampled_pipelines(graphics_context)
+                    .glow_extract_pipeline,
+            );
+            rpass.set_bind_group(0, &self.scene_context.transform_bind_group, &[]);
+            rpass.set_bind_group(
+                2,
+                &self.scene_context.emissive_sun_information_bind_group,
+                &[],
+            );
+
+            for (bind_group, vertex_buf, index_buf, index_count) in &meshes {
+                rpass.set_bind_group(1, bind_group, &[]);
+                rpass.set_index_buffer(index_buf.slice(..), IndexFormat::Uint16);
+                rpass.set_vertex_buffer(0, vertex_buf.slice(..));
+                rpass.draw_indexed(0..*index_count, 0, 0..1);
+            }
+        }
+
+        // Tiles are only part of the frame, but the glow has to be as big as in the whole frame
+        let output_height = self
+            .camera
+            .get_size()
+            .map_or(textures.glow_texture.texture.height(), |size| size.height);
+
+        let blur_passes = [
+            (
+                "Glow horizontal blur render pass",
+                &textures.glow_texture.view,
+                &textures.glow_blur_texture.view,
+                &graphics_context.glow_blur_pipeline,
+                GlowInformation::new(Vec2::X, output_height, 1.0),
+            ),
+            (
+                "Glow vertical blur render pass",
+                &textures.glow_blur_texture.view,
+                output,
+                &graphics_context.glow_composite_pipeline,
+                GlowInformation::new(Vec2::Y, output_height, strength),
+            ),
+        ];
+
+        for (label, source, target, pipeline, glow) in blur_passes {
+            let glow_buf = device.create_buffer_init(&BufferInitDescriptor {
+                label: Some("Glow Buffer"),
+                contents: bytemuck::cast_slice(&[glow]),
+                usage: wgpu::BufferUsages::UNIFORM,
+            });
+
+            let glow_bind_group = device.create_bind_group(&BindGroupDescriptor {
+                layout: &graphics_context.layouts.glow_bind_group_layout,
+                entries: &[
+                    BindGroupEntry {
+                        binding: 0,
+                        resource: wgpu::BindingResource::TextureView(source),
+                    },
+                    BindGroupEntry {
+                        binding: 1,
+                        resource: glow_buf.as_entire_binding(),
+                    },
+                ],
+                label: Some("Glow"),
+            });
+
+            let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some(label),
+                color_attachments: &[Some(RenderPassColorAttachment {
+                    view: target,
+                    resolve_target: None,
+                    ops: Operations {
+                        // The horizontal blur covers the whole texture, and the vertical one is added to the scene
+                        load: LoadOp::Load,
+                        store: StoreOp::Store,
+                    },
+                    depth_slice: None,
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: None,
+                occlusion_query_set: None,
+                multiview_mask: None,
+            });
+
+            rpass.set_pipeline(pipeline);
+            rpass.set_bind_group(0, &glow_bind_group, &[]);
+            rpass.draw(0..3, 0..1);
+        }
+
+        Ok(())
+    }
+
+    /// Creates the bind group used to sample one of the textures of the scene.
+    fn create_texture_bind_group(
+        &self,
+        graphics_context: &GraphicsContext,
+        texture_key: SceneTextureKey,
+    ) -> Result<BindGroup> {
+        let device = &graphics_context.device;
+        let texture = texture_key.texture_type;
+
+        let texture_view = &self
+            .textures
+            .get(&texture_key)
+            .ok_or(NMSRRenderingError::SceneContextTextureNotSet(texture))?
+            .view;
+
+        let (filter, mipmap_filter) = if texture.is_shadow() || texture.is_glint() {
+            (FilterMode::Linear, MipmapFilterMode::Linear)
+        } else {
+            (FilterMode::Nearest, MipmapFilterMode::Nearest)
+        };
+
+        // The glint texture is scrolled across the armor, so it needs to wrap around
+        let address_mode = if texture.is_glint() {
+            AddressMode::Repeat
+        } else {
+            AddressMode::ClampToEdge
+        };
+
+        let texture_sampler = device.create_sampler(&SamplerDescriptor {
+            label: Some(texture.into()),
+            address_mode_u: address_mode,
+            address_mode_v: address_mode,
+            address_mode_w: address_mode,
+            mag_filter: filter,
+            min_filter: filter,
+            mipmap_filter: mipmap_filter,
+            lod_min_clamp: 0.0,
+            lod_max_clamp: 0.0,
+            compare: None,
+            anisotropy_clamp: 1,
+            border_color: None,
+        });
+
+        Ok(device.create_bind_group(&BindGroupDescriptor {
+            layout: &graphics_context.layouts.skin_sampler_bind_group_layout,
+            entries: &[
+                BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(texture_view),
+                },
+                BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Sampler(&texture_sampler),
+                },
+            ],
+            label: Some(texture.into()),
+        }))
+    }
+
+    /// Copies the output texture to the output buffer, so that it can be read back.
+    fn copy_output_to_buffer(graphics_context: &GraphicsContext, textures: &SceneContextTextures) {
+        let device = &graphics_context.device;
+        let queue = &graphics_context.queue;
+
+        let _pass_span = trace_span!("copy_texture_to_buffer").entered();
+
+        let mut encoder =
+            device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+
+        encoder.copy_texture_to_buffer(
+            wgpu::TexelCopyTextureInfo {
+                aspect: wgpu::TextureAspect::All,
+                texture: &textures.output_texture.texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+            },
+            wgpu::TexelCopyBufferInfo {
+                buffer: &textures.texture_output_buffer,
+                layout: wgpu::TexelCopyBufferLayout {
+                    offset: 0,
+                    bytes_per_row: Some(
+                        textures
+                            .texture_output_buffer_dimensions
+                            .padded_bytes_per_row,
+                    ),
+                    rows_per_image: None,
+                },
+            },
+            Extent3d {
+                width: textures.viewport_size.width,
+                height: textures.viewport_size.height,
+                depth_or_array_layers: 1,
+            },
+        );
+        {
+            let _pass_span = trace_span!("copy_output_encoder_submit").entered();
+            queue.submit(Some(encoder.finish()));
+        }
+    }
+
+    /// Draws the outline over the output texture, using a copy of it as the source.
+    fn render_outline(
+        graphics_context: &GraphicsContext,
+        pipelines: &GraphicsContextSampledPipelines,
+        textures: &SceneContextTextures,
+        outline: &OutlineInformation,
+    ) {
+        let device = &graphics_context.device;
+
+        let outline_buf = device.create_buffer_init(&BufferInitDescriptor {
+            label: Some("Outline Buffer"),
+            contents: bytemuck::cast_slice(&[*outline]),
+            usage: wgpu::BufferUsages::UNIFORM,
+        });
+
+        let outline_bind_group = device.create_bind_group(&BindGroupDescriptor {
+            layout: &pipelines.outline_bind_group_layout,
+            entries: &[
+                BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(
+                        &textures.post_process_source_texture.view,
+                    ),
+                },
+                BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::TextureView(&textures.depth_texture.view),
+                },
+                BindGroupEntry {
+                    binding: 2,
+                    resource: outline_buf.as_entire_binding(),
+                },
+            ],
+            label: Some("Outline"),
+        });
+
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Outline (NMSR)"),
+        });
+
+        let output_size = textures.output_texture.texture.size();
+
+        encoder.copy_texture_to_texture(
+            textures.output_texture.texture.as_image_copy(),
+            textures.post_process_source_texture.texture.as_image_copy(),
+            output_size,
+        );
+
+        {
+            let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("Outline render pass"),
+                color_attachments: &[Some(RenderPassColorAttachment {
+                    view: &textures.output_texture.view,
+                    resolve_target: None,
+                    ops: Operations {
+                        load: LoadOp::Load,
+                        store: StoreOp::Store,
+                    },
+                    depth_slice: None,
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: None,
+                occlusion_query_set: None,
+                multiview_mask: None,
+            });
+
+            rpass.set_pipeline(&pipelines.outline_pipeline);
+            rpass.set_bind_group(0, &outline_bind_group, &[]);
+            rpass.draw(0..3, 0..1);
+        }
+
+        graphics_context.queue.submit(Some(encoder.finish()));
+    }
+
+    /// Renders the scene and copies its output like [`Scene::copy_output_texture`], in tiles of at most `max_tile_size` pixels on each side.
+    ///
+    /// Each tile is rendered with a margin around it that is thrown away, so that post-processing (the outline, the glow and SMAA)
+    /// doesn't leave seams between tiles. This is how outputs bigger than the textures of a single render can be rendered.
+    pub async fn render_in_tiles(
+        &mut self,
+        graphics_context: &GraphicsContext<'_>,
+        max_tile_size: u32,
+        cleanup_alpha: bool,
+    ) -> Result<Vec<u8>> {
+        let frame_size = self.viewport_size;
+
+        if frame_size.width.max(frame_size.height) <= max_tile_size {
+            self.render(graphics_context)?;
+
+            return self
+                .copy_output_texture(graphics_context, cleanup_alpha)
+                .await;
+        }
+
+        let tiling = SceneTiling::new(
+            max_tile_size,
+            self.get_tile_margin(),
+            self.scene_context.get_supersampling_factor(),
+        )?;
+
+        let mut output = tiling.create_output(frame_size);
+
+        for tile in tiling.get_tiles(frame_size) {
+            self.camera.set_tile(Some(tile));
+            self.viewport_size = Size {
+                width: tile.width,
+                height: tile.height,
+            };
+
+            self.update(graphics_context);
+            self.render(graphics_context)?;
+
+            let pixels = self.copy_output_texture(graphics_context, false).await?;
+
+            tiling.stitch(&mut output, frame_size, tile, &pixels);
+        }
+
+        self.camera.set_tile(None);
+        self.viewport_size = frame_size;
+        self.update(graphics_context);
+
+        if cleanup_alpha {
+            unmultiply_alpha(&mut output);
+        }
+
+        Ok(output)
+    }
+
+    /// How far past its edges a tile has to be rendered for post-processing to look the same as without tiles.
+    fn get_tile_margin(&self) -> u32 {
+        // SMAA only looks a few pixels away from each edge
+        const SMAA_MARGIN: f32 = 16.0;
+
+        let supersampling_factor = self.scene_context.get_supersampling_factor() as f32;
+        let frame_height = self.camera.get_size().unwrap_or(self.viewport_size).height;
+
+        let outline = self
+            .outline
+            .map_or(0.0, |outline| outline.width * supersampling_factor);
+        let glow = self
+            .glow_strength
+            .map_or(0.0, |_| GlowInformation::get_radius(frame_height));
+
+        SMAA_MARGIN.max(outline).max(glow).ceil() as u32
+    }
+
+    /// Returns the pipelines that match how the textures of this scene are multisampled.
+    fn get_sampled_pipelines<'g>(
+        &self,
+        graphics_context: &'g GraphicsContext,
+    ) -> &'g GraphicsContextSampledPipelines {
+        let sample_count = self
+            .scene_context
+            .get_multisampling_strategy(graphics_context)
+            .get_msaa_sample_count();
+
+        graphics_context.get_sampled_pipelines(sample_count)
+    }
+
     pub async fn copy_output_texture(
         &self,
         graphics_context: &GraphicsContext<'_>,
         cleanup_alpha: bool,
     ) -> Result<Vec<u8>> {
-        self.scene_context
-            .copy_output_texture(graphics_context, cleanup_alpha)
-            .await
+        let supersampling_factor = self.scene_context.get_supersampling_factor();
+
+        if supersampling_factor == 1 {
+            return self
+                .scene_context
+                .copy_output_texture(graphics_context, cleanup_alpha)
+                .await;
+        }
+
+        // The output is still premultiplied here, which is what makes averaging its pixels correct
+        let bytes = self
+            .scene_context
+            .copy_output_texture(graphics_context, false)
+            .await?;
+
+        // Auxiliary passes hold data instead of colors, which can't be averaged
+        let mut bytes = downsample(
+            &bytes,
+            self.viewport_size,
+            supersampling_factor,
+            self.aux_pass.is_none(),
+        );
+
+        if cleanup_alpha {
+            unmultiply_alpha(&mut bytes);
+        }
+
+        Ok(bytes)
     }
 
     fn update_scene_context(
         camera: &mut Camera,
         sun: &SunInformation,
+        glint: &GlintInformation,
         viewport_size: Size,
         scene_context: &mut SceneContext,
         graphics_context: &GraphicsContext,
@@ -484,27 +1761,81 @@ where
             camera.set_size(Some(viewport_size));
         }
 
-        scene_context.init(graphics_context, camera, sun, viewport_size);
+        scene_context.init(graphics_context, camera, sun, glint);
     }
 
     pub fn update(&mut self, graphics_context: &GraphicsContext) {
         Self::update_scene_context(
             &mut self.camera,
             &self.sun_information,
+            &self.glint_information,
             self.viewport_size,
             &mut self.scene_context,
             graphics_context,
         );
     }
 
-    pub fn rebuild_parts(
+    pub fn rebuild_parts<M: ArmorMaterial>(
+        &mut self,
+        part_context: &PlayerPartProviderContext<M>,
+        body_parts: &[PlayerBodyPartType],
+    ) -> &[Part] {
+        self.rebuild_player_parts(0, part_context, body_parts)
+    }
+
+    /// Recomputes the parts of a player in the scene, keeping the player where it was placed.
+    pub fn rebuild_player_parts<M: ArmorMaterial>(
         &mut self,
-        part_context: &PlayerPartProviderContext,
+        player: usize,
+        part_context: &PlayerPartProviderContext<M>,
         body_parts: &[PlayerBodyPartType],
     ) -> &[Part] {
-        self.computed_body_parts = Self::collect_player_parts(part_context, body_parts);
+        let (mut body_part_types, mut parts): (Vec<_>, Vec<_>) =
+            Self::collect_player_parts(part_context, body_parts)
+                .into_iter()
+                .unzip();
+
+        // The shadow is cast by the parts themselves, so it's computed from them instead of using a fixed texture
+        if let Some(index) = parts.iter().position(|part| part.get_texture().is_shadow()) {
+            let ground_y = parts[index].get_position().y;
+            let shadow = GroundShadow::compute(&parts, ground_y, self.sun_information.direction);
+
+            match shadow {
+                Some(shadow) => {
+                    parts[index] = shadow.part;
+                    self.pending_shadow_textures.insert(player, shadow.texture);
+                }
+                None => {
+                    parts.remove(index);
+                    body_part_types.remove(index);
+                }
+            }
+        }
+
+        if let Some(nametag) = self.player_nametags[player] {
+            let player_top = parts
+                .iter()
+                .filter(|part| !part.get_texture().is_shadow())
+                .flat_map(|part| primitive_convert(part).get_vertices())
+                .map(|vertex| vertex.position.y)
+                .fold(0.0, f32::max);
 
-        self.parts()
+            parts.push(nametag.create_part(player_top, &self.camera));
+            body_part_types.push(None);
+        }
+
+        let offset = self.player_offsets[player];
+
+        if offset != Vec3::ZERO {
+            for part in &mut parts {
+                part.translate(offset);
+            }
+        }
+
+        self.computed_body_parts[player] = parts;
+        self.computed_body_part_types[player] = body_part_types;
+
+        self.player_parts(player)
     }
 }
 
@@ -517,3 +1848,191 @@ fn get_successful_surface_texture(
         _ => None,
     }
 }
+
+/// How a frame is split into square tiles that are rendered on their own, see [`Scene::render_in_tiles`].
+#[derive(Debug, Copy, Clone)]
+struct SceneTiling {
+    /// How many pixels of each tile end up in the frame, on each side.
+    tile_size: u32,
+    /// How many pixels are rendered (and thrown away) around each tile.
+    margin: u32,
+    supersampling_factor: u32,
+}
+
+impl SceneTiling {
+    fn new(max_tile_size: u32, margin: u32, supersampling_factor: u32) -> Result<Self> {
+        // Supersampled tiles are scaled down on their own, so they have to be made of whole blocks of pixels
+        let margin = margin.next_multiple_of(supersampling_factor);
+        let tile_size =
+            max_tile_size.saturating_sub(margin * 2) / supersampling_factor * supersampling_factor;
+
+        if tile_size == 0 {
+            return Err(NMSRRenderingError::TileTooSmall(max_tile_size, margin));
+        }
+
+        Ok(Self {
+            tile_size,
+            margin,
+            supersampling_factor,
+        })
+    }
+
+    /// Returns the size of the scaled down frame.
+    fn get_output_size(&self, frame_size: Size) -> (usize, usize) {
+        (
+            (frame_size.width / self.supersampling_factor) as usize,
+            (frame_size.height / self.supersampling_factor) as usize,
+        )
+    }
+
+    fn create_output(&self, frame_size: Size) -> Vec<u8> {
+        let (width, height) = self.get_output_size(frame_size);
+
+        vec![0; width * height * 4]
+    }
+
+    /// Returns the tiles to render for the given frame, row by row, margins included.
+    fn get_tiles(&self, frame_size: Size) -> impl Iterator<Item = CameraTile> {
+        let Self {
+            tile_size, margin, ..
+        } = *self;
+
+        (0..frame_size.height)
+            .step_by(tile_size as usize)
+            .flat_map(move |y| {
+                (0..frame_size.width)
+                    .step_by(tile_size as usize)
+                    .map(move |x| CameraTile {
+                        x: x as i32 - margin as i32,
+                        y: y as i32 - margin as i32,
+                        width: tile_size + margin * 2,
+                        height: tile_size + margin * 2,
+                    })
+            })
+    }
+
+    /// Copies what's inside the margin of a rendered (and scaled down) tile into its place in the output.
+    fn stitch(&self, output: &mut [u8], frame_size: Size, tile: CameraTile, pixels: &[u8]) {
+        let factor = self.supersampling_factor;
+        let (output_width, output_height) = self.get_output_size(frame_size);
+
+        let tile_width = (tile.width / factor) as usize;
+        let tile_margin = (self.margin / factor) as usize;
+        let tile_x = ((tile.x + self.margin as i32) as u32 / factor) as usize;
+        let tile_y = ((tile.y + self.margin as i32) as u32 / factor) as usize;
+
+        // Tiles on the right and bottom edges can go past the output
+        let kept_size = (self.tile_size / factor) as usize;
+        let kept_width = kept_size.min(output_width - tile_x) * 4;
+        let kept_height = kept_size.min(output_height - tile_y);
+
+        for row in 0..kept_height {
+            let source = ((row + tile_margin) * tile_width + tile_margin) * 4;
+            let destination = ((tile_y + row) * output_width + tile_x) * 4;
+
+            output[destination..][..kept_width].copy_from_slice(&pixels[source..][..kept_width]);
+        }
+    }
+}
+
+/// Sorts triangles from the furthest to the closest one to the camera, using the depth of their centroid.
+fn sort_back_to_front<K>(
+    triangles: impl IntoIterator<Item = (K, [Vertex; 3])>,
+    view_projection: Mat4,
+) -> Vec<(K, [Vertex; 3])> {
+    let mut triangles = triangles
+        .into_iter()
+        .map(|(key, triangle)| {
+            let centroid = triangle.iter().map(|vertex| vertex.position).sum::<Vec3>() / 3.0;
+
+            (view_projection.project_point3(centroid).z, key, triangle)
+        })
+        .collect::<Vec<_>>();
+
+    triangles.sort_by(|(a, _, _), (b, _, _)| b.total_cmp(a));
+
+    triangles
+        .into_iter()
+        .map(|(_, key, triangle)| (key, triangle))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tiled_output_matches_untiled_output() {
+        for supersampling_factor in [1, 2, 4] {
+            let frame_size = Size {
+                width: 37 * supersampling_factor,
+                height: 23 * supersampling_factor,
+            };
+
+            let tiling = SceneTiling::new(16, 3, supersampling_factor).unwrap();
+            let (output_width, output_height) = tiling.get_output_size(frame_size);
+
+            // Every pixel of the untiled output is different from the others
+            let pixel = |x: i32, y: i32| (x * 7 + y * 131) as u32;
+            let untiled = (0..output_height as i32)
+                .flat_map(|y| (0..output_width as i32).map(move |x| pixel(x, y)))
+                .flat_map(u32::to_le_bytes)
+                .collect::<Vec<_>>();
+
+            let mut tiled = tiling.create_output(frame_size);
+
+            for tile in tiling.get_tiles(frame_size) {
+                let factor = supersampling_factor as i32;
+                let (width, height) = (tile.width as i32 / factor, tile.height as i32 / factor);
+
+                // A rendered tile shows what the frame has at its position, even past the frame's edges
+                let rendered = (0..height)
+                    .flat_map(|y| {
+                        (0..width).map(move |x| pixel(tile.x / factor + x, tile.y / factor + y))
+                    })
+                    .flat_map(u32::to_le_bytes)
+                    .collect::<Vec<_>>();
+
+                tiling.stitch(&mut tiled, frame_size, tile, &rendered);
+            }
+
+            assert_eq!(
+                tiled, untiled,
+                "supersampling factor {supersampling_factor}"
+            );
+        }
+    }
+
+    #[test]
+    fn translucent_triangles_are_sorted_back_to_front() {
+        let triangle_at = |z: f32| {
+            [(-1.0, -1.0), (1.0, -1.0), (0.0, 1.0)]
+                .map(|(x, y)| Vertex::new(Vec3::new(x, y, z), Vec2::ZERO, Vec3::Z))
+        };
+
+        let view_projection = Mat4::perspective_rh(std::f32::consts::FRAC_PI_4, 1.0, 0.1, 100.0)
+            * Mat4::look_at_rh(Vec3::new(0.0, 0.0, 10.0), Vec3::ZERO, Vec3::Y);
+
+        let triangles = [
+            ("middle", triangle_at(0.0)),
+            ("front", triangle_at(5.0)),
+            ("back", triangle_at(-5.0)),
+        ];
+
+        let sorted = sort_back_to_front(triangles, view_projection)
+            .into_iter()
+            .map(|(key, triangle)| (key, triangle[0].position.z))
+            .collect::<Vec<_>>();
+
+        assert_eq!(sorted, [("back", -5.0), ("middle", 0.0), ("front", 5.0)]);
+    }
+
+    #[test]
+    fn glint_scrolls_between_ticks() {
+        let first = GlintInformation::new(0.0).texture_matrix;
+        let second = GlintInformation::new(1.0).texture_matrix;
+
+        assert_ne!(first, second);
+        assert_eq!(first, GlintInformation::default().texture_matrix);
+    }
+}
@@ -1,5 +1,5 @@
 use super::{
-    scene::{Size, SunInformation},
+    scene::{GlintInformation, SceneAntiAliasing, Size, SunInformation},
     textures::{
         create_texture, premultiply_alpha, BufferDimensions, SceneContextTextures, SceneTexture,
     },
@@ -8,7 +8,7 @@ use crate::{
     errors::{NMSRRenderingError, Result},
     high_level::{
         camera::Camera,
-        pipeline::graphics_context::GraphicsContext,
+        pipeline::graphics_context::{GraphicsContext, MultiSamplingStrategy},
         utils::buffer::{create_buffer_and_bind_group, read_buffer},
     },
 };
@@ -31,9 +31,13 @@ pub struct SceneContext {
     pub sun_information_bind_group: BindGroup,
     pub emissive_sun_information_buffer: Buffer,
     pub emissive_sun_information_bind_group: BindGroup,
+    pub glint_information_buffer: Buffer,
+    pub glint_information_bind_group: BindGroup,
     pub(crate) textures: Option<SceneContextTextures>,
     #[debug(skip)]
     pub(crate) smaa_target: Option<SmaaTarget>,
+    /// The anti-aliasing this scene context renders with, if not the one of the graphics context.
+    pub(crate) anti_aliasing: Option<SceneAntiAliasing>,
 }
 
 #[derive(Deref, DerefMut, From)]
@@ -41,6 +45,13 @@ pub struct SceneContextWrapper(SceneContext);
 
 impl SceneContext {
     pub fn new(context: &GraphicsContext) -> Self {
+        Self::new_with_anti_aliasing(context, None)
+    }
+
+    pub fn new_with_anti_aliasing(
+        context: &GraphicsContext,
+        anti_aliasing: Option<SceneAntiAliasing>,
+    ) -> Self {
         let device = &context.device;
 
         let (transform_matrix_buffer, transform_bind_group) = create_buffer_and_bind_group(
@@ -65,6 +76,13 @@ impl SceneContext {
                 &[SunInformation::new([0.0; 3].into(), 0.0, 1.0f32)],
             );
 
+        let (glint_information_buffer, glint_information_bind_group) = create_buffer_and_bind_group(
+            device,
+            "Glint",
+            &context.layouts.glint_bind_group_layout,
+            &[GlintInformation::default()],
+        );
+
         Self {
             transform_bind_group,
             transform_matrix_buffer,
@@ -72,11 +90,29 @@ impl SceneContext {
             sun_information_bind_group,
             emissive_sun_information_buffer,
             emissive_sun_information_bind_group,
+            glint_information_buffer,
+            glint_information_bind_group,
             textures: None,
             smaa_target: None,
+            anti_aliasing,
         }
     }
 
+    pub(crate) fn get_multisampling_strategy(
+        &self,
+        graphics_context: &GraphicsContext,
+    ) -> MultiSamplingStrategy {
+        self.anti_aliasing.map_or(
+            graphics_context.multisampling_strategy,
+            SceneAntiAliasing::get_multisampling_strategy,
+        )
+    }
+
+    pub(crate) fn get_supersampling_factor(&self) -> u32 {
+        self.anti_aliasing
+            .map_or(1, SceneAntiAliasing::get_supersampling_factor)
+    }
+
     fn set_camera_parameters(&self, context: &GraphicsContext, camera: &mut Camera) {
         let matrix = camera.get_view_projection_matrix();
         context.queue.write_buffer(
@@ -94,13 +130,25 @@ impl SceneContext {
             .write_buffer(&self.sun_information_buffer, 0, data);
     }
 
-    #[instrument(skip(self, graphics_context, camera, sun, viewport_size))]
+    fn set_glint_information(
+        &self,
+        context: &GraphicsContext,
+        glint_information: &GlintInformation,
+    ) {
+        let binding = [*glint_information];
+        let data = bytemuck::cast_slice(&binding);
+        context
+            .queue
+            .write_buffer(&self.glint_information_buffer, 0, data);
+    }
+
+    #[instrument(skip(self, graphics_context, camera, sun, glint))]
     pub(crate) fn init(
         &mut self,
         graphics_context: &GraphicsContext,
         camera: &mut Camera,
         sun: &SunInformation,
-        viewport_size: Size,
+        glint: &GlintInformation,
     ) {
         // Setup camera matrix
         self.set_camera_parameters(graphics_context, camera);
@@ -108,11 +156,22 @@ impl SceneContext {
         // Setup sun information
         self.set_sun_information(graphics_context, sun);
 
-        let camera_size = camera.get_size().unwrap_or_default();
+        // Setup glint information
+        self.set_glint_information(graphics_context, glint);
+    }
 
-        let msaa_sample_count = graphics_context
-            .multisampling_strategy
-            .get_msaa_sample_count();
+    /// Makes sure the textures are as big as what the camera renders, and the output buffer as big as the viewport.
+    ///
+    /// This only happens right before rendering, so that the textures are never created for a frame that is rendered in tiles.
+    #[instrument(skip(self, graphics_context))]
+    pub(crate) fn prepare_textures(
+        &mut self,
+        graphics_context: &GraphicsContext,
+        camera_size: Size,
+        viewport_size: Size,
+    ) {
+        let multisampling_strategy = self.get_multisampling_strategy(graphics_context);
+        let msaa_sample_count = multisampling_strategy.get_msaa_sample_count();
 
         let needs_texture_resize = self
             .textures
@@ -161,7 +220,7 @@ impl SceneContext {
                 camera_size.width,
                 camera_size.height,
                 GraphicsContext::DEPTH_TEXTURE_FORMAT,
-                TextureUsages::RENDER_ATTACHMENT,
+                TextureUsages::RENDER_ATTACHMENT | TextureUsages::TEXTURE_BINDING,
                 Some("Depth Texture"),
                 msaa_sample_count,
             );
@@ -192,6 +251,59 @@ impl SceneContext {
                 1,
             );
 
+            let post_process_source_texture = create_texture(
+                graphics_context,
+                camera_size.width,
+                camera_size.height,
+                graphics_context.texture_format,
+                TextureUsages::TEXTURE_BINDING | TextureUsages::COPY_DST,
+                Some("Post-Processing Source Texture"),
+                1,
+            );
+
+            let create_glow_texture = |label: &str| {
+                create_texture(
+                    graphics_context,
+                    camera_size.width,
+                    camera_size.height,
+                    graphics_context.texture_format,
+                    TextureUsages::RENDER_ATTACHMENT | TextureUsages::TEXTURE_BINDING,
+                    Some(label),
+                    1,
+                )
+            };
+
+            let glow_texture = create_glow_texture("Glow Texture");
+            let glow_blur_texture = create_glow_texture("Glow Blur Texture");
+
+            let multisampled_glow_texture = if msaa_sample_count > 1 {
+                Some(create_texture(
+                    graphics_context,
+                    camera_size.width,
+                    camera_size.height,
+                    graphics_context.texture_format,
+                    TextureUsages::RENDER_ATTACHMENT,
+                    Some("MultiSampled Glow Texture"),
+                    msaa_sample_count,
+                ))
+            } else {
+                None
+            };
+
+            let aux_depth_texture = if msaa_sample_count > 1 {
+                Some(create_texture(
+                    graphics_context,
+                    camera_size.width,
+                    camera_size.height,
+                    GraphicsContext::DEPTH_TEXTURE_FORMAT,
+                    TextureUsages::RENDER_ATTACHMENT,
+                    Some("Aux Depth Texture"),
+                    1,
+                ))
+            } else {
+                None
+            };
+
             if let Some(target) = self.smaa_target.as_mut() {
                 let _guard = trace_span!("resize_smaa_target").entered();
                 target.resize(
@@ -207,7 +319,7 @@ impl SceneContext {
                     camera_size.width,
                     camera_size.height,
                     graphics_context.texture_format,
-                    graphics_context.multisampling_strategy.get_smaa_mode(),
+                    multisampling_strategy.get_smaa_mode(),
                 );
 
                 self.smaa_target.replace(smaa_target);
@@ -220,7 +332,12 @@ impl SceneContext {
                 self.textures = Some(SceneContextTextures {
                     depth_texture,
                     output_texture,
+                    post_process_source_texture,
                     multisampled_output_texture,
+                    glow_texture,
+                    glow_blur_texture,
+                    multisampled_glow_texture,
+                    aux_depth_texture,
                     texture_output_buffer,
                     camera_size,
                     viewport_size,
@@ -14,6 +14,11 @@ struct SunInformation {
     direction: vec3<f32>,
     intensity: f32,
     ambient: f32,
+    shading: u32,
+    color: vec3<f32>,
+    fill_intensity: f32,
+    fill_direction: vec3<f32>,
+    fill_color: vec3<f32>,
 }
 
 @group(0)
@@ -32,6 +37,14 @@ var texture_sampler: sampler;
 @binding(0)
 var<uniform> sun: SunInformation;
 
+struct GlintInformation {
+    texture_matrix: mat4x4<f32>,
+}
+
+@group(3)
+@binding(0)
+var<uniform> glint: GlintInformation;
+
 @vertex
 fn vs_main(
     vertex: VertexInput,
@@ -44,16 +57,56 @@ fn vs_main(
 }
 const MAX_LIGHT: f32 = 1.0;
 
+// These match the SceneShading enum
+const SHADING_LAMBERT: u32 = 0u;
+const SHADING_FLAT: u32 = 1u;
+const SHADING_TOON: u32 = 2u;
+const SHADING_MINECRAFT: u32 = 3u;
+
+const TOON_BANDS: f32 = 3.0;
+
+fn compute_key_light(normal: vec3<f32>) -> f32 {
+    if (sun.shading == SHADING_FLAT) {
+        return MAX_LIGHT;
+    }
+    
+    if (sun.shading == SHADING_MINECRAFT) {
+        // Blocks are lit per face in game, no matter where the sun is (rotated faces blend between their axes)
+        var vertical_light: f32 = select(0.5, 1.0, normal.y > 0.0);
+        var axis_weights: vec3<f32> = normal * normal;
+        
+        return dot(axis_weights, vec3<f32>(0.6, vertical_light, 0.8));
+    }
+    
+    var sun_direction: vec3<f32> = normalize(sun.direction);
+    var sun_dot: f32 = dot(normal, -sun_direction);
+    var light: f32 = clamp(sun.intensity * sun_dot, sun.ambient, MAX_LIGHT);
+    
+    if (sun.shading == SHADING_TOON && sun.ambient < MAX_LIGHT) {
+        // Split the light between the ambient light and the full light into a few flat bands
+        var amount: f32 = (light - sun.ambient) / (MAX_LIGHT - sun.ambient);
+        var band: f32 = min(floor(amount * TOON_BANDS) / (TOON_BANDS - 1.0), 1.0);
+        
+        return mix(sun.ambient, MAX_LIGHT, band);
+    }
+    
+    return light;
+}
+
 fn compute_sun_lighting(
     color: vec4<f32>,
     normal: vec3<f32>,
 ) -> vec4<f32> {
-    var sun_direction: vec3<f32> = normalize(sun.direction);
-    var sun_dot: f32 = dot(normal, -sun_direction);
+    var sun_color: vec3<f32> = sun.color * compute_key_light(normal);
     
-    var sun_color: vec3<f32> = vec3<f32>(1.0, 1.0, 1.0) * clamp(sun.intensity * sun_dot, sun.ambient, MAX_LIGHT);
+    // The fill light only brightens the faces it shines on (and is skipped when there's none, since it has no direction)
+    if (sun.fill_intensity > 0.0) {
+        var fill_dot: f32 = dot(normal, -normalize(sun.fill_direction));
+        
+        sun_color += sun.fill_color * max(sun.fill_intensity * fill_dot, 0.0);
+    }
     
-    return color * vec4<f32>(sun_color, 1.0);
+    return color * vec4<f32>(min(sun_color, vec3<f32>(MAX_LIGHT)), 1.0);
 }
 
 @fragment
@@ -69,4 +122,120 @@ fn fs_main(vertex: VertexOutput) -> @location(0) vec4<f32> {
     }
     
     return compute_sun_lighting(color, vertex.normal);
-}
\ No newline at end of file
+}
+
+// Textures with semi-transparent pixels are drawn twice: first their opaque pixels, like any other texture,
+// and then their semi-transparent pixels, sorted back to front so that they blend against what's behind them.
+@fragment
+fn fs_opaque(vertex: VertexOutput) -> @location(0) vec4<f32> {
+    var color: vec4<f32> = textureSample(
+        texture,
+        texture_sampler,
+        vec2<f32>(vertex.tex_coord)
+    );
+    
+    if (color.a < 1.0) {
+        discard;
+    }
+    
+    return compute_sun_lighting(color, vertex.normal);
+}
+
+@fragment
+fn fs_translucent(vertex: VertexOutput) -> @location(0) vec4<f32> {
+    var color: vec4<f32> = textureSample(
+        texture,
+        texture_sampler,
+        vec2<f32>(vertex.tex_coord)
+    );
+    
+    if (color.a == 0.0 || color.a >= 1.0) {
+        discard;
+    }
+    
+    return compute_sun_lighting(color, vertex.normal);
+}
+
+@fragment
+fn fs_glint(vertex: VertexOutput) -> @location(0) vec4<f32> {
+    var glint_coord: vec4<f32> = glint.texture_matrix * vec4<f32>(vertex.tex_coord, 0.0, 1.0);
+
+    // The glint is emissive, so it isn't affected by the sun
+    return textureSample(
+        texture,
+        texture_sampler,
+        glint_coord.xy
+    );
+}
+// These match the SceneAuxPass enum
+const AUX_PASS_DEPTH: u32 = 0u;
+const AUX_PASS_NORMAL: u32 = 1u;
+const AUX_PASS_PART_ID: u32 = 2u;
+const AUX_PASS_UV: u32 = 3u;
+
+struct AuxInformation {
+    pass: u32,
+    // The index of the body part being drawn plus one, or zero if it doesn't belong to one
+    part_id: u32,
+    // Whether the skin is being drawn, since the UV pass only writes the texels of the skin
+    is_skin: u32,
+}
+
+@group(3)
+@binding(0)
+var<uniform> aux: AuxInformation;
+
+// Writes information about the drawn parts instead of their color, every covered pixel is fully opaque
+@fragment
+fn fs_aux(vertex: VertexOutput) -> @location(0) vec4<f32> {
+    var color: vec4<f32> = textureSample(
+        texture,
+        texture_sampler,
+        vec2<f32>(vertex.tex_coord)
+    );
+    
+    if (color.a == 0.0) {
+        discard;
+    }
+    
+    if (aux.pass == AUX_PASS_DEPTH) {
+        // The depth is split across the red, green and blue channels (most significant bits first)
+        var depth: u32 = u32(round(clamp(vertex.position.z, 0.0, 1.0) * 16777215.0));
+        
+        return vec4<f32>(
+            f32((depth >> 16u) & 0xFFu),
+            f32((depth >> 8u) & 0xFFu),
+            f32(depth & 0xFFu),
+            255.0
+        ) / 255.0;
+    }
+    
+    if (aux.pass == AUX_PASS_NORMAL) {
+        return vec4<f32>(normalize(vertex.normal) * 0.5 + 0.5, 1.0);
+    }
+    
+    if (aux.pass == AUX_PASS_PART_ID) {
+        return vec4<f32>(f32(aux.part_id) / 255.0, 0.0, 0.0, 1.0);
+    }
+    
+    // Anything in front of the skin hides it, without telling which texel it is
+    if (aux.is_skin == 0u) {
+        return vec4<f32>(0.0);
+    }
+    
+    // Packed like the UV maps of nmsr-lib: 6 bits for each of u and v, 8 bits of shading and 12 bits of depth
+    // (the depth goes up the closer the pixel is to the camera)
+    var u: u32 = min(u32(floor(vertex.tex_coord.x * 64.0)), 63u);
+    var v: u32 = min(u32(floor(vertex.tex_coord.y * 64.0)), 63u);
+    var shading: u32 = u32(compute_key_light(vertex.normal) * 255.0);
+    var depth: u32 = u32((1.0 - clamp(vertex.position.z, 0.0, 1.0)) * 4095.0);
+    
+    var packed: u32 = ((depth & 0xFFFu) << 20u) | ((shading & 0xFFu) << 12u) | ((v & 0x3Fu) << 6u) | (u & 0x3Fu);
+    
+    return vec4<f32>(
+        f32(packed & 0xFFu),
+        f32((packed >> 8u) & 0xFFu),
+        f32((packed >> 16u) & 0xFFu),
+        f32((packed >> 24u) & 0xFFu)
+    ) / 255.0;
+}
@@ -0,0 +1,230 @@
+use glam::{Vec2, Vec3};
+use image::{Rgba, RgbaImage};
+use nmsr_player_parts::{
+    parts::{part::Part, uv::uv_from_pos_and_size},
+    types::PlayerPartTextureType,
+};
+
+use crate::{
+    high_level::utils::parts::primitive_convert,
+    low_level::primitives::part_primitive::PartPrimitive,
+};
+
+/// A shadow cast on the ground by the parts of a player, as seen from the light.
+pub(crate) struct GroundShadow {
+    /// The quad the shadow is drawn on, which covers everything the parts cast a shadow on.
+    pub(crate) part: Part,
+    pub(crate) texture: RgbaImage,
+}
+
+impl GroundShadow {
+    /// How steep the light can be before it's straightened, so that shadows don't stretch out too far.
+    const MAX_SLOPE: f32 = 0.5;
+
+    /// How far the shadow is blurred, in skin pixels.
+    const BLUR_RADIUS: f32 = 1.5;
+
+    /// How many box blurs are used to approximate a gaussian blur.
+    const BLUR_PASSES: usize = 3;
+
+    /// Computes the shadow the given parts cast on the ground, with the light shining towards the given direction.
+    ///
+    /// Returns [`None`] if there's nothing to cast a shadow.
+    pub(crate) fn compute(parts: &[Part], ground_y: f32, light_direction: Vec3) -> Option<Self> {
+        let slope = Self::get_slope(light_direction);
+
+        // Move every vertex along the light until it reaches the ground
+        let triangles = parts
+            .iter()
+            .filter(|part| {
+                let texture = part.get_texture();
+                !texture.is_shadow() && !texture.is_nametag() && !texture.is_glint()
+            })
+            .flat_map(|part| primitive_convert(part).get_vertices_grouped())
+            .map(|triangle| {
+                triangle.map(|vertex| {
+                    let height = (vertex.position.y - ground_y).max(0.0);
+
+                    Vec2::new(vertex.position.x, vertex.position.z) + slope * height
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let (min, max) = triangles.iter().flatten().fold(
+            (Vec2::splat(f32::INFINITY), Vec2::splat(f32::NEG_INFINITY)),
+            |(min, max), &point| (min.min(point), max.max(point)),
+        );
+
+        if triangles.is_empty() || !min.is_finite() || !max.is_finite() {
+            return None;
+        }
+
+        // Leave room for the blur around the shadow, and snap the quad to whole pixels
+        let margin = Self::BLUR_RADIUS * 2.0;
+        let min = (min - margin).floor();
+        let max = (max + margin).ceil();
+        let size = (max - min).max(Vec2::ONE);
+
+        let (width, height) = PlayerPartTextureType::Shadow.get_texture_size();
+        let texel_size = size / Vec2::new(width as f32, height as f32);
+
+        let mut coverage = vec![0.0f32; (width * height) as usize];
+
+        for triangle in &triangles {
+            let [a, b, c] = triangle.map(|point| (point - min) / texel_size);
+            Self::rasterize(&mut coverage, width, height, a, b, c);
+        }
+
+        let radius = (Self::BLUR_RADIUS / texel_size.max_element() / Self::BLUR_PASSES as f32)
+            .round()
+            .max(1.0) as usize;
+
+        for _ in 0..Self::BLUR_PASSES {
+            Self::box_blur(&mut coverage, width, height, radius, true);
+            Self::box_blur(&mut coverage, width, height, radius, false);
+        }
+
+        let texture = RgbaImage::from_fn(width, height, |x, y| {
+            let alpha = coverage[(y * width + x) as usize].clamp(0.0, 1.0);
+
+            Rgba([0, 0, 0, (alpha * 255.0).round() as u8])
+        });
+
+        let part = Part::new_quad(
+            PlayerPartTextureType::Shadow,
+            [min.x, ground_y, min.y],
+            [size.x as u32, 0, size.y as u32],
+            uv_from_pos_and_size(0, 0, width as u16, height as u16),
+            Vec3::Y,
+            #[cfg(feature = "part_tracker")]
+            Some("Shadow".to_string()),
+        );
+
+        Some(Self { part, texture })
+    }
+
+    /// Returns how far sideways the shadow moves for every pixel of height.
+    fn get_slope(light_direction: Vec3) -> Vec2 {
+        // Light that doesn't shine down casts the shadow straight down
+        if !light_direction.is_finite() || light_direction.y >= -f32::EPSILON {
+            return Vec2::ZERO;
+        }
+
+        let slope = Vec2::new(light_direction.x, light_direction.z) / -light_direction.y;
+
+        slope.clamp_length_max(Self::MAX_SLOPE)
+    }
+
+    /// Marks the texels whose center is inside the given triangle (in texel coordinates) as covered.
+    fn rasterize(coverage: &mut [f32], width: u32, height: u32, a: Vec2, b: Vec2, c: Vec2) {
+        let area = (b - a).perp_dot(c - a);
+
+        if area.abs() <= f32::EPSILON {
+            return;
+        }
+
+        let min = a.min(b).min(c).floor().max(Vec2::ZERO);
+        let max = a
+            .max(b)
+            .max(c)
+            .ceil()
+            .min(Vec2::new(width as f32, height as f32));
+
+        for y in (min.y as u32)..(max.y as u32) {
+            for x in (min.x as u32)..(max.x as u32) {
+                let point = Vec2::new(x as f32 + 0.5, y as f32 + 0.5);
+
+                // The point is inside if it's on the same side of every edge as the triangle itself
+                let w0 = (c - b).perp_dot(point - b) * area.signum();
+                let w1 = (a - c).perp_dot(point - c) * area.signum();
+                let w2 = (b - a).perp_dot(point - a) * area.signum();
+
+                if w0 >= 0.0 && w1 >= 0.0 && w2 >= 0.0 {
+                    coverage[(y * width + x) as usize] = 1.0;
+                }
+            }
+        }
+    }
+
+    fn box_blur(coverage: &mut [f32], width: u32, height: u32, radius: usize, horizontal: bool) {
+        let (lines, length) = if horizontal {
+            (height as usize, width as usize)
+        } else {
+            (width as usize, height as usize)
+        };
+
+        let index = |line: usize, position: usize| {
+            if horizontal {
+                line * width as usize + position
+            } else {
+                position * width as usize + line
+            }
+        };
+
+        let mut blurred = vec![0.0f32; length];
+        let window = (radius * 2 + 1) as f32;
+
+        for line in 0..lines {
+            for (position, value) in blurred.iter_mut().enumerate() {
+                let start = position.saturating_sub(radius);
+                let end = (position + radius + 1).min(length);
+
+                // Anything past the edges of the texture is uncovered
+                *value = (start..end)
+                    .map(|other| coverage[index(line, other)])
+                    .sum::<f32>()
+                    / window;
+            }
+
+            for (position, value) in blurred.iter().enumerate() {
+                coverage[index(line, position)] = *value;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nmsr_player_parts::parts::uv::box_uv;
+
+    use super::*;
+
+    fn cube(position: [i32; 3], size: [u32; 3]) -> Part {
+        Part::new_cube(
+            PlayerPartTextureType::Skin,
+            position,
+            size,
+            box_uv(16, 16, [size[0] as u16, size[1] as u16, size[2] as u16]),
+            #[cfg(feature = "part_tracker")]
+            None,
+        )
+    }
+
+    #[test]
+    fn shadow_covers_the_ground_under_the_parts() {
+        let parts = [cube([-4, 0, -2], [8, 12, 4])];
+        let shadow = GroundShadow::compute(&parts, 0.0, Vec3::NEG_Y).expect("No shadow was cast");
+
+        let (width, height) = shadow.texture.dimensions();
+
+        assert_eq!(shadow.texture.get_pixel(width / 2, height / 2)[3], u8::MAX);
+        assert_eq!(shadow.texture.get_pixel(0, 0)[3], 0);
+    }
+
+    #[test]
+    fn shadow_follows_the_light() {
+        let parts = [cube([-1, 0, -1], [2, 32, 2])];
+
+        let straight = GroundShadow::compute(&parts, 0.0, Vec3::NEG_Y).expect("No shadow was cast");
+        let slanted = GroundShadow::compute(&parts, 0.0, Vec3::new(1.0, -1.0, 0.0))
+            .expect("No shadow was cast");
+
+        assert!(slanted.part.get_size().x > straight.part.get_size().x);
+        assert_eq!(slanted.part.get_size().z, straight.part.get_size().z);
+    }
+
+    #[test]
+    fn nothing_casts_no_shadow() {
+        assert!(GroundShadow::compute(&[], 0.0, Vec3::NEG_Y).is_none());
+    }
+}
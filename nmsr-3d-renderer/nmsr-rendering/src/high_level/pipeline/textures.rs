@@ -43,7 +43,16 @@ impl BufferDimensions {
 pub(crate) struct SceneContextTextures {
     pub(crate) depth_texture: SceneTexture,
     pub(crate) output_texture: SceneTexture,
+    /// A copy of the output texture, which post-processing passes read from while drawing to the output texture.
+    pub(crate) post_process_source_texture: SceneTexture,
     pub(crate) multisampled_output_texture: Option<SceneTexture>,
+    /// The emissive parts of the scene, which are blurred to make them glow.
+    pub(crate) glow_texture: SceneTexture,
+    /// The glow texture after being blurred horizontally.
+    pub(crate) glow_blur_texture: SceneTexture,
+    pub(crate) multisampled_glow_texture: Option<SceneTexture>,
+    /// The depth texture used by auxiliary passes when the depth texture is multisampled, since they aren't.
+    pub(crate) aux_depth_texture: Option<SceneTexture>,
     pub(crate) texture_output_buffer: Buffer,
     pub(crate) texture_output_buffer_dimensions: BufferDimensions,
     pub(crate) camera_size: Size,
@@ -59,6 +68,13 @@ pub fn premultiply_alpha(image: &mut RgbaImage) {
     }
 }
 
+/// Returns whether the image has any pixel that is neither fully transparent nor fully opaque.
+pub fn has_translucent_pixels(image: &RgbaImage) -> bool {
+    image
+        .pixels()
+        .any(|pixel| pixel[3] != 0 && pixel[3] != u8::MAX)
+}
+
 pub fn unmultiply_alpha(image: &mut [u8]) {
     for pixel in image.chunks_exact_mut(4) {
         let alpha = pixel[3] as f32 / 255.0;
@@ -70,6 +86,41 @@ pub fn unmultiply_alpha(image: &mut [u8]) {
     }
 }
 
+/// Scales raw premultiplied RGBA pixels down by the given factor on each axis.
+///
+/// Every block of pixels is averaged into one, or when `average` is false, replaced by the pixel in its middle.
+#[instrument(skip(bytes))]
+pub fn downsample(bytes: &[u8], size: Size, factor: u32, average: bool) -> Vec<u8> {
+    let (width, factor) = (size.width as usize, factor as usize);
+    let (output_width, output_height) = (width / factor, size.height as usize / factor);
+
+    let pixel_at = |x: usize, y: usize| &bytes[(y * width + x) * 4..][..4];
+
+    let mut output = Vec::with_capacity(output_width * output_height * 4);
+
+    for y in (0..output_height).map(|y| y * factor) {
+        for x in (0..output_width).map(|x| x * factor) {
+            if !average {
+                output.extend_from_slice(pixel_at(x + factor / 2, y + factor / 2));
+                continue;
+            }
+
+            let mut sum = [0u32; 4];
+
+            for (dx, dy) in (0..factor).flat_map(|dy| (0..factor).map(move |dx| (dx, dy))) {
+                for (sum, &channel) in sum.iter_mut().zip(pixel_at(x + dx, y + dy)) {
+                    *sum += channel as u32;
+                }
+            }
+
+            let count = (factor * factor) as u32;
+            output.extend(sum.map(|sum| ((sum + count / 2) / count) as u8));
+        }
+    }
+
+    output
+}
+
 #[instrument(skip(context, usage))]
 pub fn create_texture(
     context: &GraphicsContext,
@@ -98,3 +149,38 @@ pub fn create_texture(
 
     SceneTexture { texture, view }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn downsample_averages_blocks() {
+        let bytes = [
+            [255, 0, 0, 255],
+            [0, 0, 0, 0],
+            [255, 0, 0, 255],
+            [0, 0, 0, 0],
+        ]
+        .concat();
+        let size = Size {
+            width: 2,
+            height: 2,
+        };
+
+        assert_eq!(downsample(&bytes, size, 2, true), [128, 0, 0, 128]);
+        assert_eq!(downsample(&bytes, size, 2, false), [0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn only_partially_transparent_pixels_are_translucent() {
+        let mut skin = RgbaImage::new(64, 64);
+        skin.put_pixel(8, 8, image::Rgba([255, 0, 0, 255]));
+
+        assert!(!has_translucent_pixels(&skin));
+
+        skin.put_pixel(40, 8, image::Rgba([0, 0, 255, 128]));
+
+        assert!(has_translucent_pixels(&skin));
+    }
+}
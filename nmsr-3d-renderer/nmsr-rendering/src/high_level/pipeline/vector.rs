@@ -0,0 +1,449 @@
+use std::fmt::Write;
+
+use glam::{Mat2, Mat4, Vec2, Vec3};
+use image::RgbaImage;
+use nmsr_player_parts::parts::part::Part;
+
+use super::scene::{SceneShading, Size, SunInformation};
+use crate::{
+    high_level::utils::parts::primitive_convert,
+    low_level::primitives::{part_primitive::PartPrimitive, vertex::Vertex},
+};
+
+/// A flat polygon of a vector render, in output pixels from the top left corner.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SceneVectorPolygon {
+    pub points: [Vec2; 4],
+    /// The lit color of the polygon, with straight (not premultiplied) alpha.
+    pub color: [u8; 4],
+}
+
+/// A scene drawn as flat polygons instead of pixels, one for each visible texel.
+///
+/// Every face is a flat textured quad, so each texel projects to a quadrilateral (a parallelogram in orthographic views).
+/// Faces looking away from the camera and texels hidden behind opaque ones are left out, and runs of texels of the same
+/// color along a row of the texture are merged into a single polygon.
+///
+/// Polygons are sorted back to front, so drawing them in order gives the same result as the rendering pipeline.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SceneVectorImage {
+    pub size: Size,
+    pub polygons: Vec<SceneVectorPolygon>,
+}
+
+/// A texel of a face, projected onto the output.
+struct ProjectedTexel {
+    /// The corners of the texel in output pixels, with the depth of each corner as z.
+    points: [Vec3; 4],
+    color: [u8; 4],
+    column: u32,
+    visible: bool,
+}
+
+/// The texels of a row of a face's texture, in order.
+type ProjectedRow = Vec<ProjectedTexel>;
+
+impl SceneVectorImage {
+    /// Faces this close to being edge-on aren't drawn, since they would only cover a sliver of the output.
+    const EDGE_ON_THRESHOLD: f32 = 1e-4;
+
+    /// Projects the given parts (and the texture each of them is drawn with) onto an output of the given size.
+    pub(crate) fn compute<'a>(
+        parts: impl IntoIterator<Item = (&'a Part, &'a RgbaImage)>,
+        view_projection: Mat4,
+        size: Size,
+        sun: &SunInformation,
+    ) -> Self {
+        // Where the camera looks towards, which faces need to look against to be seen
+        let forward = view_projection
+            .inverse()
+            .transform_vector3(Vec3::Z)
+            .normalize();
+
+        let mut rows = vec![];
+
+        for (part, texture) in parts {
+            let vertices = primitive_convert(part).get_vertices();
+
+            for quad in vertices.chunks_exact(4) {
+                let [top_left, top_right, bottom_left, _] = [quad[0], quad[1], quad[2], quad[3]];
+
+                if top_left.normal.dot(forward) > -Self::EDGE_ON_THRESHOLD {
+                    continue;
+                }
+
+                Self::project_quad(
+                    [top_left, top_right, bottom_left],
+                    texture,
+                    view_projection,
+                    size,
+                    compute_light(sun, top_left.normal),
+                    &mut rows,
+                );
+            }
+        }
+
+        Self::cull_hidden_texels(&mut rows, size);
+
+        let mut polygons = Self::merge_rows(&rows);
+        // Sort the polygons back to front, furthest (highest depth) first
+        polygons.sort_by(|(a, _), (b, _)| b.total_cmp(a));
+
+        Self {
+            size,
+            polygons: polygons.into_iter().map(|(_, polygon)| polygon).collect(),
+        }
+    }
+
+    fn project_quad(
+        [top_left, top_right, bottom_left]: [Vertex; 3],
+        texture: &RgbaImage,
+        view_projection: Mat4,
+        size: Size,
+        light: Vec3,
+        rows: &mut Vec<ProjectedRow>,
+    ) {
+        let texture_size = Vec2::new(texture.width() as f32, texture.height() as f32);
+
+        // Work in texels instead of UV coordinates, so that texel edges are at whole numbers
+        let uv_origin = top_left.uv * texture_size;
+        let uv_axes = Mat2::from_cols(
+            top_right.uv * texture_size - uv_origin,
+            bottom_left.uv * texture_size - uv_origin,
+        );
+
+        if uv_axes.determinant().abs() < f32::EPSILON {
+            return;
+        }
+
+        // Maps texel coordinates back to the position on the face they're shown at
+        let uv_to_face = uv_axes.inverse();
+        let position_axes = [
+            top_right.position - top_left.position,
+            bottom_left.position - top_left.position,
+        ];
+
+        let output_size = Vec2::new(size.width as f32, size.height as f32);
+        let project = |uv: Vec2| {
+            let [x, y] = (uv_to_face * (uv - uv_origin)).to_array();
+            let position = top_left.position + position_axes[0] * x + position_axes[1] * y;
+            let projected = view_projection.project_point3(position);
+
+            let pixels = Vec2::new(projected.x + 1.0, 1.0 - projected.y) * 0.5 * output_size;
+            pixels.extend(projected.z)
+        };
+
+        let corners = [
+            uv_origin,
+            uv_origin + uv_axes.x_axis,
+            uv_origin + uv_axes.y_axis,
+            uv_origin + uv_axes.x_axis + uv_axes.y_axis,
+        ];
+        let min = corners.into_iter().reduce(Vec2::min).unwrap_or_default();
+        let max = corners.into_iter().reduce(Vec2::max).unwrap_or_default();
+
+        let (start_x, end_x) = (min.x.floor().max(0.0) as u32, max.x.ceil() as u32);
+        let (start_y, end_y) = (min.y.floor().max(0.0) as u32, max.y.ceil() as u32);
+
+        for y in start_y..end_y.min(texture.height()) {
+            let mut row = vec![];
+
+            for x in start_x..end_x.min(texture.width()) {
+                let texel = texture.get_pixel(x, y).0;
+
+                if texel[3] == 0 {
+                    continue;
+                }
+
+                let texel_min = Vec2::new(x as f32, y as f32).max(min);
+                let texel_max = Vec2::new((x + 1) as f32, (y + 1) as f32).min(max);
+
+                let points = [
+                    project(texel_min),
+                    project(Vec2::new(texel_max.x, texel_min.y)),
+                    project(texel_max),
+                    project(Vec2::new(texel_min.x, texel_max.y)),
+                ];
+
+                let lit = Vec3::new(texel[0] as f32, texel[1] as f32, texel[2] as f32) * light;
+                let [r, g, b] = lit.round().min(Vec3::splat(255.0)).to_array();
+
+                row.push(ProjectedTexel {
+                    points,
+                    color: [r as u8, g as u8, b as u8, texel[3]],
+                    column: x,
+                    visible: false,
+                });
+            }
+
+            if !row.is_empty() {
+                rows.push(row);
+            }
+        }
+    }
+
+    /// Marks the texels that can be seen, which are the ones that are in front of every opaque texel on at least one pixel.
+    ///
+    /// Texels smaller than a pixel can miss every pixel center, so those are kept as long as they're in the depth range.
+    /// Polygons are drawn back to front, so keeping a texel that is hidden doesn't change the result, unlike leaving a hole.
+    fn cull_hidden_texels(rows: &mut [ProjectedRow], size: Size) {
+        let mut depth = vec![f32::INFINITY; (size.width * size.height) as usize];
+
+        for texel in rows.iter().flatten() {
+            if texel.color[3] == u8::MAX {
+                rasterize(texel.points, size, |index, texel_depth| {
+                    depth[index] = depth[index].min(texel_depth);
+                });
+            }
+        }
+
+        for texel in rows.iter_mut().flatten() {
+            let mut covers_pixel = false;
+
+            rasterize(texel.points, size, |index, texel_depth| {
+                covers_pixel = true;
+                texel.visible |= texel_depth <= depth[index];
+            });
+
+            if !covers_pixel {
+                let center = texel.points.into_iter().sum::<Vec3>() / 4.0;
+                texel.visible = (0.0..=1.0).contains(&center.z);
+            }
+        }
+    }
+
+    /// Merges the visible texels that are next to each other in a row and have the same color.
+    ///
+    /// Returns every merged polygon, along with its depth.
+    fn merge_rows(rows: &[ProjectedRow]) -> Vec<(f32, SceneVectorPolygon)> {
+        let mut polygons = vec![];
+
+        for row in rows {
+            let mut texels = row.iter().filter(|texel| texel.visible).peekable();
+
+            while let Some(first) = texels.next() {
+                let mut last = first;
+
+                while let Some(next) = texels
+                    .next_if(|next| next.column == last.column + 1 && next.color == first.color)
+                {
+                    last = next;
+                }
+
+                let points = [
+                    first.points[0],
+                    last.points[1],
+                    last.points[2],
+                    first.points[3],
+                ];
+                let depth = points.iter().map(|point| point.z).sum::<f32>() / 4.0;
+
+                polygons.push((
+                    depth,
+                    SceneVectorPolygon {
+                        points: points.map(|point| point.truncate()),
+                        color: first.color,
+                    },
+                ));
+            }
+        }
+
+        polygons
+    }
+
+    /// Writes the image as an SVG document, with one `<polygon>` for each polygon.
+    pub fn to_svg(&self) -> String {
+        let Size { width, height } = self.size;
+
+        let mut svg = format!(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" viewBox="0 0 {width} {height}">"#
+        );
+
+        for polygon in &self.polygons {
+            let [r, g, b, a] = polygon.color;
+
+            svg.push_str(r#"<polygon points=""#);
+
+            for (index, point) in polygon.points.iter().enumerate() {
+                let separator = if index == 0 { "" } else { " " };
+                // Two decimals are plenty, since these are in output pixels
+                let _ = write!(
+                    svg,
+                    "{separator}{},{}",
+                    (point.x * 100.0).round() / 100.0,
+                    (point.y * 100.0).round() / 100.0
+                );
+            }
+
+            let _ = write!(svg, r##"" fill="#{r:02x}{g:02x}{b:02x}""##);
+
+            if a != u8::MAX {
+                let _ = write!(
+                    svg,
+                    r#" fill-opacity="{}""#,
+                    (a as f32 / 255.0 * 1000.0).round() / 1000.0
+                );
+            }
+
+            svg.push_str("/>");
+        }
+
+        svg.push_str("</svg>");
+        svg
+    }
+}
+
+/// Computes how much light a face with the given normal receives, like the `compute_sun_lighting` function of the shader.
+fn compute_light(sun: &SunInformation, normal: Vec3) -> Vec3 {
+    const MAX_LIGHT: f32 = 1.0;
+    const TOON_BANDS: f32 = 3.0;
+
+    let key_light = match sun.shading {
+        shading if shading == SceneShading::Flat as u32 => MAX_LIGHT,
+        shading if shading == SceneShading::Minecraft as u32 => {
+            let vertical_light = if normal.y > 0.0 { 1.0 } else { 0.5 };
+
+            (normal * normal).dot(Vec3::new(0.6, vertical_light, 0.8))
+        }
+        shading => {
+            let sun_dot = normal.dot(-sun.direction.normalize_or_zero());
+            let light = (sun.intensity * sun_dot).clamp(sun.ambient, MAX_LIGHT);
+
+            if shading == SceneShading::Toon as u32 && sun.ambient < MAX_LIGHT {
+                let amount = (light - sun.ambient) / (MAX_LIGHT - sun.ambient);
+                let band = ((amount * TOON_BANDS).floor() / (TOON_BANDS - 1.0)).min(1.0);
+
+                sun.ambient + (MAX_LIGHT - sun.ambient) * band
+            } else {
+                light
+            }
+        }
+    };
+
+    let mut light = sun.color * key_light;
+
+    if sun.fill_intensity > 0.0 {
+        let fill_dot = normal.dot(-sun.fill_direction.normalize_or_zero());
+
+        light += sun.fill_color * (sun.fill_intensity * fill_dot).max(0.0);
+    }
+
+    light.min(Vec3::splat(MAX_LIGHT))
+}
+
+/// Calls the given function with the index and depth of every pixel whose center is covered by the given quadrilateral.
+fn rasterize(points: [Vec3; 4], size: Size, mut pixel: impl FnMut(usize, f32)) {
+    for [a, b, c] in [
+        [points[0], points[1], points[2]],
+        [points[0], points[2], points[3]],
+    ] {
+        let area = (b - a).truncate().perp_dot((c - a).truncate());
+
+        if area.abs() < f32::EPSILON {
+            continue;
+        }
+
+        let min = a.min(b).min(c);
+        let max = a.max(b).max(c);
+
+        let start_x = (min.x - 0.5).ceil().max(0.0) as u32;
+        let start_y = (min.y - 0.5).ceil().max(0.0) as u32;
+        let end_x = ((max.x - 0.5).floor() + 1.0).clamp(0.0, size.width as f32) as u32;
+        let end_y = ((max.y - 0.5).floor() + 1.0).clamp(0.0, size.height as f32) as u32;
+
+        for y in start_y..end_y {
+            for x in start_x..end_x {
+                let center = Vec2::new(x as f32 + 0.5, y as f32 + 0.5);
+
+                let weight_a = (c - b).truncate().perp_dot(center - b.truncate()) / area;
+                let weight_b = (a - c).truncate().perp_dot(center - c.truncate()) / area;
+                let weight_c = 1.0 - weight_a - weight_b;
+
+                if weight_a < 0.0 || weight_b < 0.0 || weight_c < 0.0 {
+                    continue;
+                }
+
+                let depth = a.z * weight_a + b.z * weight_b + c.z * weight_c;
+
+                // Anything outside of the depth range is clipped by the rendering pipeline
+                if (0.0..=1.0).contains(&depth) {
+                    pixel((y * size.width + x) as usize, depth);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nmsr_player_parts::{
+        parts::provider::{PlayerPartProviderContext, PlayerPartsProvider},
+        types::PlayerBodyPartType,
+    };
+
+    use super::*;
+    use crate::high_level::camera::{Camera, CameraRotation, ProjectionParameters};
+
+    fn head_parts() -> Vec<Part> {
+        let context = PlayerPartProviderContext::<()>::default();
+
+        context
+            .get_parts_by_body_part(
+                &[PlayerPartsProvider::Minecraft],
+                &[PlayerBodyPartType::Head],
+            )
+            .into_iter()
+            .map(|(_, part)| part)
+            .collect()
+    }
+
+    /// Draws the head from the front, zoomed out so that the frame is `2 * aspect` texels tall.
+    fn render_head_from_front(aspect: f32) -> SceneVectorImage {
+        let parts = head_parts();
+
+        let size = Size {
+            width: 64,
+            height: 64,
+        };
+        let mut camera = Camera::new_orbital(
+            Vec3::new(0.0, 28.0, 0.0),
+            20.0,
+            CameraRotation {
+                yaw: 0.0,
+                pitch: 0.0,
+                roll: 0.0,
+            },
+            ProjectionParameters::Orthographic { aspect },
+            Some(size),
+        );
+
+        let texture = RgbaImage::from_pixel(64, 64, image::Rgba([255, 0, 0, 255]));
+
+        SceneVectorImage::compute(
+            parts.iter().map(|part| (part, &texture)),
+            camera.get_view_projection_matrix(),
+            size,
+            &SunInformation::new(Vec3::ZERO, 0.0, 1.0),
+        )
+    }
+
+    #[test]
+    fn front_of_head_is_merged_into_rows() {
+        let image = render_head_from_front(8.0);
+
+        // Only the front of the head faces the camera, and each of its 8 rows is a single color
+        assert_eq!(image.polygons.len(), 8);
+        assert!(image
+            .polygons
+            .iter()
+            .all(|polygon| polygon.color == [255, 0, 0, 255]));
+    }
+
+    #[test]
+    fn texels_smaller_than_a_pixel_are_kept() {
+        // Each texel is an eighth of a pixel wide, so the whole head covers barely any pixel centers
+        let image = render_head_from_front(256.0);
+
+        assert_eq!(image.polygons.len(), 8);
+    }
+}
@@ -1,7 +1,7 @@
 #![allow(dead_code)]
 
 // Re-export some types from the glam crate
-pub use glam::{EulerRot, Mat4, Quat, Vec2, Vec3};
+pub use glam::{EulerRot, Mat4, Quat, Vec2, Vec3, Vec4};
 
 pub mod primitives;
 
@@ -19,7 +19,7 @@ mod routes;
 mod utils;
 
 use crate::{
-    routes::{render, render_get_warning, render_post_warning, NMSRState},
+    routes::{render, render_get_warning, render_post_warning, render_scene, NMSRState},
     utils::tracing::NmsrTracing,
 };
 
@@ -85,6 +85,7 @@ async fn main() -> anyhow::Result<()> {
 
     // build our application with a route
     let router = Router::new()
+        .route("/scene", post(render_scene))
         .route("/{mode}/{texture}", get(render))
         .route("/{mode}/{texture}", post(render_post_warning))
         .route("/{mode}", get(render_get_warning))
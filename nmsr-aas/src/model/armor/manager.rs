@@ -1,4 +1,7 @@
-use std::path::PathBuf;
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
 
 use ears_rs::utils::upgrade_skin_if_needed;
 use hyper::Method;
@@ -12,50 +15,59 @@ use nmsr_rendering::{
 };
 use strum::IntoEnumIterator;
 use tokio::fs;
-use tracing::Span;
+use tracing::{info, trace, warn, Span};
 
 use crate::{
+    config::ArmorConfiguration,
     error::{ArmorManagerError, ArmorManagerResult, ExplainableExt, Result},
-    model::armor::LeatherArmorColor,
+    model::{armor::LeatherArmorColor, assets::OpenedAssetSource},
     utils::http_client::NmsrHttpClient,
 };
 
 use super::{
-    VanillaMinecraftArmorMaterial, VanillaMinecraftArmorMaterialData, VanillaMinecraftArmorTrim,
-    VanillaMinecraftArmorTrimData, VanillaMinecraftArmorTrimPalette,
+    get_material_asset_paths, get_trim_asset_paths, get_trim_palette_asset_path,
+    registry::CustomArmorRegistry, MinecraftArmorMaterial, MinecraftArmorTrim,
+    MinecraftArmorTrimMaterial, VanillaMinecraftArmorMaterial, VanillaMinecraftArmorMaterialData,
+    VanillaMinecraftArmorTrim, VanillaMinecraftArmorTrimData, VanillaMinecraftArmorTrimPalette,
+    ELYTRA_ASSET_PATH, GLINT_ASSET_PATH,
 };
 
+type TrimPaletteColors = [[u8; 3]; 8];
+
+/// The color palette of a custom trim material.
+struct CustomTrimPalette {
+    colors: TrimPaletteColors,
+    /// The palette used when the trim is applied to armor of the same material, if there is one.
+    darker_colors: Option<TrimPaletteColors>,
+}
+
 pub struct VanillaMinecraftArmorManager {
     client: NmsrHttpClient,
+    sources: Vec<OpenedAssetSource>,
+    download_missing_assets: bool,
     material_location: PathBuf,
     trims_location: PathBuf,
+    glint_location: PathBuf,
+    elytra_location: PathBuf,
+    registry: CustomArmorRegistry,
+    custom_trim_palettes: HashMap<String, CustomTrimPalette>,
 }
 
 enum VanillaArmorApplicable<'a> {
-    Armor(VanillaMinecraftArmorMaterial),
+    Armor(&'a MinecraftArmorMaterial),
     Trim(
-        VanillaMinecraftArmorMaterial,
+        &'a MinecraftArmorMaterial,
         &'a VanillaMinecraftArmorTrimData,
     ),
 }
 
 impl<'a> VanillaArmorApplicable<'a> {
-    fn get_layer_name(&self, slot: PlayerArmorSlot) -> String {
-        match self {
-            Self::Armor(data) => data.get_layer_name(slot.layer_id(), false),
-            Self::Trim(_, trim) => trim.trim.get_layer_name(slot.is_leggings()),
-        }
-    }
-
-    fn apply_modifications_if_needed(&self, image: &mut RgbaImage) {
-        if let VanillaArmorApplicable::Trim(
-            armor_material,
-            VanillaMinecraftArmorTrimData { material, .. },
-        ) = self
-        {
-            let palette = material
-                .get_palette_for_trim_armor_material(*armor_material)
-                .get_palette_colors();
+    fn apply_modifications_if_needed(
+        &self,
+        image: &mut RgbaImage,
+        palette: Option<TrimPaletteColors>,
+    ) {
+        if let (VanillaArmorApplicable::Trim(..), Some(palette)) = (self, palette) {
             let trim_palette = VanillaMinecraftArmorTrimPalette::get_trim_palette();
 
             for pixel in image.pixels_mut() {
@@ -74,7 +86,9 @@ impl<'a> VanillaArmorApplicable<'a> {
             }
         }
 
-        if let Self::Armor(VanillaMinecraftArmorMaterial::Leather(LeatherArmorColor(color))) = self
+        if let Self::Armor(MinecraftArmorMaterial::Vanilla(
+            VanillaMinecraftArmorMaterial::Leather(LeatherArmorColor(color)),
+        )) = self
         {
             let vec_color = Vec3::from([color[0] as f32, color[1] as f32, color[2] as f32]);
 
@@ -95,11 +109,13 @@ impl<'a> VanillaArmorApplicable<'a> {
 }
 
 impl VanillaMinecraftArmorManager {
-    pub async fn new(cache_path: PathBuf) -> Result<Self> {
+    pub async fn new(cache_path: PathBuf, config: ArmorConfiguration) -> Result<Self> {
         let armor_location = cache_path.join("armor");
 
         let material_location = armor_location.join("material");
         let trims_location = armor_location.join("trims");
+        let glint_location = armor_location.join("enchanted_glint_armor.png");
+        let elytra_location = armor_location.join("elytra.png");
 
         fs::create_dir_all(&material_location)
             .await
@@ -109,10 +125,21 @@ impl VanillaMinecraftArmorManager {
             .await
             .explain("Unable to create armor cache folder".to_string())?;
 
-        let manager = Self {
+        let mut sources = Vec::with_capacity(config.asset_sources.len());
+        for source in &config.asset_sources {
+            sources.push(source.open().await?);
+        }
+
+        let mut manager = Self {
             client: NmsrHttpClient::new(20, 5 * 60 /* 5 minutes */, 5, &[], None),
+            sources,
+            download_missing_assets: config.download_missing_assets,
             material_location,
             trims_location,
+            glint_location,
+            elytra_location,
+            registry: CustomArmorRegistry::default(),
+            custom_trim_palettes: HashMap::new(),
         };
 
         manager.init().await?;
@@ -120,101 +147,327 @@ impl VanillaMinecraftArmorManager {
         Ok(manager)
     }
 
-    fn get_material_file_path(&self, material: VanillaMinecraftArmorMaterial) -> PathBuf {
-        self.material_location.join(material.to_string())
+    fn get_material_layer_path(
+        &self,
+        material: &MinecraftArmorMaterial,
+        id: u32,
+        is_overlay: bool,
+    ) -> ArmorManagerResult<PathBuf> {
+        Ok(match material {
+            MinecraftArmorMaterial::Vanilla(material) => self
+                .material_location
+                .join(material.to_string())
+                .join(material.get_layer_name(id, is_overlay)),
+            MinecraftArmorMaterial::Custom(id_name) => {
+                let asset =
+                    self.registry.materials.get(id_name).ok_or_else(|| {
+                        ArmorManagerError::UnknownCustomArmorAsset(id_name.clone())
+                    })?;
+
+                self.material_location
+                    .join("custom")
+                    .join(&asset.namespace)
+                    .join(&asset.name)
+                    .join(format!("{name}_layer_{id}.png", name = asset.name))
+            }
+        })
     }
 
-    fn get_trim_file_path(&self, trim: VanillaMinecraftArmorTrim) -> PathBuf {
-        self.trims_location.join(trim.to_string())
+    fn get_trim_layer_path(
+        &self,
+        trim: &MinecraftArmorTrim,
+        is_leggings: bool,
+    ) -> ArmorManagerResult<PathBuf> {
+        Ok(match trim {
+            MinecraftArmorTrim::Vanilla(trim) => self
+                .trims_location
+                .join(trim.to_string())
+                .join(trim.get_layer_name(is_leggings)),
+            MinecraftArmorTrim::Custom(id) => {
+                let asset = self
+                    .registry
+                    .trims
+                    .get(id)
+                    .ok_or_else(|| ArmorManagerError::UnknownCustomArmorAsset(id.clone()))?;
+
+                let suffix = if is_leggings { "_leggings" } else { "" };
+
+                self.trims_location
+                    .join("custom")
+                    .join(&asset.namespace)
+                    .join(&asset.name)
+                    .join(format!("{name}{suffix}.png", name = asset.name))
+            }
+        })
     }
 
-    async fn init(&self) -> Result<()> {
-        self.download_materials().await?;
-        self.download_trims().await?;
+    async fn init(&mut self) -> Result<()> {
+        self.discover_custom_assets().await?;
+
+        self.load_materials().await?;
+        self.load_trims().await?;
+        self.load_custom_trim_palettes().await?;
+        self.load_glint().await?;
+        self.load_elytra().await?;
 
         Ok(())
     }
 
-    async fn download_trims(&self) -> Result<()> {
-        for trim in VanillaMinecraftArmorTrim::iter() {
-            let trim_path = self.get_trim_file_path(trim);
-
-            fs::create_dir_all(&trim_path).await.explain(format!(
-                "Unable to create armor cache folder for trim {trim}"
-            ))?;
+    async fn discover_custom_assets(&mut self) -> Result<()> {
+        for source in &self.sources {
+            let assets = source.list_assets().await?;
 
-            let layers = trim.get_layer_names();
+            self.registry.discover(assets.iter().map(String::as_str));
+        }
 
-            for layer in layers {
-                let layer_path = trim_path.join(&layer);
+        info!(
+            "Found {} custom armor materials, {} custom trims and {} custom trim materials",
+            self.registry.materials.len(),
+            self.registry.trims.len(),
+            self.registry.trim_materials.len()
+        );
 
-                if !layer_path.exists() {
-                    let url = format!(
-                        "https://raw.githubusercontent.com/NickAcPT/minecraft-assets/24w11a/assets/minecraft/textures/trims/models/armor/{name}",
-                        name = &layer
-                    );
+        Ok(())
+    }
 
-                    let bytes = self
-                        .client
-                        .do_request(&url, Method::GET, &Span::current(), || None)
-                        .await?;
+    async fn load_trims(&self) -> Result<()> {
+        for trim in VanillaMinecraftArmorTrim::iter() {
+            for is_leggings in [false, true] {
+                let layer = trim.get_layer_name(is_leggings);
+
+                self.load_asset(
+                    &self.get_trim_layer_path(&trim.into(), is_leggings)?,
+                    &trim.get_asset_paths(is_leggings),
+                    Some(format!(
+                        "https://raw.githubusercontent.com/NickAcPT/minecraft-assets/24w11a/assets/minecraft/textures/trims/models/armor/{layer}"
+                    )),
+                )
+                .await?;
+            }
+        }
 
-                    fs::write(&layer_path, bytes)
-                        .await
-                        .explain(format!("Unable to write armor cache file for trim {layer}"))?;
-                }
+        for (id, asset) in &self.registry.trims {
+            for is_leggings in [false, true] {
+                self.load_asset(
+                    &self.get_trim_layer_path(
+                        &MinecraftArmorTrim::Custom(id.clone()),
+                        is_leggings,
+                    )?,
+                    &get_trim_asset_paths(&asset.namespace, &asset.name, is_leggings),
+                    None,
+                )
+                .await?;
             }
         }
 
         Ok(())
     }
-    async fn download_materials(&self) -> Result<()> {
+
+    async fn load_materials(&self) -> Result<()> {
         for material in VanillaMinecraftArmorMaterial::iter() {
-            let material_path = self.get_material_file_path(material);
-            let material_name = material.to_string().to_lowercase();
+            for (id, is_overlay) in material.get_layers() {
+                let file_name = material.get_layer_name(id, is_overlay);
+
+                self.load_asset(
+                    &self.get_material_layer_path(&material.into(), id, is_overlay)?,
+                    &material.get_asset_paths(id, is_overlay),
+                    Some(format!(
+                        "https://raw.githubusercontent.com/InventivetalentDev/minecraft-assets/1.20.1/assets/minecraft/textures/models/armor/{file_name}"
+                    )),
+                )
+                .await?;
+            }
+        }
+
+        for (id, asset) in &self.registry.materials {
+            for layer_id in [1, 2] {
+                self.load_asset(
+                    &self.get_material_layer_path(
+                        &MinecraftArmorMaterial::Custom(id.clone()),
+                        layer_id,
+                        false,
+                    )?,
+                    &get_material_asset_paths(
+                        &asset.namespace,
+                        &asset.name,
+                        &asset.name,
+                        layer_id,
+                        false,
+                    ),
+                    None,
+                )
+                .await?;
+            }
+        }
 
-            fs::create_dir_all(&material_path).await.explain(format!(
-                "Unable to create armor cache folder for material {material}"
-            ))?;
+        Ok(())
+    }
 
-            let layers = material.get_layer_names();
+    async fn load_custom_trim_palettes(&mut self) -> Result<()> {
+        for (id, asset) in &self.registry.trim_materials {
+            let palette_path = get_trim_palette_asset_path(&asset.namespace, &asset.name);
+            let darker_palette_path =
+                get_trim_palette_asset_path(&asset.namespace, &format!("{}_darker", asset.name));
 
-            for layer in layers {
-                let file_name = format!("{material_name}_layer_{layer}.png");
-                let layer_path = material_path.join(&file_name);
+            let Some(colors) = self.read_asset(&[palette_path.clone()]).await? else {
+                continue;
+            };
 
-                if !layer_path.exists() {
-                    let url = format!(
-                        "https://raw.githubusercontent.com/InventivetalentDev/minecraft-assets/1.20.1/assets/minecraft/textures/models/armor/{name}",
-                        name = &file_name
-                    );
+            let darker_colors = self.read_asset(&[darker_palette_path.clone()]).await?;
 
-                    let bytes = self
-                        .client
-                        .do_request(&url, Method::GET, &Span::current(), || None)
-                        .await?;
+            let palette = CustomTrimPalette {
+                colors: Self::decode_trim_palette(&palette_path, &colors)?,
+                darker_colors: darker_colors
+                    .map(|bytes| Self::decode_trim_palette(&darker_palette_path, &bytes))
+                    .transpose()?,
+            };
+
+            self.custom_trim_palettes.insert(id.clone(), palette);
+        }
+
+        Ok(())
+    }
 
-                    fs::write(&layer_path, bytes).await.explain(format!(
-                        "Unable to write armor cache file for material {material}"
-                    ))?;
+    async fn load_glint(&self) -> Result<()> {
+        self.load_asset(
+            &self.glint_location,
+            &[GLINT_ASSET_PATH.to_string()],
+            Some(format!(
+                "https://raw.githubusercontent.com/InventivetalentDev/minecraft-assets/1.20.1/{GLINT_ASSET_PATH}"
+            )),
+        )
+        .await
+    }
+
+    async fn load_elytra(&self) -> Result<()> {
+        self.load_asset(
+            &self.elytra_location,
+            &[ELYTRA_ASSET_PATH.to_string()],
+            Some(format!(
+                "https://raw.githubusercontent.com/InventivetalentDev/minecraft-assets/1.20.1/{ELYTRA_ASSET_PATH}"
+            )),
+        )
+        .await
+    }
+
+    fn decode_trim_palette(
+        asset_path: &str,
+        bytes: &[u8],
+    ) -> ArmorManagerResult<TrimPaletteColors> {
+        let image = image::load_from_memory(bytes)
+            .map_err(|e| ArmorManagerError::ArmorTextureLoadError(asset_path.into(), e))?
+            .into_rgb8();
+
+        if image.width() < 8 || image.height() < 1 {
+            return Err(ArmorManagerError::InvalidTrimPaletteError(
+                asset_path.to_string(),
+            ));
+        }
+
+        Ok(std::array::from_fn(|i| image.get_pixel(i as u32, 0).0))
+    }
+
+    /// Reads the first of the given assets found in the configured local sources, in order.
+    async fn read_asset(&self, asset_paths: &[String]) -> Result<Option<Vec<u8>>> {
+        for source in &self.sources {
+            for asset_path in asset_paths {
+                if let Some(bytes) = source.read_asset(asset_path).await? {
+                    trace!("Loaded armor asset {asset_path} from {source:?}");
+
+                    return Ok(Some(bytes));
                 }
             }
         }
 
-        Ok(())
+        Ok(None)
+    }
+
+    /// Copies an armor asset into our cache.
+    ///
+    /// The configured local sources are checked first, in order, and always take precedence over what is
+    /// already cached. If none of them have the asset and it isn't cached yet, it's downloaded from `fallback_url`.
+    async fn load_asset(
+        &self,
+        cache_path: &Path,
+        asset_paths: &[String],
+        fallback_url: Option<String>,
+    ) -> Result<()> {
+        if let Some(parent) = cache_path.parent() {
+            fs::create_dir_all(parent)
+                .await
+                .explain(format!("Unable to create armor cache folder {parent:?}"))?;
+        }
+
+        if let Some(bytes) = self.read_asset(asset_paths).await? {
+            return fs::write(cache_path, bytes)
+                .await
+                .explain(format!("Unable to write armor cache file {cache_path:?}"));
+        }
+
+        if cache_path.exists() {
+            return Ok(());
+        }
+
+        let Some(fallback_url) = fallback_url.filter(|_| self.download_missing_assets) else {
+            warn!("Unable to find armor asset {asset_paths:?} in any of the configured sources");
+            return Ok(());
+        };
+
+        let bytes = self
+            .client
+            .do_request(&fallback_url, Method::GET, &Span::current(), || None)
+            .await?;
+
+        fs::write(cache_path, bytes)
+            .await
+            .explain(format!("Unable to write armor cache file {cache_path:?}"))
     }
 
     fn get_image_path(
         &self,
         applicable: &VanillaArmorApplicable,
         slot: PlayerArmorSlot,
-    ) -> PathBuf {
-        let root = match applicable {
-            VanillaArmorApplicable::Armor(data) => self.get_material_file_path(*data),
-            VanillaArmorApplicable::Trim(_, data) => self.get_trim_file_path(data.trim),
-        };
+    ) -> ArmorManagerResult<PathBuf> {
+        match applicable {
+            VanillaArmorApplicable::Armor(material) => {
+                self.get_material_layer_path(material, slot.layer_id(), false)
+            }
+            VanillaArmorApplicable::Trim(_, data) => {
+                self.get_trim_layer_path(&data.trim, slot.is_leggings())
+            }
+        }
+    }
 
-        root.join(applicable.get_layer_name(slot))
+    fn get_trim_palette_colors(
+        &self,
+        trim_material: &MinecraftArmorTrimMaterial,
+        armor_material: &MinecraftArmorMaterial,
+    ) -> ArmorManagerResult<TrimPaletteColors> {
+        match (trim_material, armor_material) {
+            (
+                MinecraftArmorTrimMaterial::Vanilla(trim_material),
+                MinecraftArmorMaterial::Vanilla(armor_material),
+            ) => Ok(trim_material
+                .get_palette_for_trim_armor_material(*armor_material)
+                .get_palette_colors()),
+            (
+                MinecraftArmorTrimMaterial::Vanilla(trim_material),
+                MinecraftArmorMaterial::Custom(_),
+            ) => Ok(VanillaMinecraftArmorTrimPalette::from(*trim_material).get_palette_colors()),
+            (MinecraftArmorTrimMaterial::Custom(id), armor_material) => {
+                let palette = self
+                    .custom_trim_palettes
+                    .get(id)
+                    .ok_or_else(|| ArmorManagerError::UnknownCustomArmorAsset(id.clone()))?;
+
+                let is_same_material = matches!(armor_material, MinecraftArmorMaterial::Custom(armor_id) if armor_id == id);
+
+                Ok(palette
+                    .darker_colors
+                    .filter(|_| is_same_material)
+                    .unwrap_or(palette.colors))
+            }
+        }
     }
 
     pub async fn create_armor_texture(
@@ -225,19 +478,21 @@ impl VanillaMinecraftArmorManager {
         let mut output_armor_two_image = image::RgbaImage::new(64, 64);
 
         for (data, slot) in slots.get_all_materials_in_slots() {
+            let data = self.registry.resolve(data)?;
+
             let output_image = if slot.is_leggings() {
                 &mut output_armor_two_image
             } else {
                 &mut output_armor_image
             };
 
-            let mut to_apply = vec![VanillaArmorApplicable::Armor(data.material)];
+            let mut to_apply = vec![VanillaArmorApplicable::Armor(&data.material)];
 
             to_apply.append(
                 &mut data
                     .trims
                     .iter()
-                    .map(|trim| VanillaArmorApplicable::Trim(data.material, trim))
+                    .map(|trim| VanillaArmorApplicable::Trim(&data.material, trim))
                     .collect(),
             );
 
@@ -252,13 +507,32 @@ impl VanillaMinecraftArmorManager {
         ))
     }
 
+    pub async fn create_glint_texture(&self) -> Result<RgbaImage> {
+        Self::load_cached_texture(&self.glint_location).await
+    }
+
+    /// Creates the elytra texture used when the player doesn't have a cape to put on their elytra.
+    pub async fn create_elytra_texture(&self) -> Result<RgbaImage> {
+        Self::load_cached_texture(&self.elytra_location).await
+    }
+
+    async fn load_cached_texture(path: &Path) -> Result<RgbaImage> {
+        let bytes = fs::read(path)
+            .await
+            .map_err(|_| ArmorManagerError::MissingArmorTextureError(path.to_path_buf()))?;
+
+        Ok(image::load_from_memory(&bytes)
+            .map_err(|e| ArmorManagerError::ArmorTextureLoadError(path.to_path_buf(), e))?
+            .into_rgba8())
+    }
+
     async fn apply_parts(
         &self,
         applicable: &VanillaArmorApplicable<'_>,
         slot: PlayerArmorSlot,
         output_image: &mut RgbaImage,
     ) -> ArmorManagerResult<()> {
-        let material_path = self.get_image_path(applicable, slot);
+        let material_path = self.get_image_path(applicable, slot)?;
 
         let bytes = fs::read(&material_path)
             .await
@@ -268,7 +542,13 @@ impl VanillaMinecraftArmorManager {
             .map_err(|e| ArmorManagerError::ArmorTextureLoadError(material_path.clone(), e))?
             .into_rgba8();
 
-        applicable.apply_modifications_if_needed(&mut image);
+        let trim_palette = if let VanillaArmorApplicable::Trim(armor_material, trim) = applicable {
+            Some(self.get_trim_palette_colors(&trim.material, armor_material)?)
+        } else {
+            None
+        };
+
+        applicable.apply_modifications_if_needed(&mut image, trim_palette);
 
         let image = upgrade_skin_if_needed(image);
 
@@ -1,4 +1,5 @@
 pub mod manager;
+pub mod registry;
 
 use std::collections::VecDeque;
 
@@ -55,18 +56,32 @@ impl VanillaMinecraftArmorMaterial {
         }
     }
 
+    /// Returns the layers (layer id and whether it's an overlay) that make up this material.
     #[must_use]
-    pub fn get_layer_names(&self) -> Vec<String> {
-        let result = (0..Self::layer_count()).map(|i| (i + 1).to_string());
+    pub fn get_layers(&self) -> Vec<(u32, bool)> {
+        let result = (1..=Self::layer_count() as u32).map(|id| (id, false));
 
         if self.has_overlay() {
             result
-                .flat_map(|layer| vec![layer.clone(), format!("{}_overlay", &layer)])
+                .flat_map(|(id, _)| vec![(id, false), (id, true)])
                 .collect()
         } else {
             result.collect()
         }
     }
+
+    /// Returns the paths this layer can be found at inside a resource pack or client jar.
+    #[must_use]
+    pub fn get_asset_paths(&self, id: u32, is_overlay: bool) -> Vec<String> {
+        let name = self.to_string().to_lowercase();
+
+        let equipment_name = match self {
+            Self::Turtle => "turtle_scute",
+            _ => &name,
+        };
+
+        get_material_asset_paths(VANILLA_NAMESPACE, equipment_name, &name, id, is_overlay)
+    }
 }
 
 #[derive(Debug, Display, Clone, Copy, PartialEq, Eq, strum::IntoStaticStr, strum::EnumIter)]
@@ -92,13 +107,6 @@ pub enum VanillaMinecraftArmorTrim {
 }
 
 impl VanillaMinecraftArmorTrim {
-    #[must_use]
-    pub fn get_layer_names(&self) -> Vec<String> {
-        let name = self.to_string().to_lowercase();
-
-        vec![format!("{}.png", name), format!("{}_leggings.png", name)]
-    }
-
     #[must_use]
     pub fn get_layer_name(&self, is_leggings: bool) -> String {
         let name = self.to_string().to_lowercase();
@@ -109,6 +117,16 @@ impl VanillaMinecraftArmorTrim {
             format!("{name}.png")
         }
     }
+
+    /// Returns the paths this trim layer can be found at inside a resource pack or client jar.
+    #[must_use]
+    pub fn get_asset_paths(&self, is_leggings: bool) -> Vec<String> {
+        get_trim_asset_paths(
+            VANILLA_NAMESPACE,
+            &self.to_string().to_lowercase(),
+            is_leggings,
+        )
+    }
 }
 
 #[derive(Debug, Display, Clone, Copy, PartialEq, Eq, strum::EnumIter, strum::IntoStaticStr)]
@@ -145,26 +163,127 @@ pub enum VanillaMinecraftArmorTrimPalette {
     Resin,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// The namespace of the built-in vanilla armor assets.
+pub const VANILLA_NAMESPACE: &str = "minecraft";
+
+/// Returns the paths an armor material layer can be found at inside a resource pack or client jar.
+/// Both the equipment layout (1.21.2+) and the legacy `models/armor` layout are supported.
+#[must_use]
+pub fn get_material_asset_paths(
+    namespace: &str,
+    equipment_name: &str,
+    legacy_name: &str,
+    id: u32,
+    is_overlay: bool,
+) -> Vec<String> {
+    let overlay = if is_overlay { "_overlay" } else { "" };
+
+    let equipment_layer = if id == 2 {
+        "humanoid_leggings"
+    } else {
+        "humanoid"
+    };
+
+    vec![
+        format!("assets/{namespace}/textures/entity/equipment/{equipment_layer}/{equipment_name}{overlay}.png"),
+        format!("assets/{namespace}/textures/models/armor/{legacy_name}_layer_{id}{overlay}.png"),
+    ]
+}
+
+/// Returns the paths an armor trim layer can be found at inside a resource pack or client jar.
+/// Both the equipment layout (1.21.2+) and the legacy `trims/models/armor` layout are supported.
+#[must_use]
+pub fn get_trim_asset_paths(namespace: &str, name: &str, is_leggings: bool) -> Vec<String> {
+    let (equipment_layer, legacy_suffix) = if is_leggings {
+        ("humanoid_leggings", "_leggings")
+    } else {
+        ("humanoid", "")
+    };
+
+    vec![
+        format!("assets/{namespace}/textures/trims/entity/{equipment_layer}/{name}.png"),
+        format!("assets/{namespace}/textures/trims/models/armor/{name}{legacy_suffix}.png"),
+    ]
+}
+
+/// Returns the path of a trim color palette inside a resource pack or client jar.
+#[must_use]
+pub fn get_trim_palette_asset_path(namespace: &str, name: &str) -> String {
+    format!("assets/{namespace}/textures/trims/color_palettes/{name}.png")
+}
+
+/// The path of the enchantment glint texture used on armor inside a resource pack or client jar.
+pub const GLINT_ASSET_PATH: &str = "assets/minecraft/textures/misc/enchanted_glint_armor.png";
+
+/// The path of the default elytra texture inside a resource pack or client jar.
+pub const ELYTRA_ASSET_PATH: &str = "assets/minecraft/textures/entity/elytra.png";
+
+/// An armor material, either one of the built-in vanilla ones or a custom one loaded from a resource pack.
+#[derive(Debug, Clone, PartialEq, Eq, From)]
+pub enum MinecraftArmorMaterial {
+    Vanilla(VanillaMinecraftArmorMaterial),
+    Custom(String),
+}
+
+/// An armor trim pattern, either one of the built-in vanilla ones or a custom one loaded from a resource pack.
+#[derive(Debug, Clone, PartialEq, Eq, From)]
+pub enum MinecraftArmorTrim {
+    Vanilla(VanillaMinecraftArmorTrim),
+    Custom(String),
+}
+
+/// An armor trim material, either one of the built-in vanilla ones or a custom one loaded from a resource pack.
+#[derive(Debug, Clone, PartialEq, Eq, From)]
+pub enum MinecraftArmorTrimMaterial {
+    Vanilla(VanillaMinecraftArmorTrimMaterial),
+    Custom(String),
+}
+
+macro_rules! impl_custom_armor_display {
+    ($($name: ident),*) => {
+        $(
+            impl std::fmt::Display for $name {
+                fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                    match self {
+                        Self::Vanilla(vanilla) => std::fmt::Display::fmt(vanilla, f),
+                        Self::Custom(id) => f.write_str(id),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_custom_armor_display!(
+    MinecraftArmorMaterial,
+    MinecraftArmorTrim,
+    MinecraftArmorTrimMaterial
+);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct VanillaMinecraftArmorTrimData {
-    trim: VanillaMinecraftArmorTrim,
-    material: VanillaMinecraftArmorTrimMaterial,
+    trim: MinecraftArmorTrim,
+    material: MinecraftArmorTrimMaterial,
 }
 
 impl VanillaMinecraftArmorTrimData {
     #[must_use]
-    pub const fn new(
-        trim: VanillaMinecraftArmorTrim,
-        material: VanillaMinecraftArmorTrimMaterial,
+    pub fn new(
+        trim: impl Into<MinecraftArmorTrim>,
+        material: impl Into<MinecraftArmorTrimMaterial>,
     ) -> Self {
-        Self { trim, material }
+        Self {
+            trim: trim.into(),
+            material: material.into(),
+        }
     }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct VanillaMinecraftArmorMaterialData {
-    pub material: VanillaMinecraftArmorMaterial,
+    pub material: MinecraftArmorMaterial,
     pub trims: Vec<VanillaMinecraftArmorTrimData>,
+    pub glint: bool,
 }
 
 impl VanillaMinecraftArmorMaterialData {
@@ -181,18 +300,26 @@ impl VanillaMinecraftArmorMaterialData {
     };
 
     #[must_use]
-    pub const fn new(material: VanillaMinecraftArmorMaterial) -> Self {
+    pub fn new(material: impl Into<MinecraftArmorMaterial>) -> Self {
         Self {
-            material,
+            material: material.into(),
             trims: Vec::new(),
+            glint: false,
         }
     }
 
+    #[must_use]
+    pub fn with_glint(mut self) -> Self {
+        self.glint = true;
+
+        self
+    }
+
     #[must_use]
     pub fn with_trim(
         mut self,
-        trim: VanillaMinecraftArmorTrim,
-        material: VanillaMinecraftArmorTrimMaterial,
+        trim: impl Into<MinecraftArmorTrim>,
+        material: impl Into<MinecraftArmorTrimMaterial>,
     ) -> Self {
         self.trims
             .push(VanillaMinecraftArmorTrimData::new(trim, material));
@@ -205,11 +332,30 @@ impl TryFrom<String> for VanillaMinecraftArmorMaterialData {
     type Error = ArmorManagerError;
 
     fn try_from(value: String) -> Result<Self, Self::Error> {
+        // Modifiers are appended with a `+` (which may have already been decoded into a space)
+        let mut modifiers = value.split(['+', ' ']);
+        let value = modifiers.next().unwrap_or_default();
+
+        let mut glint = false;
+
+        for modifier in modifiers {
+            match modifier.to_lowercase().as_str() {
+                "glint" => glint = true,
+                _ => {
+                    return Err(ArmorManagerError::UnknownArmorModifierError(
+                        modifier.to_string(),
+                    ))
+                }
+            }
+        }
+
         let mut split_values: VecDeque<_> = value.split('_').collect();
-        let mut material: VanillaMinecraftArmorMaterial = if split_values.is_empty() {
+        let mut material = if split_values.is_empty() {
             return Err(ArmorManagerError::EmptyArmorSlotError);
         } else {
-            partial_match(split_values.pop_front().unwrap_or_default())?
+            partial_match_or_custom::<VanillaMinecraftArmorMaterial, MinecraftArmorMaterial>(
+                split_values.pop_front().unwrap_or_default(),
+            )?
         };
 
         let dye_color = if split_values.back().is_some_and(|x| x.contains(',')) {
@@ -228,8 +374,14 @@ impl TryFrom<String> for VanillaMinecraftArmorMaterialData {
             values
                 .chunks_exact(2)
                 .map(|chunk| {
-                    let trim: VanillaMinecraftArmorTrim = partial_match(chunk[0])?;
-                    let material: VanillaMinecraftArmorTrimMaterial = partial_match(chunk[1])?;
+                    let trim = partial_match_or_custom::<
+                        VanillaMinecraftArmorTrim,
+                        MinecraftArmorTrim,
+                    >(chunk[0])?;
+                    let material = partial_match_or_custom::<
+                        VanillaMinecraftArmorTrimMaterial,
+                        MinecraftArmorTrimMaterial,
+                    >(chunk[1])?;
 
                     ArmorManagerResult::Ok(VanillaMinecraftArmorTrimData::new(trim, material))
                 })
@@ -237,8 +389,18 @@ impl TryFrom<String> for VanillaMinecraftArmorMaterialData {
                 .collect::<Vec<_>>()
         };
 
-        if let (Some(dye_color), VanillaMinecraftArmorMaterial::Leather(_)) = (dye_color, material)
-        {
+        // Dye colors only apply to leather, which may also have been given by a partial name
+        let is_leather = match &material {
+            MinecraftArmorMaterial::Vanilla(material) => {
+                matches!(material, VanillaMinecraftArmorMaterial::Leather(_))
+            }
+            MinecraftArmorMaterial::Custom(id) => matches!(
+                partial_match::<VanillaMinecraftArmorMaterial>(id),
+                Ok(VanillaMinecraftArmorMaterial::Leather(_))
+            ),
+        };
+
+        if let (Some(dye_color), true) = (dye_color, is_leather) {
             let rgb = dye_color
                 .split(',')
                 .map(|x| x.parse::<u8>())
@@ -246,15 +408,20 @@ impl TryFrom<String> for VanillaMinecraftArmorMaterialData {
 
             if let Ok([r, g, b]) = rgb.as_deref() {
                 material =
-                    VanillaMinecraftArmorMaterial::Leather(LeatherArmorColor(Rgb([*r, *g, *b])));
+                    VanillaMinecraftArmorMaterial::Leather(LeatherArmorColor(Rgb([*r, *g, *b])))
+                        .into();
             }
         }
 
-        Ok(Self { material, trims })
+        Ok(Self {
+            material,
+            trims,
+            glint,
+        })
     }
 }
 
-fn partial_match<E>(value: &str) -> ArmorManagerResult<E>
+pub(super) fn partial_match<E>(value: &str) -> ArmorManagerResult<E>
 where
     E: IntoEnumIterator + ToString,
 {
@@ -269,6 +436,53 @@ where
         ))
 }
 
+/// Matches a value against the full names of the vanilla entries first, falling back to treating it as the
+/// id of a custom entry loaded from a resource pack.
+///
+/// Custom ids are either a plain name (e.g. `ruby`) or a namespaced one (e.g. `mymod:ruby`).
+/// Since underscores separate the armor components, underscores in custom names are written as hyphens.
+///
+/// Partial vanilla names (e.g. `dia`) are parsed as custom ids too, since a custom entry with that exact id
+/// takes precedence over them. The armor manager resolves the ones that aren't registered back into vanilla entries.
+fn partial_match_or_custom<E, C>(value: &str) -> ArmorManagerResult<C>
+where
+    E: IntoEnumIterator + ToString,
+    C: From<E> + From<String>,
+{
+    let id = parse_custom_id(value)?;
+
+    if let Some(vanilla) = E::iter().find(|x| x.to_string().eq_ignore_ascii_case(&id)) {
+        return Ok(C::from(vanilla));
+    }
+
+    Ok(C::from(id))
+}
+
+/// Parses a custom id, dropping the vanilla namespace (`minecraft:ruby` is the same as `ruby`).
+fn parse_custom_id(value: &str) -> ArmorManagerResult<String> {
+    let id = value.to_lowercase();
+    let (namespace, name) = id.split_once(':').unwrap_or((VANILLA_NAMESPACE, &id));
+
+    let is_valid_part = |part: &str| {
+        !part.is_empty()
+            && part
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || matches!(c, '-' | '.'))
+    };
+
+    if !is_valid_part(namespace) || !is_valid_part(name) {
+        return Err(ArmorManagerError::UnknownPartialArmorMaterialName(
+            value.to_string(),
+        ));
+    }
+
+    if namespace == VANILLA_NAMESPACE {
+        Ok(name.to_string())
+    } else {
+        Ok(id)
+    }
+}
+
 impl ArmorMaterial for VanillaMinecraftArmorMaterialData {
     fn get_texture_type(slot: PlayerArmorSlot) -> Option<PlayerPartTextureType> {
         Some(if slot.is_leggings() {
@@ -277,6 +491,10 @@ impl ArmorMaterial for VanillaMinecraftArmorMaterialData {
             Self::ARMOR_TEXTURE_ONE
         })
     }
+
+    fn has_glint(&self) -> bool {
+        self.glint
+    }
 }
 
 impl VanillaMinecraftArmorTrimPalette {
@@ -371,3 +589,70 @@ impl TryFrom<VanillaMinecraftArmorMaterial> for VanillaMinecraftArmorTrimMateria
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn armor_material_data_parses_vanilla_and_custom_ids() {
+        let cases = [
+            (
+                "diamond_bolt_gold",
+                VanillaMinecraftArmorMaterialData::new(VanillaMinecraftArmorMaterial::Diamond)
+                    .with_trim(
+                        VanillaMinecraftArmorTrim::Bolt,
+                        VanillaMinecraftArmorTrimMaterial::Gold,
+                    ),
+            ),
+            (
+                // Partial names are resolved once we know which custom ids exist
+                "dia_bolt_gold",
+                VanillaMinecraftArmorMaterialData::new("dia".to_string()).with_trim(
+                    VanillaMinecraftArmorTrim::Bolt,
+                    VanillaMinecraftArmorTrimMaterial::Gold,
+                ),
+            ),
+            (
+                "minecraft:copper_minecraft:bolt_minecraft:ruby",
+                VanillaMinecraftArmorMaterialData::new("copper".to_string())
+                    .with_trim(VanillaMinecraftArmorTrim::Bolt, "ruby".to_string()),
+            ),
+            (
+                "ruby_swirl_ruby",
+                VanillaMinecraftArmorMaterialData::new("ruby".to_string())
+                    .with_trim("swirl".to_string(), "ruby".to_string()),
+            ),
+            (
+                "mymod:rose-gold_wild_mymod:ruby",
+                VanillaMinecraftArmorMaterialData::new("mymod:rose-gold".to_string())
+                    .with_trim(VanillaMinecraftArmorTrim::Wild, "mymod:ruby".to_string()),
+            ),
+            (
+                "netherite_rib_redstone+glint",
+                VanillaMinecraftArmorMaterialData::new(VanillaMinecraftArmorMaterial::Netherite)
+                    .with_trim(
+                        VanillaMinecraftArmorTrim::Rib,
+                        VanillaMinecraftArmorTrimMaterial::Redstone,
+                    )
+                    .with_glint(),
+            ),
+            (
+                "diamond glint",
+                VanillaMinecraftArmorMaterialData::new(VanillaMinecraftArmorMaterial::Diamond)
+                    .with_glint(),
+            ),
+        ];
+
+        for (value, expected) in cases {
+            assert_eq!(
+                VanillaMinecraftArmorMaterialData::try_from(value.to_string()).unwrap(),
+                expected,
+                "Failed to parse {value}"
+            );
+        }
+
+        assert!(VanillaMinecraftArmorMaterialData::try_from("ruby!".to_string()).is_err());
+        assert!(VanillaMinecraftArmorMaterialData::try_from("diamond+shiny".to_string()).is_err());
+    }
+}
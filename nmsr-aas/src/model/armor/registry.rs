@@ -0,0 +1,243 @@
+use std::collections::HashMap;
+
+use strum::IntoEnumIterator;
+
+use crate::error::{ArmorManagerError, ArmorManagerResult};
+
+use super::{
+    partial_match, MinecraftArmorMaterial, MinecraftArmorTrim, MinecraftArmorTrimMaterial,
+    VanillaMinecraftArmorMaterial, VanillaMinecraftArmorMaterialData, VanillaMinecraftArmorTrim,
+    VanillaMinecraftArmorTrimData, VanillaMinecraftArmorTrimMaterial, VANILLA_NAMESPACE,
+};
+
+/// A custom armor material, trim pattern or trim material found in an asset source.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CustomArmorAsset {
+    pub namespace: String,
+    pub name: String,
+}
+
+impl CustomArmorAsset {
+    /// Returns the id used to refer to this asset in render requests.
+    #[must_use]
+    pub fn id(&self) -> String {
+        let name = self.name.replace('_', "-");
+
+        if self.namespace == VANILLA_NAMESPACE {
+            name
+        } else {
+            format!("{namespace}:{name}", namespace = self.namespace)
+        }
+    }
+}
+
+/// The custom armor assets found in the configured asset sources, keyed by their id.
+#[derive(Debug, Default)]
+pub struct CustomArmorRegistry {
+    pub materials: HashMap<String, CustomArmorAsset>,
+    pub trims: HashMap<String, CustomArmorAsset>,
+    pub trim_materials: HashMap<String, CustomArmorAsset>,
+}
+
+impl CustomArmorRegistry {
+    /// Registers every custom armor asset found in the given asset paths.
+    /// Assets that replace one of the built-in vanilla ones are skipped.
+    pub fn discover<'a>(&mut self, asset_paths: impl IntoIterator<Item = &'a str>) {
+        for asset_path in asset_paths {
+            let Some((namespace, texture)) = asset_path
+                .strip_prefix("assets/")
+                .and_then(|path| path.strip_suffix(".png"))
+                .and_then(|path| path.split_once("/textures/"))
+            else {
+                continue;
+            };
+
+            let Some((folder, name)) = texture.rsplit_once('/') else {
+                continue;
+            };
+
+            let is_vanilla = namespace == VANILLA_NAMESPACE;
+
+            let (registry, name) = match folder {
+                "entity/equipment/humanoid" | "models/armor" => {
+                    let name = if folder == "models/armor" {
+                        name.strip_suffix("_layer_1")
+                    } else {
+                        Some(name).filter(|name| !name.ends_with("_overlay"))
+                    };
+
+                    let is_vanilla = is_vanilla
+                        && VanillaMinecraftArmorMaterial::iter()
+                            .any(|m| m.get_asset_paths(1, false).iter().any(|p| p == asset_path));
+
+                    match name {
+                        Some(name) if !is_vanilla => (&mut self.materials, name),
+                        _ => continue,
+                    }
+                }
+                "trims/entity/humanoid" | "trims/models/armor" => {
+                    let is_vanilla = is_vanilla
+                        && VanillaMinecraftArmorTrim::iter()
+                            .any(|t| t.get_asset_paths(false).iter().any(|p| p == asset_path));
+
+                    if is_vanilla || name.ends_with("_leggings") {
+                        continue;
+                    }
+
+                    (&mut self.trims, name)
+                }
+                "trims/color_palettes" => {
+                    let is_vanilla = is_vanilla
+                        && VanillaMinecraftArmorTrimMaterial::iter()
+                            .any(|m| m.to_string().eq_ignore_ascii_case(name));
+
+                    if is_vanilla || name == "trim_palette" || name.ends_with("_darker") {
+                        continue;
+                    }
+
+                    (&mut self.trim_materials, name)
+                }
+                _ => continue,
+            };
+
+            let asset = CustomArmorAsset {
+                namespace: namespace.to_string(),
+                name: name.to_string(),
+            };
+
+            registry.entry(asset.id()).or_insert(asset);
+        }
+    }
+
+    /// Resolves the custom ids of the given armor into the entries they refer to.
+    ///
+    /// Ids of registered custom assets are kept as they are, while the others are matched against the
+    /// vanilla entries they're a partial name of (e.g. `dia` for diamond).
+    pub fn resolve(
+        &self,
+        data: &VanillaMinecraftArmorMaterialData,
+    ) -> ArmorManagerResult<VanillaMinecraftArmorMaterialData> {
+        let material = match &data.material {
+            MinecraftArmorMaterial::Custom(id) => {
+                Self::resolve_id::<VanillaMinecraftArmorMaterial, _>(&self.materials, id)?
+            }
+            material => material.clone(),
+        };
+
+        let trims = data
+            .trims
+            .iter()
+            .map(|data| {
+                let trim = match &data.trim {
+                    MinecraftArmorTrim::Custom(id) => {
+                        Self::resolve_id::<VanillaMinecraftArmorTrim, _>(&self.trims, id)?
+                    }
+                    trim => trim.clone(),
+                };
+
+                let material = match &data.material {
+                    MinecraftArmorTrimMaterial::Custom(id) => {
+                        Self::resolve_id::<VanillaMinecraftArmorTrimMaterial, _>(
+                            &self.trim_materials,
+                            id,
+                        )?
+                    }
+                    material => material.clone(),
+                };
+
+                Ok(VanillaMinecraftArmorTrimData::new(trim, material))
+            })
+            .collect::<ArmorManagerResult<Vec<_>>>()?;
+
+        Ok(VanillaMinecraftArmorMaterialData {
+            material,
+            trims,
+            glint: data.glint,
+        })
+    }
+
+    fn resolve_id<E, C>(
+        assets: &HashMap<String, CustomArmorAsset>,
+        id: &str,
+    ) -> ArmorManagerResult<C>
+    where
+        E: IntoEnumIterator + ToString,
+        C: From<E> + From<String>,
+    {
+        if assets.contains_key(id) {
+            return Ok(C::from(id.to_string()));
+        }
+
+        partial_match::<E>(id)
+            .map(C::from)
+            .map_err(|_| ArmorManagerError::UnknownCustomArmorAsset(id.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn discover_skips_vanilla_assets() {
+        let mut registry = CustomArmorRegistry::default();
+
+        registry.discover([
+            "assets/minecraft/textures/entity/equipment/humanoid/diamond.png",
+            "assets/minecraft/textures/entity/equipment/humanoid/leather_overlay.png",
+            "assets/minecraft/textures/entity/equipment/humanoid/copper.png",
+            "assets/mymod/textures/models/armor/rose_gold_layer_1.png",
+            "assets/mymod/textures/models/armor/rose_gold_layer_2.png",
+            "assets/minecraft/textures/trims/models/armor/bolt.png",
+            "assets/mymod/textures/trims/entity/humanoid/swirl.png",
+            "assets/mymod/textures/trims/entity/humanoid_leggings/swirl.png",
+            "assets/minecraft/textures/trims/color_palettes/trim_palette.png",
+            "assets/minecraft/textures/trims/color_palettes/gold_darker.png",
+            "assets/minecraft/textures/trims/color_palettes/amethyst.png",
+            "assets/mymod/textures/trims/color_palettes/ruby.png",
+        ]);
+
+        let mut materials: Vec<_> = registry.materials.keys().cloned().collect();
+        materials.sort();
+
+        assert_eq!(materials, ["copper", "mymod:rose-gold"]);
+        assert_eq!(registry.trims.keys().collect::<Vec<_>>(), ["mymod:swirl"]);
+        assert_eq!(
+            registry.trim_materials.keys().collect::<Vec<_>>(),
+            ["mymod:ruby"]
+        );
+    }
+
+    #[test]
+    fn resolve_prefers_custom_ids_over_partial_vanilla_names() {
+        let mut registry = CustomArmorRegistry::default();
+
+        registry.discover([
+            "assets/minecraft/textures/entity/equipment/humanoid/copper.png",
+            "assets/minecraft/textures/entity/equipment/humanoid/net.png",
+        ]);
+
+        let resolve = |value: &str| {
+            registry
+                .resolve(&VanillaMinecraftArmorMaterialData::try_from(value.to_string()).unwrap())
+        };
+
+        assert_eq!(
+            resolve("minecraft:copper").unwrap(),
+            VanillaMinecraftArmorMaterialData::new("copper".to_string())
+        );
+        assert_eq!(
+            resolve("net").unwrap(),
+            VanillaMinecraftArmorMaterialData::new("net".to_string())
+        );
+        assert_eq!(
+            resolve("neth_bo_gol").unwrap(),
+            VanillaMinecraftArmorMaterialData::new(VanillaMinecraftArmorMaterial::Netherite)
+                .with_trim(
+                    VanillaMinecraftArmorTrim::Bolt,
+                    VanillaMinecraftArmorTrimMaterial::Gold
+                )
+        );
+        assert!(resolve("ruby").is_err());
+    }
+}
@@ -0,0 +1,174 @@
+use std::{
+    fmt::Debug,
+    fs::File,
+    io::{ErrorKind, Read},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, PoisonError},
+};
+
+use serde::{Deserialize, Serialize};
+use tokio::fs;
+use zip::ZipArchive;
+
+use crate::error::{ExplainableExt, Result};
+
+/// A local location that game assets (like armor textures and fonts) can be read from.
+///
+/// Every source is expected to follow the vanilla resource layout (i.e. have an `assets/` folder at its root).
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(tag = "type", content = "path", rename_all = "snake_case")]
+pub enum AssetSource {
+    /// A directory containing extracted game assets or an unzipped resource pack.
+    Directory(PathBuf),
+    /// A resource pack zip file.
+    ResourcePack(PathBuf),
+    /// A vanilla Minecraft client `.jar` file.
+    ClientJar(PathBuf),
+}
+
+impl AssetSource {
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        match self {
+            Self::Directory(path) | Self::ResourcePack(path) | Self::ClientJar(path) => path,
+        }
+    }
+
+    /// Opens this source so that assets can be read from it.
+    ///
+    /// Archives are opened and indexed once here, instead of on every asset read.
+    pub async fn open(&self) -> Result<OpenedAssetSource> {
+        let archive = match self {
+            Self::Directory(_) => None,
+            Self::ResourcePack(archive) | Self::ClientJar(archive) => {
+                let archive_path = archive.clone();
+
+                let opened = tokio::task::spawn_blocking(move || {
+                    ZipArchive::new(File::open(archive_path)?).map_err(std::io::Error::other)
+                })
+                .await
+                .map_err(std::io::Error::other)
+                .and_then(|result| result)
+                .explain(format!("Unable to open asset archive {archive:?}"))?;
+
+                Some(Arc::new(Mutex::new(opened)))
+            }
+        };
+
+        Ok(OpenedAssetSource {
+            source: self.clone(),
+            archive,
+        })
+    }
+
+    fn list_directory_assets(
+        root: &Path,
+        directory: &Path,
+        assets: &mut Vec<String>,
+    ) -> std::io::Result<()> {
+        if !directory.is_dir() {
+            return Ok(());
+        }
+
+        for entry in std::fs::read_dir(directory)? {
+            let path = entry?.path();
+
+            if path.is_dir() {
+                Self::list_directory_assets(root, &path, assets)?;
+            } else if let Ok(relative) = path.strip_prefix(root) {
+                let components: Vec<_> = relative
+                    .components()
+                    .map(|c| c.as_os_str().to_string_lossy())
+                    .collect();
+
+                assets.push(components.join("/"));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// An [`AssetSource`] that has been opened, ready to have its assets read.
+#[derive(Clone)]
+pub struct OpenedAssetSource {
+    source: AssetSource,
+    archive: Option<Arc<Mutex<ZipArchive<File>>>>,
+}
+
+impl Debug for OpenedAssetSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.source.fmt(f)
+    }
+}
+
+impl OpenedAssetSource {
+    /// Reads the asset at the given path (relative to the root of the source, e.g. `assets/minecraft/...`).
+    ///
+    /// Returns [`None`] if the source doesn't contain this asset.
+    pub async fn read_asset(&self, asset_path: &str) -> Result<Option<Vec<u8>>> {
+        let root = self.source.path();
+
+        let Some(archive) = &self.archive else {
+            return match fs::read(root.join(asset_path)).await {
+                Ok(bytes) => Ok(Some(bytes)),
+                Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+                Err(e) => {
+                    Err(e).explain(format!("Unable to read asset {asset_path} from {root:?}"))
+                }
+            };
+        };
+
+        let archive = Arc::clone(archive);
+        let entry_path = asset_path.to_string();
+
+        tokio::task::spawn_blocking(move || Self::read_archive_asset(&archive, &entry_path))
+            .await
+            .map_err(std::io::Error::other)
+            .and_then(|result| result)
+            .explain(format!("Unable to read asset {asset_path} from {root:?}"))
+    }
+
+    /// Lists the paths of every asset in this source (relative to the root of the source, e.g. `assets/minecraft/...`).
+    pub async fn list_assets(&self) -> Result<Vec<String>> {
+        if let Some(archive) = &self.archive {
+            let archive = archive.lock().unwrap_or_else(PoisonError::into_inner);
+
+            return Ok(archive.file_names().map(ToOwned::to_owned).collect());
+        }
+
+        let root = self.source.path().to_path_buf();
+
+        tokio::task::spawn_blocking(move || -> std::io::Result<Vec<String>> {
+            let mut assets = Vec::new();
+            AssetSource::list_directory_assets(&root, &root.join("assets"), &mut assets)?;
+
+            Ok(assets)
+        })
+        .await
+        .map_err(std::io::Error::other)
+        .and_then(|result| result)
+        .explain(format!(
+            "Unable to list assets from {:?}",
+            self.source.path()
+        ))
+    }
+
+    fn read_archive_asset(
+        archive: &Mutex<ZipArchive<File>>,
+        asset_path: &str,
+    ) -> std::io::Result<Option<Vec<u8>>> {
+        let mut archive = archive.lock().unwrap_or_else(PoisonError::into_inner);
+
+        let mut entry = match archive.by_name(asset_path) {
+            Ok(entry) => entry,
+            Err(zip::result::ZipError::FileNotFound) => return Ok(None),
+            Err(e) => return Err(std::io::Error::other(e)),
+        };
+
+        let mut bytes = Vec::with_capacity(entry.size() as usize);
+        entry.read_to_end(&mut bytes)?;
+
+        Ok(Some(bytes))
+    }
+}
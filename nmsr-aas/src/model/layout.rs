@@ -0,0 +1,63 @@
+use std::collections::BTreeMap;
+
+use nmsr_rendering::high_level::pipeline::{
+    layout::{SceneBoundingBox, ScenePlayerLayout},
+    scene::Size,
+};
+use serde::Serialize;
+
+/// Where the parts of the entry ended up in a render, so that overlays can be placed on top of them.
+#[derive(Debug, Clone, Serialize)]
+pub struct RenderLayout {
+    pub width: u32,
+    pub height: u32,
+    /// The bounding box of each body part (e.g. `head` or `left_arm`), including its layer.
+    pub body_parts: BTreeMap<&'static str, RenderLayoutBox>,
+    /// The bounding box of each named part that isn't a whole body part, like Ears features (e.g. `Ears Top`) or the cape.
+    pub parts: BTreeMap<String, RenderLayoutBox>,
+}
+
+/// A bounding box in output pixels, rounded outwards to whole pixels.
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct RenderLayoutBox {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl RenderLayoutBox {
+    fn new(bounding_box: SceneBoundingBox, (offset_x, offset_y): (i32, i32)) -> Self {
+        let min = bounding_box.min.floor();
+        let max = bounding_box.max.ceil();
+
+        Self {
+            x: min.x as i32 + offset_x,
+            y: min.y as i32 + offset_y,
+            width: (max.x - min.x) as u32,
+            height: (max.y - min.y) as u32,
+        }
+    }
+}
+
+impl RenderLayout {
+    /// Creates the layout of a render of the given size, where the scene was moved by the given offset (e.g. when trimmed).
+    pub fn new(size: Size, offset: (i32, i32), layout: ScenePlayerLayout) -> Self {
+        Self {
+            width: size.width,
+            height: size.height,
+            body_parts: layout
+                .body_parts
+                .into_iter()
+                .map(|(body_part, bounding_box)| {
+                    (body_part.into(), RenderLayoutBox::new(bounding_box, offset))
+                })
+                .collect(),
+            parts: layout
+                .named_parts
+                .into_iter()
+                .map(|(name, bounding_box)| (name, RenderLayoutBox::new(bounding_box, offset)))
+                .collect(),
+        }
+    }
+}
@@ -1,3 +1,6 @@
 pub mod armor;
+pub mod assets;
+pub mod layout;
+pub mod nametag;
 pub mod request;
 pub mod resolver;
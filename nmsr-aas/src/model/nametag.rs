@@ -0,0 +1,453 @@
+use std::collections::{HashMap, HashSet};
+
+use image::{Rgba, RgbaImage};
+use nmsr_rendering::high_level::{pipeline::scene::SceneNametag, types::PlayerPartTextureType};
+use serde::Deserialize;
+use tokio::fs;
+
+use crate::{
+    config::NametagFontSource,
+    error::{ExplainableExt, NametagFontError, Result},
+    model::assets::OpenedAssetSource,
+};
+
+/// The maximum amount of characters (formatting codes included) that can be written on a name tag.
+pub const MAX_NAMETAG_LENGTH: usize = 64;
+
+/// A font made of bitmap glyphs, used to write name tags like the game does.
+pub struct NametagFont {
+    glyphs: HashMap<char, NametagGlyph>,
+}
+
+struct NametagGlyph {
+    /// The pixels of the glyph, already scaled to the height it's drawn at.
+    image: Option<RgbaImage>,
+    /// Where the top of the glyph is, relative to the top of the line.
+    top: i32,
+    /// How far the next glyph is drawn after this one.
+    advance: u32,
+}
+
+#[derive(Deserialize)]
+struct FontDefinition {
+    providers: Vec<FontProvider>,
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum FontProvider {
+    Bitmap(BitmapFontProvider),
+    Space {
+        advances: HashMap<char, f32>,
+    },
+    Reference {
+        id: String,
+    },
+    #[serde(other)]
+    Unsupported,
+}
+
+#[derive(Deserialize)]
+struct BitmapFontProvider {
+    #[serde(default)]
+    file: Option<String>,
+    #[serde(default = "BitmapFontProvider::default_height")]
+    height: u32,
+    ascent: i32,
+    chars: Vec<String>,
+}
+
+impl BitmapFontProvider {
+    const fn default_height() -> u32 {
+        8
+    }
+}
+
+impl NametagFont {
+    /// The line height of the font, where the baseline is one pixel above the bottom of the line.
+    const LINE_HEIGHT: i32 = 8;
+
+    /// How far a space moves the next glyph, if the font doesn't say.
+    const DEFAULT_SPACE_ADVANCE: u32 = 4;
+
+    /// The color of the name plate behind the text (black, at a quarter of the opacity).
+    const BACKGROUND_COLOR: Rgba<u8> = Rgba([0, 0, 0, 64]);
+    const DEFAULT_TEXT_COLOR: [u8; 3] = [255, 255, 255];
+
+    /// The characters that start a formatting code (e.g. `§c` or `&c` for red text).
+    const FORMATTING_CODE_PREFIXES: [char; 2] = ['§', '&'];
+
+    const DEFAULT_FONT_ID: &'static str = "minecraft:default";
+
+    pub async fn load(source: &NametagFontSource) -> Result<Self> {
+        let mut font = Self {
+            glyphs: HashMap::new(),
+        };
+
+        match source {
+            NametagFontSource::Assets { source } => {
+                let source = source.open().await?;
+                let mut visited = HashSet::new();
+                font.load_font_definition(&source, Self::DEFAULT_FONT_ID, &mut visited)
+                    .await?;
+            }
+            NametagFontSource::GlyphSheet { image, definition } => {
+                let definition_bytes = fs::read(definition).await.explain(format!(
+                    "Unable to read name tag font definition {definition:?}"
+                ))?;
+                let image_bytes = fs::read(image)
+                    .await
+                    .explain(format!("Unable to read name tag font image {image:?}"))?;
+
+                let definition_name = definition.display().to_string();
+                let provider: BitmapFontProvider = serde_json::from_slice(&definition_bytes)
+                    .map_err(|e| NametagFontError::InvalidFontDefinition(definition_name, e))?;
+
+                font.load_bitmap_provider(&provider, &image.display().to_string(), &image_bytes)?;
+            }
+        }
+
+        Ok(font)
+    }
+
+    async fn load_font_definition(
+        &mut self,
+        source: &OpenedAssetSource,
+        id: &str,
+        visited: &mut HashSet<String>,
+    ) -> Result<()> {
+        // Fonts can reference each other, so make sure we don't go around in circles
+        if !visited.insert(id.to_owned()) {
+            return Ok(());
+        }
+
+        let path = Self::get_asset_path(id, "font", ".json");
+        let bytes = source
+            .read_asset(&path)
+            .await?
+            .ok_or_else(|| NametagFontError::MissingFontAsset(path.clone()))?;
+
+        let definition: FontDefinition = serde_json::from_slice(&bytes)
+            .map_err(|e| NametagFontError::InvalidFontDefinition(path.clone(), e))?;
+
+        for provider in definition.providers {
+            match provider {
+                FontProvider::Bitmap(provider) => {
+                    let Some(file) = &provider.file else {
+                        continue;
+                    };
+
+                    let image_path = Self::get_asset_path(file, "textures", "");
+                    let image_bytes = source
+                        .read_asset(&image_path)
+                        .await?
+                        .ok_or_else(|| NametagFontError::MissingFontAsset(image_path.clone()))?;
+
+                    self.load_bitmap_provider(&provider, &image_path, &image_bytes)?;
+                }
+                FontProvider::Space { advances } => {
+                    for (character, advance) in advances {
+                        self.glyphs.entry(character).or_insert(NametagGlyph {
+                            image: None,
+                            top: 0,
+                            advance: advance.round().max(0.0) as u32,
+                        });
+                    }
+                }
+                FontProvider::Reference { id } => {
+                    Box::pin(self.load_font_definition(source, &id, visited)).await?;
+                }
+                FontProvider::Unsupported => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Turns a resource location (e.g. `minecraft:font/ascii.png`) into the path of its asset.
+    fn get_asset_path(id: &str, kind: &str, extension: &str) -> String {
+        let (namespace, path) = id.split_once(':').unwrap_or(("minecraft", id));
+
+        format!("assets/{namespace}/{kind}/{path}{extension}")
+    }
+
+    fn load_bitmap_provider(
+        &mut self,
+        provider: &BitmapFontProvider,
+        image_name: &str,
+        image_bytes: &[u8],
+    ) -> Result<()> {
+        let sheet = image::load_from_memory(image_bytes)
+            .map_err(|e| NametagFontError::FontImageLoadError(image_name.to_owned(), e))?
+            .into_rgba8();
+
+        let rows = provider.chars.len() as u32;
+        let columns = provider
+            .chars
+            .iter()
+            .map(|row| row.chars().count() as u32)
+            .max()
+            .unwrap_or_default();
+
+        if rows == 0 || columns == 0 {
+            return Ok(());
+        }
+
+        let (cell_width, cell_height) = (sheet.width() / columns, sheet.height() / rows);
+        if cell_width == 0 || cell_height == 0 {
+            return Ok(());
+        }
+
+        let scale = provider.height as f32 / cell_height as f32;
+
+        for (row, characters) in provider.chars.iter().enumerate() {
+            for (column, character) in characters.chars().enumerate() {
+                // The null character is used to leave a cell of the sheet empty
+                if character == '\0' || self.glyphs.contains_key(&character) {
+                    continue;
+                }
+
+                let (cell_x, cell_y) = (column as u32 * cell_width, row as u32 * cell_height);
+
+                // Glyphs are as wide as their rightmost visible column
+                let width = (0..cell_width)
+                    .rev()
+                    .find(|&x| {
+                        (0..cell_height).any(|y| sheet.get_pixel(cell_x + x, cell_y + y)[3] != 0)
+                    })
+                    .map_or(0, |x| x + 1);
+
+                let scaled_width = (width as f32 * scale).round() as u32;
+
+                let image = (scaled_width > 0).then(|| {
+                    RgbaImage::from_fn(scaled_width, provider.height, |x, y| {
+                        let source_x = ((x as f32 / scale) as u32).min(cell_width - 1);
+                        let source_y = ((y as f32 / scale) as u32).min(cell_height - 1);
+
+                        *sheet.get_pixel(cell_x + source_x, cell_y + source_y)
+                    })
+                });
+
+                self.glyphs.insert(
+                    character,
+                    NametagGlyph {
+                        image,
+                        top: Self::LINE_HEIGHT - 1 - provider.ascent,
+                        advance: scaled_width + 1,
+                    },
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Writes the given text on a name plate, returning the name tag texture and how much of it the plate covers.
+    ///
+    /// Formatting codes (like `§c` or `&c`) can be used to color the text, or make it bold with `§l`.
+    pub fn render(&self, text: &str) -> (SceneNametag, RgbaImage) {
+        let (texture_width, texture_height) = PlayerPartTextureType::Nametag.get_texture_size();
+
+        let mut layout = Vec::new();
+        let mut x = 0;
+
+        let mut color = Self::DEFAULT_TEXT_COLOR;
+        let mut is_bold = false;
+
+        let mut characters = text.chars();
+        while let Some(character) = characters.next() {
+            if Self::FORMATTING_CODE_PREFIXES.contains(&character) {
+                match characters.next().map(|code| code.to_ascii_lowercase()) {
+                    Some('l') => is_bold = true,
+                    Some('r') => {
+                        color = Self::DEFAULT_TEXT_COLOR;
+                        is_bold = false;
+                    }
+                    Some(code) => {
+                        // Colors also reset the formatting, like in game
+                        if let Some(new_color) = Self::get_formatting_color(code) {
+                            color = new_color;
+                            is_bold = false;
+                        }
+                    }
+                    None => {}
+                }
+
+                continue;
+            }
+
+            let Some(glyph) = self.glyphs.get(&character) else {
+                if character == ' ' {
+                    x += Self::DEFAULT_SPACE_ADVANCE;
+                }
+
+                continue;
+            };
+
+            layout.push((glyph, x, color, is_bold));
+            x += glyph.advance + u32::from(is_bold);
+        }
+
+        // The plate starts one pixel before and above the text, and ends right after it
+        let width = (x + 1).min(texture_width);
+        let nametag = SceneNametag { width };
+
+        let mut texture = RgbaImage::new(texture_width, texture_height);
+
+        for plate_x in 0..width {
+            for plate_y in 0..SceneNametag::HEIGHT {
+                texture.put_pixel(plate_x, plate_y, Self::BACKGROUND_COLOR);
+            }
+        }
+
+        for (glyph, glyph_x, color, is_bold) in layout {
+            let Some(image) = &glyph.image else {
+                continue;
+            };
+
+            for offset in 0..=u32::from(is_bold) {
+                Self::draw_glyph(
+                    &mut texture,
+                    nametag,
+                    image,
+                    1 + glyph_x + offset,
+                    1 + glyph.top,
+                    color,
+                );
+            }
+        }
+
+        (nametag, texture)
+    }
+
+    fn draw_glyph(
+        texture: &mut RgbaImage,
+        nametag: SceneNametag,
+        image: &RgbaImage,
+        x: u32,
+        y: i32,
+        color: [u8; 3],
+    ) {
+        for (glyph_x, glyph_y, pixel) in image.enumerate_pixels() {
+            let (texture_x, texture_y) = (x + glyph_x, y + glyph_y as i32);
+
+            if texture_x >= nametag.width
+                || texture_y < 0
+                || texture_y >= SceneNametag::HEIGHT as i32
+            {
+                continue;
+            }
+
+            let alpha = u32::from(pixel[3]);
+            if alpha == 0 {
+                continue;
+            }
+
+            let background = texture.get_pixel_mut(texture_x, texture_y as u32);
+
+            // The name tag texture is premultiplied, since that's how the renderer blends it
+            let blend = |glyph: u8, tint: u8, background: u8| -> u8 {
+                let glyph = u32::from(glyph) * u32::from(tint) / 255;
+
+                ((glyph * alpha + u32::from(background) * (255 - alpha)) / 255) as u8
+            };
+
+            *background = Rgba([
+                blend(pixel[0], color[0], background[0]),
+                blend(pixel[1], color[1], background[1]),
+                blend(pixel[2], color[2], background[2]),
+                (alpha + u32::from(background[3]) * (255 - alpha) / 255) as u8,
+            ]);
+        }
+    }
+
+    const fn get_formatting_color(code: char) -> Option<[u8; 3]> {
+        Some(match code {
+            '0' => [0x00, 0x00, 0x00],
+            '1' => [0x00, 0x00, 0xAA],
+            '2' => [0x00, 0xAA, 0x00],
+            '3' => [0x00, 0xAA, 0xAA],
+            '4' => [0xAA, 0x00, 0x00],
+            '5' => [0xAA, 0x00, 0xAA],
+            '6' => [0xFF, 0xAA, 0x00],
+            '7' => [0xAA, 0xAA, 0xAA],
+            '8' => [0x55, 0x55, 0x55],
+            '9' => [0x55, 0x55, 0xFF],
+            'a' => [0x55, 0xFF, 0x55],
+            'b' => [0x55, 0xFF, 0xFF],
+            'c' => [0xFF, 0x55, 0x55],
+            'd' => [0xFF, 0x55, 0xFF],
+            'e' => [0xFF, 0xFF, 0x55],
+            'f' => [0xFF, 0xFF, 0xFF],
+            _ => return None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use image::{ImageFormat, Rgba, RgbaImage};
+    use nmsr_rendering::high_level::pipeline::scene::SceneNametag;
+    use std::io::Cursor;
+
+    use super::{BitmapFontProvider, NametagFont};
+
+    fn create_test_font() -> NametagFont {
+        // A sheet with two 8x8 cells: "A" is 5 pixels wide, and "B" is left empty
+        let mut sheet = RgbaImage::new(16, 8);
+        for x in 0..5 {
+            sheet.put_pixel(x, 0, Rgba([255, 255, 255, 255]));
+        }
+
+        let mut bytes = Vec::new();
+        sheet
+            .write_to(&mut Cursor::new(&mut bytes), ImageFormat::Png)
+            .unwrap();
+
+        let provider = BitmapFontProvider {
+            file: None,
+            height: 8,
+            ascent: 7,
+            chars: vec!["AB".to_string()],
+        };
+
+        let mut font = NametagFont {
+            glyphs: Default::default(),
+        };
+        font.load_bitmap_provider(&provider, "test", &bytes)
+            .unwrap();
+
+        font
+    }
+
+    #[test]
+    fn render_measures_text_like_the_game() {
+        let font = create_test_font();
+
+        // Each "A" moves the next glyph by its width and a pixel of spacing, spaces move it by 4
+        let (nametag, texture) = font.render("A A");
+        assert_eq!(
+            nametag,
+            SceneNametag {
+                width: 6 + 4 + 6 + 1
+            }
+        );
+
+        // The plate starts one pixel before the text, which starts right below the plate's top row
+        assert_eq!(*texture.get_pixel(0, 1), NametagFont::BACKGROUND_COLOR);
+        assert_eq!(*texture.get_pixel(1, 1), Rgba([255, 255, 255, 255]));
+        assert_eq!(*texture.get_pixel(nametag.width, 1), Rgba([0, 0, 0, 0]));
+    }
+
+    #[test]
+    fn render_applies_formatting_codes() {
+        let font = create_test_font();
+
+        let (nametag, texture) = font.render("§cA&lA");
+        assert_eq!(nametag, SceneNametag { width: 6 + 7 + 1 });
+
+        assert_eq!(*texture.get_pixel(1, 1), Rgba([0xFF, 0x55, 0x55, 255]));
+        // Bold glyphs are drawn twice, one pixel apart
+        assert_eq!(*texture.get_pixel(7 + 5, 1), Rgba([0xFF, 0x55, 0x55, 255]));
+    }
+}
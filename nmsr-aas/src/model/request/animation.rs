@@ -0,0 +1,468 @@
+use std::{ops::Range, sync::Arc};
+
+use nmsr_rendering::high_level::{
+    parts::provider::animation::{
+        PlayerAnimation, PlayerAnimationChannel, PlayerAnimationEasing, PlayerAnimationKeyframe,
+        PlayerAnimationProperty, PlayerAnimationTarget,
+    },
+    types::PlayerBodyPartType,
+};
+use serde_json::{Map, Value};
+
+use crate::error::{RenderRequestError, Result};
+
+/// The amount of game ticks in a second, which is also the frame rate of animated renders.
+pub const ANIMATION_TICKS_PER_SECOND: f32 = 20.0;
+
+/// The maximum amount of frames that can be rendered for a single animated render.
+pub const MAX_ANIMATION_FRAMES: u32 = 100;
+
+/// An animation uploaded along with a render request.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RenderRequestAnimation {
+    pub animation: Arc<PlayerAnimation>,
+    /// The time (in seconds) of the animation to render when rendering a still image.
+    pub timestamp: f32,
+    /// The range of frames (in game ticks) to render as an animated image, if any.
+    pub frames: Option<Range<u32>>,
+}
+
+impl RenderRequestAnimation {
+    /// Loads either a Bedrock `.animation.json` or an Emotecraft emote.
+    ///
+    /// The value is either the raw bytes of an uploaded file, or the json itself when it was sent as text.
+    ///
+    /// Bedrock files can contain multiple animations, `name` picks one of them (the first one is used otherwise).
+    pub fn parse_animation(value: Value, name: Option<&str>) -> Result<PlayerAnimation> {
+        let value = match value {
+            Value::Array(bytes) => {
+                let bytes = bytes
+                    .iter()
+                    .map(|b| b.as_u64().and_then(|b| u8::try_from(b).ok()))
+                    .collect::<Option<Vec<_>>>()
+                    .ok_or_else(|| invalid("expected a json file"))?;
+
+                serde_json::from_slice(&bytes).map_err(|e| invalid(e.to_string()))?
+            }
+            Value::String(str) => serde_json::from_str(&str).map_err(|e| invalid(e.to_string()))?,
+            value => value,
+        };
+
+        if let Some(animations) = value.get("animations").and_then(Value::as_object) {
+            parse_bedrock_animation(animations, name)
+        } else if let Some(emote) = value.get("emote").and_then(Value::as_object) {
+            parse_emotecraft_animation(emote)
+        } else {
+            Err(invalid(
+                "expected a Bedrock animation or an Emotecraft emote",
+            ))
+        }
+    }
+}
+
+fn invalid(reason: impl Into<String>) -> crate::error::NMSRaaSError {
+    RenderRequestError::InvalidAnimationError(reason.into()).into()
+}
+
+const ROTATION_PROPERTIES: [PlayerAnimationProperty; 3] = [
+    PlayerAnimationProperty::RotationX,
+    PlayerAnimationProperty::RotationY,
+    PlayerAnimationProperty::RotationZ,
+];
+
+const OFFSET_PROPERTIES: [PlayerAnimationProperty; 3] = [
+    PlayerAnimationProperty::OffsetX,
+    PlayerAnimationProperty::OffsetY,
+    PlayerAnimationProperty::OffsetZ,
+];
+
+fn parse_bedrock_animation(
+    animations: &Map<String, Value>,
+    name: Option<&str>,
+) -> Result<PlayerAnimation> {
+    let animation = match name {
+        Some(name) => animations
+            .get(name)
+            .or_else(|| animations.get(&format!("animation.{name}"))),
+        None => animations.values().next(),
+    }
+    .and_then(Value::as_object)
+    .ok_or_else(|| invalid("the requested animation does not exist"))?;
+
+    let mut channels = Vec::new();
+
+    if let Some(bones) = animation.get("bones").and_then(Value::as_object) {
+        for (bone, bone_value) in bones {
+            let Some(target) = PlayerAnimationTarget::from_bone_name(bone) else {
+                continue;
+            };
+
+            let properties = [
+                ("rotation", ROTATION_PROPERTIES),
+                ("position", OFFSET_PROPERTIES),
+            ];
+
+            for (property_name, properties) in properties {
+                if let Some(value) = bone_value.get(property_name) {
+                    let keyframes = parse_bedrock_keyframes(value)?;
+
+                    // Bedrock's X and Y axes are flipped compared to the game's model axes,
+                    // for both rotations and positions
+                    for (axis, property) in properties.into_iter().enumerate() {
+                        let sign = if axis == 2 { 1.0 } else { -1.0 };
+
+                        let keyframes = keyframes
+                            .iter()
+                            .map(|keyframe| PlayerAnimationKeyframe {
+                                time: keyframe.time,
+                                value: keyframe.post[axis] * sign,
+                                pre_value: keyframe.pre.map(|pre| pre[axis] * sign),
+                                easing: keyframe.easing,
+                            })
+                            .collect();
+
+                        channels.push(PlayerAnimationChannel {
+                            target,
+                            property,
+                            keyframes,
+                        });
+                    }
+                }
+            }
+        }
+    }
+
+    let length = animation
+        .get("animation_length")
+        .and_then(Value::as_f64)
+        .map(|l| l as f32)
+        .unwrap_or_else(|| get_last_keyframe_time(&channels));
+
+    let looping = matches!(animation.get("loop"), Some(Value::Bool(true)));
+
+    Ok(PlayerAnimation {
+        length,
+        loop_start: looping.then_some(0.0),
+        channels,
+    })
+}
+
+/// A keyframe of a bone's property, with the values of every axis.
+struct BedrockKeyframe {
+    time: f32,
+    /// The value blended into when coming from the previous keyframe, if it differs from [`Self::post`].
+    pre: Option<[f32; 3]>,
+    post: [f32; 3],
+    easing: PlayerAnimationEasing,
+}
+
+/// Parses the keyframes of a bone's property, which are either a constant value or a map of times to values.
+fn parse_bedrock_keyframes(value: &Value) -> Result<Vec<BedrockKeyframe>> {
+    let Some(keyframes) = value.as_object() else {
+        return Ok(vec![BedrockKeyframe {
+            time: 0.0,
+            pre: None,
+            post: parse_bedrock_vector(value)?,
+            easing: PlayerAnimationEasing::Linear,
+        }]);
+    };
+
+    let mut result = keyframes
+        .iter()
+        .map(|(time, value)| {
+            let time = time
+                .parse::<f32>()
+                .map_err(|_| invalid(format!("invalid keyframe time {time}")))?;
+
+            // Keyframes can have a different value before and after them (jumping from one to the other)
+            let pre = value.get("pre").map(parse_bedrock_vector).transpose()?;
+            let post = value.get("post").map(parse_bedrock_vector).transpose()?;
+
+            // The previous keyframe's lerp mode says how to get to this one, which we fix up below
+            let easing = match value.get("lerp_mode").and_then(Value::as_str) {
+                Some("step") => PlayerAnimationEasing::Constant,
+                _ => PlayerAnimationEasing::Linear,
+            };
+
+            let (pre, post) = match (pre, post) {
+                (None, None) => (None, parse_bedrock_vector(value)?),
+                (pre, Some(post)) => (pre, post),
+                (Some(pre), None) => (None, pre),
+            };
+
+            Ok(BedrockKeyframe {
+                time,
+                pre,
+                post,
+                easing,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    result.sort_by(|a, b| a.time.total_cmp(&b.time));
+
+    for index in (1..result.len()).rev() {
+        result[index].easing = result[index - 1].easing;
+    }
+
+    Ok(result)
+}
+
+/// Parses a vector of 3 values, which can either be numbers or numbers written as strings.
+///
+/// We don't evaluate Molang, so animations using expressions that aren't plain numbers are rejected.
+fn parse_bedrock_vector(value: &Value) -> Result<[f32; 3]> {
+    let parse_value = |value: &Value| match value {
+        Value::Number(number) => Ok(number.as_f64().unwrap_or_default() as f32),
+        Value::String(str) => str.trim().parse().map_err(|_| {
+            invalid(format!(
+                "Molang expressions aren't supported, only numbers are ({str})"
+            ))
+        }),
+        _ => Err(invalid(format!("invalid keyframe value {value}"))),
+    };
+
+    match value {
+        Value::Array(values) if values.len() == 3 => Ok([
+            parse_value(&values[0])?,
+            parse_value(&values[1])?,
+            parse_value(&values[2])?,
+        ]),
+        Value::Number(_) | Value::String(_) => Ok([parse_value(value)?; 3]),
+        _ => Err(invalid(format!("invalid keyframe value {value}"))),
+    }
+}
+
+fn parse_emotecraft_animation(emote: &Map<String, Value>) -> Result<PlayerAnimation> {
+    let get_tick = |name: &str| {
+        emote
+            .get(name)
+            .and_then(Value::as_f64)
+            .map(|t| t as f32 / ANIMATION_TICKS_PER_SECOND)
+    };
+
+    let get_bool = |name: &str| match emote.get(name) {
+        Some(Value::Bool(value)) => *value,
+        Some(Value::String(value)) => value.eq_ignore_ascii_case("true"),
+        _ => false,
+    };
+
+    let is_degrees = get_bool("degrees");
+
+    let moves = emote
+        .get("moves")
+        .and_then(Value::as_array)
+        .ok_or_else(|| invalid("the emote has no moves"))?;
+
+    let mut channels: Vec<PlayerAnimationChannel> = Vec::new();
+
+    for emote_move in moves {
+        let Some(time) = emote_move
+            .get("tick")
+            .and_then(Value::as_f64)
+            .map(|t| t as f32 / ANIMATION_TICKS_PER_SECOND)
+        else {
+            continue;
+        };
+
+        let easing = emote_move
+            .get("easing")
+            .and_then(Value::as_str)
+            .and_then(PlayerAnimationEasing::from_name)
+            .unwrap_or_default();
+
+        for (part_name, axes) in emote_move.as_object().into_iter().flatten() {
+            let target = match part_name.as_str() {
+                // The emote's body moves the whole player, while the torso only moves the body part
+                "body" => PlayerAnimationTarget::Model,
+                "torso" => PlayerAnimationTarget::BodyPart(PlayerBodyPartType::Body),
+                name => match PlayerAnimationTarget::from_bone_name(name) {
+                    Some(target) => target,
+                    None => continue,
+                },
+            };
+
+            for (axis, value) in axes.as_object().into_iter().flatten() {
+                let Some(value) = value.as_f64().map(|v| v as f32) else {
+                    continue;
+                };
+
+                let (property, value) = match axis.as_str() {
+                    "pitch" | "yaw" | "roll" => {
+                        let property = match axis.as_str() {
+                            "pitch" => PlayerAnimationProperty::RotationX,
+                            "yaw" => PlayerAnimationProperty::RotationY,
+                            _ => PlayerAnimationProperty::RotationZ,
+                        };
+
+                        let value = if is_degrees {
+                            value
+                        } else {
+                            value.to_degrees()
+                        };
+
+                        (property, value)
+                    }
+                    // Bends aren't supported, since our body parts are a single cube
+                    "x" | "y" | "z" => {
+                        let index = match axis.as_str() {
+                            "x" => 0,
+                            "y" => 1,
+                            _ => 2,
+                        };
+
+                        let value = if target == PlayerAnimationTarget::Model {
+                            // The whole player is moved in blocks, with Y pointing up
+                            let sign = if index == 1 { -1.0 } else { 1.0 };
+                            value * 16.0 * sign
+                        } else {
+                            // Body parts are positioned using their pivot, we only want the offset from it
+                            value - get_emotecraft_default_position(target)[index]
+                        };
+
+                        (OFFSET_PROPERTIES[index], value)
+                    }
+                    _ => continue,
+                };
+
+                let keyframe = PlayerAnimationKeyframe {
+                    time,
+                    value,
+                    pre_value: None,
+                    easing,
+                };
+
+                match channels
+                    .iter_mut()
+                    .find(|c| c.target == target && c.property == property)
+                {
+                    Some(channel) => channel.keyframes.push(keyframe),
+                    None => channels.push(PlayerAnimationChannel {
+                        target,
+                        property,
+                        keyframes: vec![keyframe],
+                    }),
+                }
+            }
+        }
+    }
+
+    for channel in &mut channels {
+        channel.keyframes.sort_by(|a, b| a.time.total_cmp(&b.time));
+
+        // Emotes start out from the default pose
+        if channel.keyframes.first().is_some_and(|k| k.time > 0.0) {
+            channel.keyframes.insert(
+                0,
+                PlayerAnimationKeyframe {
+                    time: 0.0,
+                    value: 0.0,
+                    pre_value: None,
+                    easing: PlayerAnimationEasing::Linear,
+                },
+            );
+        }
+    }
+
+    let length = get_tick("endTick").unwrap_or_else(|| get_last_keyframe_time(&channels));
+
+    let loop_start = get_bool("isLoop").then(|| get_tick("returnTick").unwrap_or_default());
+
+    Ok(PlayerAnimation {
+        length,
+        loop_start,
+        channels,
+    })
+}
+
+/// Returns where Emotecraft places a body part when it isn't moved, which is the body part's pivot.
+fn get_emotecraft_default_position(target: PlayerAnimationTarget) -> [f32; 3] {
+    match target {
+        PlayerAnimationTarget::BodyPart(PlayerBodyPartType::RightArm) => [-5.0, 2.0, 0.0],
+        PlayerAnimationTarget::BodyPart(PlayerBodyPartType::LeftArm) => [5.0, 2.0, 0.0],
+        PlayerAnimationTarget::BodyPart(PlayerBodyPartType::RightLeg) => [-1.9, 12.0, 0.0],
+        PlayerAnimationTarget::BodyPart(PlayerBodyPartType::LeftLeg) => [1.9, 12.0, 0.0],
+        _ => [0.0; 3],
+    }
+}
+
+fn get_last_keyframe_time(channels: &[PlayerAnimationChannel]) -> f32 {
+    channels
+        .iter()
+        .filter_map(|c| c.keyframes.last())
+        .map(|k| k.time)
+        .fold(0.0, f32::max)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn parse_bedrock_and_emotecraft_animations() {
+        let bedrock = json!({
+            "format_version": "1.8.0",
+            "animations": {
+                "animation.player.wave": {
+                    "loop": true,
+                    "animation_length": 1.0,
+                    "bones": {
+                        "rightArm": {
+                            "rotation": { "0.0": [0, 0, 0], "0.5": [0, 0, "120"] }
+                        }
+                    }
+                }
+            }
+        });
+
+        let emote = json!({
+            "emote": {
+                "isLoop": "true",
+                "endTick": 20,
+                "degrees": true,
+                "moves": [{ "tick": 10, "easing": "LINEAR", "rightArm": { "roll": 120 } }]
+            }
+        });
+
+        for value in [bedrock, emote] {
+            let animation = RenderRequestAnimation::parse_animation(value, None).unwrap();
+
+            assert_eq!(animation.length, 1.0);
+            assert_eq!(animation.loop_start, Some(0.0));
+            assert_eq!(animation.sample(0.25).right_arm.rotation.z, 60.0);
+        }
+    }
+
+    #[test]
+    fn parse_bedrock_pre_post_keyframes_and_reject_molang() {
+        let animation = |rotation| {
+            json!({
+                "format_version": "1.8.0",
+                "animations": {
+                    "animation.player.jump": {
+                        "animation_length": 2.0,
+                        "bones": { "head": { "rotation": rotation } }
+                    }
+                }
+            })
+        };
+
+        let keyframes = json!({
+            "0.0": [0, 0, 0],
+            "1.0": { "pre": [0, 30, 0], "post": [0, 90, 0] },
+            "2.0": [0, 0, 0]
+        });
+
+        let parsed = RenderRequestAnimation::parse_animation(animation(keyframes), None).unwrap();
+
+        // Bedrock's Y axis is flipped compared to ours
+        assert_eq!(parsed.sample(0.5).head.rotation.y, -15.0);
+        assert_eq!(parsed.sample(1.5).head.rotation.y, -45.0);
+
+        let molang = json!({ "0.0": [0, "math.sin(query.anim_time * 90) * 30", 0] });
+
+        assert!(RenderRequestAnimation::parse_animation(animation(molang), None).is_err());
+    }
+}
@@ -23,7 +23,7 @@ pub struct ResolvedModelTexturesCacheHandler {
 }
 
 #[async_trait]
-impl CacheHandler<RenderRequestEntry, ResolvedRenderEntryTextures, ModelCacheConfiguration, [u8; 1]>
+impl CacheHandler<RenderRequestEntry, ResolvedRenderEntryTextures, ModelCacheConfiguration, Vec<u8>>
     for ResolvedModelTexturesCacheHandler
 {
     #[inline]
@@ -63,7 +63,7 @@ impl CacheHandler<RenderRequestEntry, ResolvedRenderEntryTextures, ModelCacheCon
         &self,
         entry: &RenderRequestEntry,
         config: &ModelCacheConfiguration,
-        _marker: &[u8; 1],
+        _marker: &Vec<u8>,
         marker_metadata: Metadata,
     ) -> Result<bool> {
         config.is_expired(entry, &marker_metadata)
@@ -119,7 +119,7 @@ impl CacheHandler<RenderRequestEntry, ResolvedRenderEntryTextures, ModelCacheCon
         entry: &RenderRequestEntry,
         config: &ModelCacheConfiguration,
         base: &Path,
-        marker: &[u8; 1],
+        marker: &Vec<u8>,
     ) -> Result<Option<ResolvedRenderEntryTextures>> {
         let mut textures = BTreeMap::new();
 
@@ -166,7 +166,7 @@ impl CacheHandler<RenderRequestEntry, ResolvedRenderEntryTextures, ModelCacheCon
                         RenderRequestEntry,
                         ResolvedRenderEntryTextures,
                         ModelCacheConfiguration,
-                        [u8; 1],
+                        Vec<u8>,
                         Self,
                     >::invalidate_self(entry, base)
                     .await?;
@@ -189,7 +189,7 @@ impl CacheHandler<RenderRequestEntry, ResolvedRenderEntryTextures, ModelCacheCon
                     RenderRequestEntry,
                     ResolvedRenderEntryTextures,
                     ModelCacheConfiguration,
-                    [u8; 1],
+                    Vec<u8>,
                     Self,
                 >::invalidate_self(entry, base)
                 .await?;
@@ -207,16 +207,16 @@ impl CacheHandler<RenderRequestEntry, ResolvedRenderEntryTextures, ModelCacheCon
         entry: &RenderRequestEntry,
         _config: &ModelCacheConfiguration,
         marker: &Path,
-    ) -> Result<[u8; 1]> {
+    ) -> Result<Vec<u8>> {
         let result = fs::read(marker)
             .await
             .explain(format!("Unable to read marker file for {entry:?}"))?;
 
-        if result.len() != 1 {
+        if result.is_empty() {
             return Err(ModelCacheError::MarkerMetadataError(entry.clone()).into());
         }
 
-        Ok([result[0]])
+        Ok(result)
     }
 
     async fn write_marker(
@@ -68,7 +68,7 @@ pub struct ModelCache {
         RenderRequestEntry,
         ResolvedRenderEntryTextures,
         ModelCacheConfiguration,
-        [u8; 1],
+        Vec<u8>,
         ResolvedModelTexturesCacheHandler,
     >,
 }
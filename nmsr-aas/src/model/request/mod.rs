@@ -4,15 +4,28 @@ use is_empty::IsEmpty;
 use nmsr_rendering::{
     high_level::{
         camera::Camera,
-        pipeline::scene::{Size, SunInformation},
+        parts::provider::{
+            pose::{PlayerBodyPartRotations, PlayerPose},
+            skull::PlayerSkullPlacement,
+        },
+        pipeline::scene::{
+            SceneAntiAliasing, SceneAuxPass, SceneOutline, SceneShading, Size, SunInformation,
+        },
     },
-    low_level::{EulerRot, Quat, Vec3},
+    low_level::{EulerRot, Quat, Vec3, Vec4},
 };
+use std::ops::Range;
 use strum::{Display, EnumString};
 use uuid::{uuid, Uuid};
 
-use self::entry::{RenderRequestEntry, RenderRequestEntryModel};
+use crate::error::RenderRequestError;
 
+use self::{
+    animation::RenderRequestAnimation,
+    entry::{RenderRequestEntry, RenderRequestEntryModel},
+};
+
+pub mod animation;
 pub mod cache;
 pub mod entry;
 mod mode;
@@ -41,6 +54,7 @@ pub enum RenderRequestFeatures {
     Shadow,
     Shading,
     Cape,
+    Elytra,
     UnProcessedSkin,
     ExtraSettings,
     Custom,
@@ -63,6 +77,11 @@ pub struct RenderRequestExtraSettings {
     #[is_empty(if = "is_false")]
     pub show_back: bool,
 
+    #[is_empty(if = "is_false")]
+    pub gliding: bool,
+    /// The texture hash of the elytra texture to use instead of the cape.
+    pub elytra_texture: Option<String>,
+
     pub width: Option<u32>,
     pub height: Option<u32>,
 
@@ -80,6 +99,252 @@ pub struct RenderRequestExtraSettings {
 
     pub time: Option<f32>,
     pub limb_swing: Option<f32>,
+
+    pub pose: Option<PlayerPose>,
+    pub body_part_rotations: Option<PlayerBodyPartRotations>,
+
+    pub animation: Option<RenderRequestAnimation>,
+
+    pub nametag: Option<String>,
+
+    pub lighting: Option<RenderRequestLighting>,
+    pub shading: Option<SceneShading>,
+    pub outline: Option<RenderRequestOutline>,
+    pub glow: Option<f32>,
+    pub aux_pass: Option<SceneAuxPass>,
+    pub layout: Option<RenderRequestLayout>,
+    pub format: Option<RenderRequestFormat>,
+    pub fit: Option<RenderRequestFit>,
+    pub trim: Option<RenderRequestTrim>,
+    pub pixel_scale: Option<u32>,
+    pub anti_aliasing: Option<SceneAntiAliasing>,
+
+    #[is_empty(if = "is_false")]
+    pub wall_skull: bool,
+    pub skull_rotation: Option<u8>,
+}
+
+/// Overrides for the lighting of the render, anything left out uses the default lighting of the mode.
+#[derive(Debug, Clone, Copy, PartialEq, Default, IsEmpty)]
+pub struct RenderRequestLighting {
+    /// The direction the key light shines towards.
+    pub direction: Option<Vec3>,
+    pub intensity: Option<f32>,
+    pub ambient: Option<f32>,
+    /// The color of the key light, with each channel going from 0 to 1.
+    pub color: Option<Vec3>,
+
+    pub fill_direction: Option<Vec3>,
+    pub fill_intensity: Option<f32>,
+    pub fill_color: Option<Vec3>,
+}
+
+impl RenderRequestLighting {
+    /// The direction the fill light shines towards when only its intensity is given (up and towards the back).
+    const DEFAULT_FILL_DIRECTION: Vec3 = Vec3::new(0.0, 1.0, 1.0);
+
+    fn apply(&self, mut sun: SunInformation) -> SunInformation {
+        if let Some(direction) = self.direction {
+            sun.direction = direction;
+        }
+
+        if let Some(intensity) = self.intensity {
+            sun.intensity = intensity;
+        }
+
+        if let Some(ambient) = self.ambient {
+            sun.ambient = ambient;
+        }
+
+        if let Some(color) = self.color {
+            sun = sun.with_color(color);
+        }
+
+        let has_fill_light = self.fill_direction.is_some()
+            || self.fill_intensity.is_some()
+            || self.fill_color.is_some();
+
+        if has_fill_light {
+            sun = sun.with_fill_light(
+                self.fill_direction.unwrap_or(Self::DEFAULT_FILL_DIRECTION),
+                self.fill_intensity.unwrap_or(1.0),
+                self.fill_color.unwrap_or(Vec3::ONE),
+            );
+        }
+
+        sun
+    }
+}
+
+/// An outline drawn around the silhouette of the render, written as `<width>[,<color>][,inner]`
+/// (e.g. `2,ffffff` for a white sticker outline, or `1,000000,inner` to also outline the inner edges).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RenderRequestOutline {
+    /// How thick the outline is, in pixels.
+    pub width: u32,
+    /// The RGBA color of the outline, written as `RRGGBB` or `RRGGBBAA`.
+    pub color: [u8; 4],
+    pub inner_edges: bool,
+}
+
+impl Default for RenderRequestOutline {
+    fn default() -> Self {
+        Self {
+            width: 2,
+            color: [255; 4],
+            inner_edges: false,
+        }
+    }
+}
+
+impl RenderRequestOutline {
+    pub const MAX_WIDTH: u32 = 16;
+
+    fn parse_color(value: &str) -> Option<[u8; 4]> {
+        let value = value.strip_prefix('#').unwrap_or(value);
+
+        if !value.chars().all(|c| c.is_ascii_hexdigit()) {
+            return None;
+        }
+
+        let color = u32::from_str_radix(value, 16).ok()?;
+
+        match value.len() {
+            6 => Some((color << 8 | 0xFF).to_be_bytes()),
+            8 => Some(color.to_be_bytes()),
+            _ => None,
+        }
+    }
+}
+
+impl TryFrom<String> for RenderRequestOutline {
+    type Error = RenderRequestError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let invalid = || {
+            RenderRequestError::InvalidRenderSettingError(
+                "outline",
+                "written as <width>[,<color>][,inner] (e.g. 2,ffffff)".to_string(),
+            )
+        };
+
+        let mut outline = Self::default();
+        let mut values = value.split(',').filter(|value| !value.is_empty());
+
+        if let Some(width) = values.next() {
+            outline.width = width.parse().map_err(|_| invalid())?;
+        }
+
+        for value in values {
+            if value.eq_ignore_ascii_case("inner") {
+                outline.inner_edges = true;
+            } else {
+                outline.color = Self::parse_color(value).ok_or_else(invalid)?;
+            }
+        }
+
+        Ok(outline)
+    }
+}
+
+/// Frames the camera around the generated parts instead of using the framing of the mode, written as `[<padding>]`
+/// (the fraction of the render left empty on each side, e.g. `0.1`).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RenderRequestFit {
+    pub padding: f32,
+}
+
+impl Default for RenderRequestFit {
+    fn default() -> Self {
+        Self { padding: 0.05 }
+    }
+}
+
+impl RenderRequestFit {
+    pub const MAX_PADDING: f32 = 0.4;
+}
+
+impl TryFrom<String> for RenderRequestFit {
+    type Error = RenderRequestError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        if value.is_empty() {
+            return Ok(Self::default());
+        }
+
+        let padding = value.parse().map_err(|_| {
+            RenderRequestError::InvalidRenderSettingError(
+                "fit",
+                "written as the padding around the entry (e.g. 0.1)".to_string(),
+            )
+        })?;
+
+        Ok(Self { padding })
+    }
+}
+
+/// Crops the transparent margins around the render, then pads it back.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct RenderRequestTrim {
+    /// How many transparent pixels are left on each side of the entry.
+    pub padding: u32,
+    /// Whether the render is padded to a square, with the entry in the middle.
+    pub square: bool,
+}
+
+impl RenderRequestTrim {
+    pub const MAX_PADDING: u32 = 256;
+}
+
+/// How the layout of the render (where each part of the entry ended up) is returned, written as `header` or `json`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RenderRequestLayout {
+    /// The layout is sent as JSON in the `X-NMSR-Layout` header, next to the render.
+    Header,
+    /// The layout is sent as JSON instead of the render.
+    Json,
+}
+
+impl TryFrom<String> for RenderRequestLayout {
+    type Error = RenderRequestError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        match value.to_ascii_lowercase().as_str() {
+            "" | "header" => Ok(Self::Header),
+            "json" => Ok(Self::Json),
+            _ => Err(RenderRequestError::InvalidRenderSettingError(
+                "layout",
+                "either header or json".to_string(),
+            )),
+        }
+    }
+}
+
+/// The format of the render, written as `png`, `svg` or `glb`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RenderRequestFormat {
+    #[default]
+    Png,
+    /// One polygon for each visible texel, which only orthographic modes can be drawn as.
+    Svg,
+    /// A binary glTF 2.0 model of the posed entry, which only the export mode can be exported as (instead of a Blockbench project).
+    Glb,
+}
+
+impl TryFrom<String> for RenderRequestFormat {
+    type Error = RenderRequestError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        match value.to_ascii_lowercase().as_str() {
+            "png" => Ok(Self::Png),
+            "svg" => Ok(Self::Svg),
+            "glb" | "gltf" => Ok(Self::Glb),
+            _ => Err(RenderRequestError::InvalidRenderSettingError(
+                "format",
+                "either png, svg or glb".to_string(),
+            )),
+        }
+    }
 }
 
 impl RenderRequestExtraSettings {
@@ -182,6 +447,13 @@ impl RenderRequest {
                 }
             }
 
+            if let Some(skull) = self.get_skull_placement() {
+                let head_center = skull.get_head_center();
+
+                camera.set_look_at_y(head_center.y);
+                camera.set_look_at_z(head_center.z);
+            }
+
             if self.mode.is_custom() {
                 if let Some(x_pos) = settings.x_pos {
                     camera.set_look_at_x(x_pos);
@@ -241,6 +513,27 @@ impl RenderRequest {
             return SunInformation::new([0.0; 3].into(), 0.0, 1.0);
         }
 
+        let mut sun = self.get_default_lighting();
+
+        if let Some(lighting) = self.extra_settings.as_ref().and_then(|x| x.lighting) {
+            sun = lighting.apply(sun);
+        }
+
+        match self.extra_settings.as_ref().and_then(|x| x.shading) {
+            Some(shading) => sun.with_shading(shading),
+            None => sun,
+        }
+    }
+
+    fn get_default_lighting(&self) -> SunInformation {
+        if self.mode.is_skull() {
+            // Light the skull like items are lit in inventories, where the top is fully lit
+            // and the sides facing the camera are at 80% and 60% brightness
+            let light = Vec3::new(-0.8, -1.0, 0.6);
+
+            return SunInformation::new(light, light.length(), 0.5);
+        }
+
         let camera = self.get_camera();
         let one_eighty_diff = (camera.get_yaw().abs() - 180.0).abs();
         let yaw = if one_eighty_diff < 0.01 {
@@ -273,6 +566,15 @@ impl RenderRequest {
         if let Some(settings) = &self.extra_settings {
             if settings.custom_arm_rotation.is_some() {
                 return settings.custom_arm_rotation;
+            } else if settings.gliding {
+                // Gliding players keep their arms along their body, only bobbing them like the game does
+                return None;
+            } else if matches!(settings.pose, Some(pose) if pose.has_arm_pose())
+                || matches!(settings.body_part_rotations, Some(rotations) if rotations.has_arm_rotations())
+                || settings.animation.is_some()
+            {
+                // The pose (or animation) already places the arms, don't open them any further
+                return Some(0.0);
             } else if settings.time.is_some() {
                 return None;
             };
@@ -281,6 +583,90 @@ impl RenderRequest {
         return Some(self.mode.get_arm_rotation());
     }
 
+    pub(crate) fn get_outline(&self) -> Option<SceneOutline> {
+        let outline = self.extra_settings.as_ref().and_then(|x| x.outline)?;
+
+        Some(SceneOutline {
+            width: outline.width as f32,
+            color: Vec4::from_array(outline.color.map(|c| f32::from(c) / 255.0)),
+            inner_edges: outline.inner_edges,
+        })
+    }
+
+    pub(crate) fn get_glow_strength(&self) -> Option<f32> {
+        self.extra_settings.as_ref().and_then(|x| x.glow)
+    }
+
+    pub(crate) fn get_aux_pass(&self) -> Option<SceneAuxPass> {
+        self.extra_settings.as_ref().and_then(|x| x.aux_pass)
+    }
+
+    pub(crate) fn get_layout(&self) -> Option<RenderRequestLayout> {
+        self.extra_settings.as_ref().and_then(|x| x.layout)
+    }
+
+    pub(crate) fn get_format(&self) -> RenderRequestFormat {
+        self.extra_settings
+            .as_ref()
+            .and_then(|x| x.format)
+            .unwrap_or_default()
+    }
+
+    pub(crate) fn get_trim(&self) -> Option<RenderRequestTrim> {
+        self.extra_settings.as_ref().and_then(|x| x.trim)
+    }
+
+    pub(crate) fn get_pixel_scale(&self) -> Option<u32> {
+        self.extra_settings.as_ref().and_then(|x| x.pixel_scale)
+    }
+
+    /// Returns the anti-aliasing to render with, if not the default one.
+    ///
+    /// Pixel-perfect renders don't smooth their edges, unless asked to.
+    pub(crate) fn get_anti_aliasing(&self) -> Option<SceneAntiAliasing> {
+        self.extra_settings
+            .as_ref()
+            .and_then(|x| x.anti_aliasing)
+            .or_else(|| self.get_pixel_scale().map(|_| SceneAntiAliasing::None))
+    }
+
+    /// Returns the padding to leave around the entry if the camera should be framed around it.
+    pub(crate) fn get_fit_padding(&self) -> Option<f32> {
+        self.extra_settings
+            .as_ref()
+            .and_then(|x| x.fit)
+            .map(|fit| fit.padding)
+    }
+
+    /// Returns how the head is placed if this request renders a skull block.
+    pub(crate) fn get_skull_placement(&self) -> Option<PlayerSkullPlacement> {
+        if !self.mode.is_skull() {
+            return None;
+        }
+
+        let placement = match &self.extra_settings {
+            Some(settings) if settings.wall_skull => PlayerSkullPlacement::Wall,
+            Some(settings) => PlayerSkullPlacement::Floor {
+                rotation: settings.skull_rotation.unwrap_or_default(),
+            },
+            None => PlayerSkullPlacement::default(),
+        };
+
+        Some(placement)
+    }
+
+    /// Returns the frames (in game ticks) to render if this request should be rendered as an animated image.
+    pub(crate) fn get_animation_frames(&self) -> Option<Range<u32>> {
+        if self.mode.is_skin() || self.mode.is_cape() || self.mode.is_blockbench_export() {
+            return None;
+        }
+
+        self.extra_settings
+            .as_ref()
+            .and_then(|s| s.animation.as_ref())
+            .and_then(|a| a.frames.clone())
+    }
+
     pub(crate) fn get_shadow_y_pos(&self) -> Option<f32> {
         if self.features.contains(RenderRequestFeatures::Shadow) {
             if (self.mode.is_head() || self.mode.is_head_iso())
@@ -322,6 +708,11 @@ impl RenderRequest {
         let is_grumm = check_id(&request.entry, Self::GRUMM_ID);
         let is_dinnerbone = check_id(&request.entry, Self::DINNERBONE_ID);
 
+        // The game doesn't give skulls any of these special features
+        if request.mode.is_skull() {
+            return request;
+        }
+
         if is_deadmau5 {
             request.features |= enum_set!(RenderRequestFeatures::Deadmau5Ears);
         }
@@ -358,10 +749,24 @@ impl RenderRequest {
                 extra_settings.chestplate = None;
                 extra_settings.leggings = None;
                 extra_settings.boots = None;
+                extra_settings.gliding = false;
+                extra_settings.pose = None;
             }
 
             request.features.remove(RenderRequestFeatures::BodyLayers);
             request.features.remove(RenderRequestFeatures::Cape);
+            request.features.remove(RenderRequestFeatures::Elytra);
+        }
+
+        // Skulls are a block on their own, so they don't wear a helmet, cast the player's shadow or move
+        if request.mode.is_skull() {
+            if let Some(extra_settings) = request.extra_settings.as_mut() {
+                extra_settings.helmet = None;
+                extra_settings.body_part_rotations = None;
+                extra_settings.animation = None;
+            }
+
+            request.features.remove(RenderRequestFeatures::Shadow);
         }
 
         // If the request is custom, we add the custom feature, otherwise we remove it
@@ -34,6 +34,12 @@ pub enum RenderRequestMode {
     FullBodyIso,
     #[strum(serialize = "head_iso", serialize = "headiso")]
     HeadIso,
+    #[strum(
+        serialize = "skull",
+        serialize = "player_head",
+        serialize = "playerhead"
+    )]
+    Skull,
     Custom,
 }
 
@@ -50,7 +56,12 @@ impl RenderRequestMode {
     pub(crate) const fn is_isometric(self) -> bool {
         matches!(
             self,
-            Self::FullBodyIso | Self::HeadIso | Self::FrontBust | Self::FrontFull | Self::Face
+            Self::FullBodyIso
+                | Self::HeadIso
+                | Self::Skull
+                | Self::FrontBust
+                | Self::FrontFull
+                | Self::Face
         )
     }
 
@@ -70,7 +81,7 @@ impl RenderRequestMode {
     }
 
     pub(crate) const fn is_head_or_face(self) -> bool {
-        matches!(self, Self::Head | Self::Face | Self::HeadIso)
+        matches!(self, Self::Head | Self::Face | Self::HeadIso | Self::Skull)
     }
 
     pub(crate) const fn is_head(self) -> bool {
@@ -80,6 +91,10 @@ impl RenderRequestMode {
         matches!(self, Self::HeadIso)
     }
 
+    pub(crate) const fn is_skull(self) -> bool {
+        matches!(self, Self::Skull)
+    }
+
     pub(crate) const fn is_face(self) -> bool {
         matches!(self, Self::Face)
     }
@@ -166,6 +181,11 @@ impl RenderRequestMode {
     pub const MAX_RENDER_WIDTH: u32 = Self::DEFAULT_RENDER_WIDTH * 2;
     pub const MAX_RENDER_HEIGHT: u32 = Self::DEFAULT_RENDER_HEIGHT * 2;
 
+    /// How big poster renders can be on each side, when they are allowed.
+    pub const MAX_POSTER_RENDER_SIZE: u32 = 8192;
+    /// How big the tiles that big renders are split into can be on each side.
+    pub const MAX_TILE_SIZE: u32 = 2048;
+
     pub const MIN_RENDER_WIDTH: u32 = Self::DEFAULT_RENDER_WIDTH / 32;
     pub const MIN_RENDER_HEIGHT: u32 = Self::DEFAULT_RENDER_HEIGHT / 32;
 
@@ -226,6 +246,13 @@ impl RenderRequestMode {
                 pitch: 0.0,
                 roll: 0.0,
             }
+        } else if self.is_skull() {
+            // The same angle the game shows the player head item at in inventories
+            CameraRotation {
+                yaw: 45.0,
+                pitch: 30.0,
+                roll: 0.0,
+            }
         } else if self.is_isometric() {
             CameraRotation {
                 yaw: 45.0,
@@ -263,7 +290,7 @@ impl RenderRequestMode {
             Self::Custom | Self::FullBody | Self::FrontFull | Self::FullBodyIso => {
                 PlayerBodyPartType::iter().collect()
             }
-            Self::Head | Self::HeadIso | Self::Face => {
+            Self::Head | Self::HeadIso | Self::Face | Self::Skull => {
                 vec![PlayerBodyPartType::Head, PlayerBodyPartType::HeadLayer]
             }
             Self::BodyBust | Self::FrontBust => {
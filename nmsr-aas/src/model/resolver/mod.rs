@@ -167,6 +167,8 @@ impl MojangTexture {
 pub struct ResolvedRenderEntryTextures {
     pub model: Option<RenderRequestEntryModel>,
     pub textures: BTreeMap<ResolvedRenderEntryTextureType, MojangTexture>,
+    /// The name of the player these textures belong to, if we know it.
+    pub name: Option<String>,
 }
 
 pub struct ResolvedRenderEntryTexturesMarker {
@@ -188,27 +190,45 @@ impl ResolvedRenderEntryTextures {
     pub const fn new(
         textures: BTreeMap<ResolvedRenderEntryTextureType, MojangTexture>,
         model: Option<RenderRequestEntryModel>,
+        name: Option<String>,
     ) -> Self {
-        Self { model, textures }
+        Self {
+            model,
+            textures,
+            name,
+        }
     }
 
+    /// Reads the textures' marker, which is the model followed by the player's name (if we know it).
     #[must_use]
     pub fn new_from_marker_slice(
         textures: BTreeMap<ResolvedRenderEntryTextureType, MojangTexture>,
         marker: &[u8],
     ) -> Self {
         let model = RenderRequestEntryModel::from_repr(marker[0] as usize);
+        let name = String::from_utf8(marker[1..].to_vec())
+            .ok()
+            .filter(|name| !name.is_empty());
 
-        Self { model, textures }
+        Self {
+            model,
+            textures,
+            name,
+        }
     }
 
     #[must_use]
-    pub fn to_marker_slice(&self) -> [u8; 1] {
+    pub fn to_marker_slice(&self) -> Vec<u8> {
         let model = self
             .model
             .map_or(RenderRequestEntryModel::COUNT as u8, |m| m as u8);
 
-        [model]
+        let mut marker = vec![model];
+        if let Some(name) = &self.name {
+            marker.extend_from_slice(name.as_bytes());
+        }
+
+        marker
     }
 }
 
@@ -262,6 +282,15 @@ impl RenderRequestResolver {
         Ok(texture)
     }
 
+    /// Fetches a texture from Mojang by its hash (e.g. a custom elytra texture), going through the texture cache.
+    pub async fn resolve_texture_hash(&self, texture_hash: &str) -> Result<Vec<u8>> {
+        let texture = self
+            .fetch_texture_from_mojang(texture_hash, None, MojangTextureRequestType::Cape)
+            .await?;
+
+        Ok(texture.data)
+    }
+
     #[instrument(skip(self))]
     async fn resolve_entry_textures(
         &self,
@@ -288,6 +317,7 @@ impl RenderRequestResolver {
         let model: Option<RenderRequestEntryModel>;
         let skin_texture: Option<MojangTexture>;
         let cape_texture: Option<MojangTexture>;
+        let mut name: Option<String> = None;
 
         match &entry {
             RenderRequestEntry::MojangPlayerName(name) => {
@@ -332,6 +362,7 @@ impl RenderRequestResolver {
                     .await?;
 
                 let textures = result.textures()?;
+                name = result.name().map(ToOwned::to_owned);
 
                 let skin = textures
                     .skin()
@@ -412,7 +443,7 @@ impl RenderRequestResolver {
                 .or_insert(skin_texture);
         }
 
-        let result = ResolvedRenderEntryTextures::new(textures, model);
+        let result = ResolvedRenderEntryTextures::new(textures, model, name);
 
         self.model_cache
             .cache_resolved_texture(entry, &result)
@@ -623,6 +654,10 @@ impl RenderRequestResolver {
                     };
                     result.is_fallback_textures = true;
 
+                    if let RenderRequestEntry::MojangPlayerName(name) = &request.entry {
+                        result.name = Some(name.clone());
+                    }
+
                     return Ok(result);
                 }
             }
@@ -667,6 +702,7 @@ impl RenderRequestResolver {
             model: final_model,
             textures,
             is_fallback_textures: false,
+            name: resolved_textures.name,
         })
     }
 
@@ -682,4 +718,6 @@ pub struct ResolvedRenderRequest {
     #[debug(skip)]
     pub textures: BTreeMap<ResolvedRenderEntryTextureType, Vec<u8>>,
     pub is_fallback_textures: bool,
+    /// The name of the player, if the entry was a player.
+    pub name: Option<String>,
 }
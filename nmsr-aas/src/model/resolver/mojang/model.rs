@@ -72,6 +72,8 @@ struct GameProfileProperty {
 
 #[derive(Deserialize, Debug)]
 pub struct GameProfile {
+    #[serde(default)]
+    name: Option<String>,
     #[serde(deserialize_with = "from_properties")]
     properties: HashMap<String, Value>,
 }
@@ -79,6 +81,11 @@ pub struct GameProfile {
 impl GameProfile {
     const TEXTURES_KEY: &'static str = "textures";
 
+    #[must_use]
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
     pub fn textures(&self) -> MojangRequestResult<GameProfileTextures> {
         let textures = self
             .properties
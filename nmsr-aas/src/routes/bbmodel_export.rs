@@ -17,6 +17,7 @@ use nmsr_rendering_blockbench_model_generator_experiment::{
     blockbench::generate_project,
     error::BlockbenchGeneratorError,
     generator::{ModelGenerationProject, ModelProjectImageIO},
+    gltf::generate_gltf_binary,
 };
 use tracing::instrument;
 
@@ -24,15 +25,15 @@ use crate::{
     error::Result,
     model::{
         armor::VanillaMinecraftArmorMaterialData,
-        request::{RenderRequest, RenderRequestFeatures},
+        request::{RenderRequest, RenderRequestFeatures, RenderRequestFormat},
     },
-    routes::render_model::create_part_context,
+    routes::render_model::{create_part_context, load_elytra_texture},
     utils::png::create_png_from_bytes,
 };
 
-use super::{render_model::load_image_raw, NMSRState};
+use super::{render::APPLICATION_JSON_MIME, render_model::load_image_raw, NMSRState};
 
-const APPLICATION_JSON_MIME: &str = "application/json";
+const MODEL_GLTF_BINARY_MIME: &str = "model/gltf-binary";
 
 struct NMSRaaSImageIO;
 
@@ -65,12 +66,15 @@ pub(crate) async fn internal_bbmodel_export(
 ) -> Result<Response> {
     let resolved = state.resolver.resolve(&request).await?;
 
+    let is_gltf = request.get_format() == RenderRequestFormat::Glb;
+    let (mime, extension) = if is_gltf {
+        (MODEL_GLTF_BINARY_MIME, "glb")
+    } else {
+        (APPLICATION_JSON_MIME, "bbmodel")
+    };
+
     if method == Method::HEAD {
-        return Ok(([(
-            CONTENT_TYPE,
-            HeaderValue::from_static(APPLICATION_JSON_MIME),
-        )])
-        .into_response());
+        return Ok(([(CONTENT_TYPE, HeaderValue::from_static(mime))]).into_response());
     }
 
     let mut part_context = create_part_context(&mut request, &resolved);
@@ -81,8 +85,8 @@ pub(crate) async fn internal_bbmodel_export(
 
     let mut textures = HashMap::new();
 
-    for (texture_type, texture_bytes) in resolved.textures {
-        textures.insert(texture_type.into(), load_image_raw(&texture_bytes)?);
+    for (&texture_type, texture_bytes) in &resolved.textures {
+        textures.insert(texture_type.into(), load_image_raw(texture_bytes)?);
     }
 
     if request.features.contains(RenderRequestFeatures::Shadow) {
@@ -110,6 +114,13 @@ pub(crate) async fn internal_bbmodel_export(
         }
     }
 
+    if part_context.has_elytra {
+        textures.insert(
+            PlayerPartTextureType::Elytra,
+            load_elytra_texture(&resolved, &state, &request).await?,
+        );
+    }
+
     let mut blockbench_project =
         ModelGenerationProject::new_with_part_context(NMSRaaSImageIO, part_context);
 
@@ -133,19 +144,19 @@ pub(crate) async fn internal_bbmodel_export(
         blockbench_project.add_texture(texture_type, texture, do_ears_processing)?;
     }
 
-    let result = generate_project(blockbench_project)?;
-
-    let mut res = result.into_response();
+    let mut res = if is_gltf {
+        generate_gltf_binary(blockbench_project)?.into_response()
+    } else {
+        generate_project(blockbench_project)?.into_response()
+    };
 
-    res.headers_mut().insert(
-        CONTENT_TYPE,
-        HeaderValue::from_static(APPLICATION_JSON_MIME),
-    );
+    res.headers_mut()
+        .insert(CONTENT_TYPE, HeaderValue::from_static(mime));
 
     let entry_str = String::try_from(request.entry).unwrap_or("model".to_string());
 
     if let Ok(value) = HeaderValue::from_str(&format!(
-        "attachment; filename={name}.bbmodel",
+        "attachment; filename={name}.{extension}",
         name = entry_str
     )) {
         res.headers_mut().insert(CONTENT_DISPOSITION, value);
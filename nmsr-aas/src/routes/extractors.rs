@@ -5,7 +5,8 @@ use super::{
 use crate::{
     error::{NMSRaaSError, RenderRequestError, Result},
     model::request::{
-        entry::RenderRequestEntry, RenderRequest, RenderRequestExtraSettings, RenderRequestMode,
+        animation::RenderRequestAnimation, entry::RenderRequestEntry, RenderRequest,
+        RenderRequestExtraSettings, RenderRequestMode,
     },
 };
 use axum::{
@@ -16,7 +17,7 @@ use axum_extra::extract::Multipart;
 use hyper::Method;
 use is_empty::IsEmpty;
 use serde_json::{json, Value};
-use std::collections::HashMap;
+use std::{collections::HashMap, sync::Arc};
 
 impl<S> FromRequest<S> for RenderRequest
 where
@@ -35,7 +36,7 @@ where
     /// The entry is in the URL path, and the options are in the query string.
     ///
     async fn from_request(mut request: Request, state: &S) -> Result<Self> {
-        let (mode, entry, mut query) = if request.method() == Method::POST {
+        let (mode, entry, query, animation) = if request.method() == Method::POST {
             let Path(mode_str) = request
                 .extract_parts_with_state::<Path<String>, S>(state)
                 .await
@@ -82,7 +83,7 @@ where
 
             let entry = RenderRequestEntry::new_from_skin_and_cape(query.skin, query.cape);
 
-            (mode, entry, query.query)
+            (mode, entry, query.query, query.animation)
         } else {
             let Path((mode_str, entry_str)) = request
                 .extract_parts_with_state::<Path<(String, String)>, S>(state)
@@ -101,15 +102,62 @@ where
                 .await
                 .map_err(RenderRequestError::from)?;
 
-            (mode, entry, query)
+            (mode, entry, query, None)
         };
 
-        query.validate(mode)?;
+        Self::from_query_params(state, mode, entry, query, animation)
+    }
+}
+
+impl RenderRequest {
+    /// Create a [`RenderRequest`] from an entry and the options it was requested with.
+    ///
+    /// The options are validated against the mode, and the request is cleaned up by the given validator.
+    pub(crate) fn from_query_params<S: RenderRequestValidator>(
+        state: &S,
+        mode: RenderRequestMode,
+        entry: RenderRequestEntry,
+        mut query: RenderRequestQueryParams,
+        animation: Option<Value>,
+    ) -> Result<Self> {
+        query.validate(mode, state.allows_poster_renders())?;
+
+        let animation = animation
+            .map(|animation| -> Result<_> {
+                let animation = RenderRequestAnimation::parse_animation(
+                    animation,
+                    query.animation_name.as_deref(),
+                )?;
+
+                Ok(RenderRequestAnimation {
+                    animation: Arc::new(animation),
+                    timestamp: query.timestamp.unwrap_or_default(),
+                    frames: query.get_animation_frames(),
+                })
+            })
+            .transpose()?;
+
+        if animation.is_none() {
+            let animation_settings = [
+                ("an animation name", query.animation_name.is_some()),
+                ("an animation timestamp", query.timestamp.is_some()),
+                ("animation frames", query.frames.is_some()),
+            ];
+
+            if let Some((setting, _)) = animation_settings.into_iter().find(|(_, set)| *set) {
+                return Err(RenderRequestError::MissingAnimationError(setting).into());
+            }
+        }
 
         let excluded_features = query.get_excluded_features();
 
         let model = query.get_model();
 
+        // These borrow the whole query, so they're computed before its fields are moved out
+        let body_part_rotations = query.get_body_part_rotations();
+        let lighting = query.get_lighting();
+        let trim = query.get_trim();
+
         let extra_settings = Some(RenderRequestExtraSettings {
             width: query.width,
             height: query.height,
@@ -119,6 +167,8 @@ where
             roll: query.roll,
 
             show_back: query.back.is_some(),
+            gliding: query.gliding.is_some(),
+            elytra_texture: query.elytra.filter(|elytra| !elytra.is_empty()),
 
             custom_arm_rotation: query.arms,
             distance: query.distance,
@@ -134,6 +184,28 @@ where
 
             time: query.time,
             limb_swing: query.limb_swing,
+
+            pose: query.pose,
+            body_part_rotations,
+
+            animation,
+
+            nametag: query.nametag,
+
+            lighting,
+            shading: query.shading,
+            outline: query.outline,
+            glow: query.glow,
+            aux_pass: query.pass,
+            layout: query.layout,
+            format: query.format,
+            fit: query.fit,
+            trim,
+            pixel_scale: query.pixel_scale,
+            anti_aliasing: query.aa,
+
+            wall_skull: query.wall.is_some(),
+            skull_rotation: query.skull_rotation,
         })
         .filter(|s| !s.is_empty());
 
@@ -153,6 +225,13 @@ mod tests {
     use axum::{body::Body, debug_handler, extract::State, routing::get, Router};
     use enumset::{enum_set, EnumSet};
     use hyper::Request;
+    use nmsr_rendering::{
+        high_level::{
+            parts::provider::pose::{PlayerBodyPartRotations, PlayerPose},
+            pipeline::scene::{SceneAntiAliasing, SceneAuxPass, SceneShading},
+        },
+        low_level::Vec3,
+    };
     use tokio::sync::mpsc::Sender;
     use tower::ServiceExt;
     use uuid::uuid;
@@ -160,7 +239,9 @@ mod tests {
     use crate::{
         model::request::{
             entry::{RenderRequestEntry, RenderRequestEntryModel},
-            RenderRequest, RenderRequestExtraSettings, RenderRequestFeatures, RenderRequestMode,
+            RenderRequest, RenderRequestExtraSettings, RenderRequestFeatures, RenderRequestFit,
+            RenderRequestFormat, RenderRequestLayout, RenderRequestLighting, RenderRequestMode,
+            RenderRequestOutline, RenderRequestTrim,
         },
         routes::RenderRequestValidator,
     };
@@ -235,7 +316,7 @@ mod tests {
                     mode: RenderRequestMode::FullBody,
                     entry: entry.clone(),
                     model: None,
-                    features: EnumSet::all().difference(enum_set!(RenderRequestFeatures::BodyLayers | RenderRequestFeatures::HatLayer | RenderRequestFeatures::Cape | RenderRequestFeatures::UnProcessedSkin | RenderRequestFeatures::Custom | RenderRequestFeatures::ExtraSettings | RenderRequestFeatures::Deadmau5Ears | RenderRequestFeatures::FlipUpsideDown)),
+                    features: EnumSet::all().difference(enum_set!(RenderRequestFeatures::BodyLayers | RenderRequestFeatures::HatLayer | RenderRequestFeatures::Cape | RenderRequestFeatures::UnProcessedSkin | RenderRequestFeatures::Custom | RenderRequestFeatures::ExtraSettings | RenderRequestFeatures::Deadmau5Ears | RenderRequestFeatures::FlipUpsideDown | RenderRequestFeatures::Elytra)),
                     extra_settings: None
                 },
             ),
@@ -245,7 +326,7 @@ mod tests {
                     mode: RenderRequestMode::FullBody,
                     entry: entry.clone(),
                     model: None,
-                    features: EnumSet::all().difference(enum_set!(RenderRequestFeatures::UnProcessedSkin | RenderRequestFeatures::Custom | RenderRequestFeatures::Deadmau5Ears | RenderRequestFeatures::FlipUpsideDown)),
+                    features: EnumSet::all().difference(enum_set!(RenderRequestFeatures::UnProcessedSkin | RenderRequestFeatures::Custom | RenderRequestFeatures::Deadmau5Ears | RenderRequestFeatures::FlipUpsideDown | RenderRequestFeatures::Elytra)),
                     extra_settings: Some(RenderRequestExtraSettings {
                         yaw: Some(-179.0f32),
                         ..Default::default()
@@ -258,7 +339,7 @@ mod tests {
                     mode: RenderRequestMode::FullBody,
                     entry: entry.clone(),
                     model: None,
-                    features: EnumSet::all().difference(enum_set!(RenderRequestFeatures::UnProcessedSkin | RenderRequestFeatures::Custom | RenderRequestFeatures::Deadmau5Ears | RenderRequestFeatures::FlipUpsideDown)),
+                    features: EnumSet::all().difference(enum_set!(RenderRequestFeatures::UnProcessedSkin | RenderRequestFeatures::Custom | RenderRequestFeatures::Deadmau5Ears | RenderRequestFeatures::FlipUpsideDown | RenderRequestFeatures::Elytra)),
                     extra_settings: Some(RenderRequestExtraSettings {
                         yaw: Some(179.0f32),
                         ..Default::default()
@@ -271,7 +352,7 @@ mod tests {
                     mode: RenderRequestMode::FullBody,
                     entry: entry.clone(),
                     model: None,
-                    features: EnumSet::all().difference(enum_set!(RenderRequestFeatures::UnProcessedSkin | RenderRequestFeatures::Custom | RenderRequestFeatures::Deadmau5Ears | RenderRequestFeatures::FlipUpsideDown)),
+                    features: EnumSet::all().difference(enum_set!(RenderRequestFeatures::UnProcessedSkin | RenderRequestFeatures::Custom | RenderRequestFeatures::Deadmau5Ears | RenderRequestFeatures::FlipUpsideDown | RenderRequestFeatures::Elytra)),
                     extra_settings: Some(RenderRequestExtraSettings {
                         yaw: Some(0.0f32),
                         ..Default::default()
@@ -284,7 +365,7 @@ mod tests {
                     mode: RenderRequestMode::FullBody,
                     entry: entry.clone(),
                     model: None,
-                    features: EnumSet::all().difference(enum_set!(RenderRequestFeatures::UnProcessedSkin | RenderRequestFeatures::Custom | RenderRequestFeatures::Deadmau5Ears | RenderRequestFeatures::FlipUpsideDown)),
+                    features: EnumSet::all().difference(enum_set!(RenderRequestFeatures::UnProcessedSkin | RenderRequestFeatures::Custom | RenderRequestFeatures::Deadmau5Ears | RenderRequestFeatures::FlipUpsideDown | RenderRequestFeatures::Elytra)),
                     extra_settings: Some(RenderRequestExtraSettings {
                         yaw: Some(-5.0f32),
                         ..Default::default()
@@ -297,7 +378,7 @@ mod tests {
                     mode: RenderRequestMode::FullBody,
                     entry: entry.clone(),
                     model: None,
-                    features: EnumSet::all().difference(enum_set!(RenderRequestFeatures::UnProcessedSkin | RenderRequestFeatures::Custom | RenderRequestFeatures::ExtraSettings | RenderRequestFeatures::FlipUpsideDown)),
+                    features: EnumSet::all().difference(enum_set!(RenderRequestFeatures::UnProcessedSkin | RenderRequestFeatures::Custom | RenderRequestFeatures::ExtraSettings | RenderRequestFeatures::FlipUpsideDown | RenderRequestFeatures::Elytra)),
                     extra_settings: None
                 },
             ),
@@ -307,10 +388,244 @@ mod tests {
                     mode: RenderRequestMode::FullBody,
                     entry: entry.clone(),
                     model: None,
-                    features: EnumSet::all().difference(enum_set!(RenderRequestFeatures::UnProcessedSkin | RenderRequestFeatures::Custom | RenderRequestFeatures::ExtraSettings | RenderRequestFeatures::Deadmau5Ears)),
+                    features: EnumSet::all().difference(enum_set!(RenderRequestFeatures::UnProcessedSkin | RenderRequestFeatures::Custom | RenderRequestFeatures::ExtraSettings | RenderRequestFeatures::Deadmau5Ears | RenderRequestFeatures::Elytra)),
                     extra_settings: None
                 },
             ),
+            (
+                "http://localhost:8621/fullbody/ad4569f3-7576-4376-a7c7-8e8cfcd9b832?elytra&gliding",
+                RenderRequest {
+                    mode: RenderRequestMode::FullBody,
+                    entry: entry.clone(),
+                    model: None,
+                    features: EnumSet::all().difference(enum_set!(RenderRequestFeatures::UnProcessedSkin | RenderRequestFeatures::Custom | RenderRequestFeatures::Deadmau5Ears | RenderRequestFeatures::FlipUpsideDown)),
+                    extra_settings: Some(RenderRequestExtraSettings {
+                        gliding: true,
+                        ..Default::default()
+                    })
+                },
+            ),
+            (
+                "http://localhost:8621/fullbody/ad4569f3-7576-4376-a7c7-8e8cfcd9b832?elytra=2340c0e03dd24a11b15a8b33c2a7e9e32abb2051b2481d0ba7defd635ca7a933",
+                RenderRequest {
+                    mode: RenderRequestMode::FullBody,
+                    entry: entry.clone(),
+                    model: None,
+                    features: EnumSet::all().difference(enum_set!(RenderRequestFeatures::UnProcessedSkin | RenderRequestFeatures::Custom | RenderRequestFeatures::Deadmau5Ears | RenderRequestFeatures::FlipUpsideDown)),
+                    extra_settings: Some(RenderRequestExtraSettings {
+                        elytra_texture: Some("2340c0e03dd24a11b15a8b33c2a7e9e32abb2051b2481d0ba7defd635ca7a933".to_string()),
+                        ..Default::default()
+                    })
+                },
+            ),
+            (
+                "http://localhost:8621/fullbody/ad4569f3-7576-4376-a7c7-8e8cfcd9b832?pose=sneak",
+                RenderRequest {
+                    mode: RenderRequestMode::FullBody,
+                    entry: entry.clone(),
+                    model: None,
+                    features: EnumSet::all().difference(enum_set!(RenderRequestFeatures::UnProcessedSkin | RenderRequestFeatures::Custom | RenderRequestFeatures::Deadmau5Ears | RenderRequestFeatures::FlipUpsideDown | RenderRequestFeatures::Elytra)),
+                    extra_settings: Some(RenderRequestExtraSettings {
+                        pose: Some(PlayerPose::Sneaking),
+                        ..Default::default()
+                    })
+                },
+            ),
+            (
+                "http://localhost:8621/custom/ad4569f3-7576-4376-a7c7-8e8cfcd9b832?head=30,-10,0&left_arm=-60,0,10",
+                RenderRequest {
+                    mode: RenderRequestMode::Custom,
+                    entry: entry.clone(),
+                    model: None,
+                    features: EnumSet::all().difference(enum_set!(RenderRequestFeatures::UnProcessedSkin | RenderRequestFeatures::Deadmau5Ears | RenderRequestFeatures::FlipUpsideDown | RenderRequestFeatures::Elytra)),
+                    extra_settings: Some(RenderRequestExtraSettings {
+                        body_part_rotations: Some(PlayerBodyPartRotations {
+                            head: Some(Vec3::new(30.0, -10.0, 0.0)),
+                            left_arm: Some(Vec3::new(-60.0, 0.0, 10.0)),
+                            ..Default::default()
+                        }),
+                        ..Default::default()
+                    })
+                },
+            ),
+            (
+                "http://localhost:8621/custom/ad4569f3-7576-4376-a7c7-8e8cfcd9b832?light=0,-1,1&light_color=255,0,0&fill_intensity=0.5",
+                RenderRequest {
+                    mode: RenderRequestMode::Custom,
+                    entry: entry.clone(),
+                    model: None,
+                    features: EnumSet::all().difference(enum_set!(RenderRequestFeatures::UnProcessedSkin | RenderRequestFeatures::Deadmau5Ears | RenderRequestFeatures::FlipUpsideDown | RenderRequestFeatures::Elytra)),
+                    extra_settings: Some(RenderRequestExtraSettings {
+                        lighting: Some(RenderRequestLighting {
+                            direction: Some(Vec3::new(0.0, -1.0, 1.0)),
+                            color: Some(Vec3::new(1.0, 0.0, 0.0)),
+                            fill_intensity: Some(0.5),
+                            ..Default::default()
+                        }),
+                        ..Default::default()
+                    })
+                },
+            ),
+            (
+                "http://localhost:8621/fullbody/ad4569f3-7576-4376-a7c7-8e8cfcd9b832?outline=1,000000,inner",
+                RenderRequest {
+                    mode: RenderRequestMode::FullBody,
+                    entry: entry.clone(),
+                    model: None,
+                    features: EnumSet::all().difference(enum_set!(RenderRequestFeatures::UnProcessedSkin | RenderRequestFeatures::Custom | RenderRequestFeatures::Deadmau5Ears | RenderRequestFeatures::FlipUpsideDown | RenderRequestFeatures::Elytra)),
+                    extra_settings: Some(RenderRequestExtraSettings {
+                        outline: Some(RenderRequestOutline {
+                            width: 1,
+                            color: [0, 0, 0, 255],
+                            inner_edges: true,
+                        }),
+                        ..Default::default()
+                    })
+                },
+            ),
+            (
+                "http://localhost:8621/fullbody/ad4569f3-7576-4376-a7c7-8e8cfcd9b832?shading=minecraft",
+                RenderRequest {
+                    mode: RenderRequestMode::FullBody,
+                    entry: entry.clone(),
+                    model: None,
+                    features: EnumSet::all().difference(enum_set!(RenderRequestFeatures::UnProcessedSkin | RenderRequestFeatures::Custom | RenderRequestFeatures::Deadmau5Ears | RenderRequestFeatures::FlipUpsideDown | RenderRequestFeatures::Elytra)),
+                    extra_settings: Some(RenderRequestExtraSettings {
+                        shading: Some(SceneShading::Minecraft),
+                        ..Default::default()
+                    })
+                },
+            ),
+            (
+                "http://localhost:8621/fullbody/ad4569f3-7576-4376-a7c7-8e8cfcd9b832?glow=1.5",
+                RenderRequest {
+                    mode: RenderRequestMode::FullBody,
+                    entry: entry.clone(),
+                    model: None,
+                    features: EnumSet::all().difference(enum_set!(RenderRequestFeatures::UnProcessedSkin | RenderRequestFeatures::Custom | RenderRequestFeatures::Deadmau5Ears | RenderRequestFeatures::FlipUpsideDown | RenderRequestFeatures::Elytra)),
+                    extra_settings: Some(RenderRequestExtraSettings {
+                        glow: Some(1.5),
+                        ..Default::default()
+                    })
+                },
+            ),
+            (
+                "http://localhost:8621/fullbody/ad4569f3-7576-4376-a7c7-8e8cfcd9b832?pass=part_id",
+                RenderRequest {
+                    mode: RenderRequestMode::FullBody,
+                    entry: entry.clone(),
+                    model: None,
+                    features: EnumSet::all().difference(enum_set!(RenderRequestFeatures::UnProcessedSkin | RenderRequestFeatures::Custom | RenderRequestFeatures::Deadmau5Ears | RenderRequestFeatures::FlipUpsideDown | RenderRequestFeatures::Elytra)),
+                    extra_settings: Some(RenderRequestExtraSettings {
+                        aux_pass: Some(SceneAuxPass::PartId),
+                        ..Default::default()
+                    })
+                },
+            ),
+            (
+                "http://localhost:8621/fullbody/ad4569f3-7576-4376-a7c7-8e8cfcd9b832?layout=json",
+                RenderRequest {
+                    mode: RenderRequestMode::FullBody,
+                    entry: entry.clone(),
+                    model: None,
+                    features: EnumSet::all().difference(enum_set!(RenderRequestFeatures::UnProcessedSkin | RenderRequestFeatures::Custom | RenderRequestFeatures::Deadmau5Ears | RenderRequestFeatures::FlipUpsideDown | RenderRequestFeatures::Elytra)),
+                    extra_settings: Some(RenderRequestExtraSettings {
+                        layout: Some(RenderRequestLayout::Json),
+                        ..Default::default()
+                    })
+                },
+            ),
+            (
+                "http://localhost:8621/fullbody/ad4569f3-7576-4376-a7c7-8e8cfcd9b832?fit=0.1",
+                RenderRequest {
+                    mode: RenderRequestMode::FullBody,
+                    entry: entry.clone(),
+                    model: None,
+                    features: EnumSet::all().difference(enum_set!(RenderRequestFeatures::UnProcessedSkin | RenderRequestFeatures::Custom | RenderRequestFeatures::Deadmau5Ears | RenderRequestFeatures::FlipUpsideDown | RenderRequestFeatures::Elytra)),
+                    extra_settings: Some(RenderRequestExtraSettings {
+                        fit: Some(RenderRequestFit { padding: 0.1 }),
+                        ..Default::default()
+                    })
+                },
+            ),
+            (
+                "http://localhost:8621/fullbody/ad4569f3-7576-4376-a7c7-8e8cfcd9b832?padding=8&square",
+                RenderRequest {
+                    mode: RenderRequestMode::FullBody,
+                    entry: entry.clone(),
+                    model: None,
+                    features: EnumSet::all().difference(enum_set!(RenderRequestFeatures::UnProcessedSkin | RenderRequestFeatures::Custom | RenderRequestFeatures::Deadmau5Ears | RenderRequestFeatures::FlipUpsideDown | RenderRequestFeatures::Elytra)),
+                    extra_settings: Some(RenderRequestExtraSettings {
+                        trim: Some(RenderRequestTrim { padding: 8, square: true }),
+                        ..Default::default()
+                    })
+                },
+            ),
+            (
+                "http://localhost:8621/fullbody/ad4569f3-7576-4376-a7c7-8e8cfcd9b832?aa=ssaa4",
+                RenderRequest {
+                    mode: RenderRequestMode::FullBody,
+                    entry: entry.clone(),
+                    model: None,
+                    features: EnumSet::all().difference(enum_set!(RenderRequestFeatures::UnProcessedSkin | RenderRequestFeatures::Custom | RenderRequestFeatures::Deadmau5Ears | RenderRequestFeatures::FlipUpsideDown | RenderRequestFeatures::Elytra)),
+                    extra_settings: Some(RenderRequestExtraSettings {
+                        anti_aliasing: Some(SceneAntiAliasing::Ssaa4),
+                        ..Default::default()
+                    })
+                },
+            ),
+            (
+                "http://localhost:8621/frontfull/ad4569f3-7576-4376-a7c7-8e8cfcd9b832?pixel_scale=4",
+                RenderRequest {
+                    mode: RenderRequestMode::FrontFull,
+                    entry: entry.clone(),
+                    model: None,
+                    features: EnumSet::all().difference(enum_set!(RenderRequestFeatures::UnProcessedSkin | RenderRequestFeatures::Custom | RenderRequestFeatures::Deadmau5Ears | RenderRequestFeatures::FlipUpsideDown | RenderRequestFeatures::Elytra)),
+                    extra_settings: Some(RenderRequestExtraSettings {
+                        pixel_scale: Some(4),
+                        ..Default::default()
+                    })
+                },
+            ),
+            (
+                "http://localhost:8621/face/ad4569f3-7576-4376-a7c7-8e8cfcd9b832?format=svg",
+                RenderRequest {
+                    mode: RenderRequestMode::Face,
+                    entry: entry.clone(),
+                    model: None,
+                    features: EnumSet::all().difference(enum_set!(RenderRequestFeatures::UnProcessedSkin | RenderRequestFeatures::Custom | RenderRequestFeatures::Deadmau5Ears | RenderRequestFeatures::FlipUpsideDown | RenderRequestFeatures::Elytra | RenderRequestFeatures::BodyLayers | RenderRequestFeatures::Cape)),
+                    extra_settings: Some(RenderRequestExtraSettings {
+                        format: Some(RenderRequestFormat::Svg),
+                        ..Default::default()
+                    })
+                },
+            ),
+            (
+                "http://localhost:8621/export/ad4569f3-7576-4376-a7c7-8e8cfcd9b832?format=glb",
+                RenderRequest {
+                    mode: RenderRequestMode::BlockbenchExport,
+                    entry: entry.clone(),
+                    model: None,
+                    features: EnumSet::all().difference(enum_set!(RenderRequestFeatures::UnProcessedSkin | RenderRequestFeatures::Custom | RenderRequestFeatures::Deadmau5Ears | RenderRequestFeatures::FlipUpsideDown | RenderRequestFeatures::Elytra)),
+                    extra_settings: Some(RenderRequestExtraSettings {
+                        format: Some(RenderRequestFormat::Glb),
+                        ..Default::default()
+                    })
+                },
+            ),
+            (
+                "http://localhost:8621/skull/ad4569f3-7576-4376-a7c7-8e8cfcd9b832?wall",
+                RenderRequest {
+                    mode: RenderRequestMode::Skull,
+                    entry: entry.clone(),
+                    model: None,
+                    features: EnumSet::all().difference(enum_set!(RenderRequestFeatures::UnProcessedSkin | RenderRequestFeatures::Custom | RenderRequestFeatures::Deadmau5Ears | RenderRequestFeatures::FlipUpsideDown | RenderRequestFeatures::Elytra | RenderRequestFeatures::BodyLayers | RenderRequestFeatures::Cape | RenderRequestFeatures::Shadow)),
+                    extra_settings: Some(RenderRequestExtraSettings {
+                        wall_skull: true,
+                        ..Default::default()
+                    })
+                },
+            ),
         ]);
 
         for (url, element) in expected {
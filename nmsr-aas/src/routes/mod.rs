@@ -3,12 +3,14 @@ pub mod extractors;
 pub mod query;
 mod render;
 mod render_model;
+mod render_scene;
 mod render_skin;
 use crate::{
     config::{FeaturesConfiguration, ModelCacheConfiguration, NmsrConfiguration},
     error::Result,
     model::{
         armor::manager::VanillaMinecraftArmorManager,
+        nametag::NametagFont,
         request::{
             cache::{CacheBias, ModelCache},
             entry::RenderRequestEntry,
@@ -25,10 +27,11 @@ use enumset::EnumSet;
 use image::RgbaImage;
 use nmsr_rendering::high_level::camera::Camera;
 use nmsr_rendering::high_level::pipeline::{
-    pools::SceneContextPoolManager, Backends, Features, GraphicsContext, GraphicsContextDescriptor,
-    GraphicsContextPools,
+    pools::SceneContextPoolManager, scene::SceneAntiAliasing, Backends, Features, GraphicsContext,
+    GraphicsContextDescriptor, GraphicsContextPools,
 };
 pub use render::{render, render_get_warning, render_post_warning};
+pub use render_scene::render_scene;
 use std::{borrow::Cow, sync::Arc, time::Duration};
 use strum::IntoEnumIterator;
 use tracing::{info, info_span, instrument, Instrument, Span};
@@ -43,12 +46,18 @@ pub trait RenderRequestValidator {
 
     #[allow(unused_variables)]
     fn cleanup_request(&self, request: &mut RenderRequest) {}
+
+    /// Whether renders can be as big as [`RenderRequestMode::MAX_POSTER_RENDER_SIZE`].
+    fn allows_poster_renders(&self) -> bool {
+        false
+    }
 }
 
 #[derive(Clone)]
 pub struct NMSRState<'a> {
     pub resolver: Arc<RenderRequestResolver>,
     pub armor_manager: Option<Arc<VanillaMinecraftArmorManager>>,
+    pub nametag_font: Option<Arc<NametagFont>>,
     pub graphics_context: Arc<GraphicsContext<'a>>,
     pools: Arc<GraphicsContextPools<'a>>,
     cache_config: ModelCacheConfiguration,
@@ -62,6 +71,10 @@ impl<'a> RenderRequestValidator for NMSRState<'a> {
         !self.features_config.disabled_modes.contains(mode)
     }
 
+    fn allows_poster_renders(&self) -> bool {
+        self.features_config.allow_poster_renders
+    }
+
     fn cleanup_request(&self, request: &mut RenderRequest) {
         let mut disabled_features: EnumSet<RenderRequestFeatures> = EnumSet::new();
         for feature in self.features_config.disabled_features.iter() {
@@ -80,6 +93,20 @@ impl<'a> RenderRequestValidator for NMSRState<'a> {
             extra.leggings.take();
             extra.boots.take();
         }
+
+        if let (None, Some(extra)) = (&self.nametag_font, &mut request.extra_settings) {
+            extra.nametag.take();
+        }
+
+        // Only Ears textures can be emissive, so nothing glows without them
+        #[cfg(feature = "ears")]
+        let has_emissive_textures = request.features.contains(RenderRequestFeatures::Ears);
+        #[cfg(not(feature = "ears"))]
+        let has_emissive_textures = false;
+
+        if let (false, Some(extra)) = (has_emissive_textures, &mut request.extra_settings) {
+            extra.glow.take();
+        }
     }
 }
 
@@ -130,24 +157,42 @@ impl<'a> NMSRState<'a> {
             None
         } else {
             Some(Arc::new(
-                VanillaMinecraftArmorManager::new("cache".into()).await?,
+                VanillaMinecraftArmorManager::new(
+                    "cache".into(),
+                    config.armor.clone().unwrap_or_default(),
+                )
+                .await?,
             ))
         };
 
+        let nametag_font = if let Some(nametag) = &config.nametag {
+            info!("Loading the name tag font.");
+            Some(Arc::new(NametagFont::load(&nametag.font).await?))
+        } else {
+            None
+        };
+
         Ok(Self {
             resolver: Arc::new(resolver),
             graphics_context,
             pools: Arc::new(pools),
             cache_config: config.caching.clone(),
             armor_manager,
+            nametag_font,
             features_config: config.features.clone().unwrap_or_default(),
             #[cfg(feature = "renderdoc")]
             render_doc: Arc::new(Mutex::new(rd)),
         })
     }
 
-    pub async fn create_scene_context(&self) -> Result<Object<SceneContextPoolManager<'a>>> {
-        Ok(self.pools.create_scene_context().await?)
+    pub async fn create_scene_context(
+        &self,
+        anti_aliasing: Option<SceneAntiAliasing>,
+    ) -> Result<Object<SceneContextPoolManager<'a>>> {
+        Ok(self
+            .pools
+            .create_scene_context_with_anti_aliasing(anti_aliasing)
+            .await?)
     }
 
     #[allow(unused_variables)]
@@ -259,6 +304,21 @@ impl<'a> NMSRState<'a> {
         camera.set_distance(camera.get_distance() + distance_offset);
     }
 
+    pub fn apply_nametag_camera_settings(mode: RenderRequestMode, camera: &mut Camera) {
+        if mode.is_custom() {
+            return;
+        }
+
+        // Make room for the name tag above the player's head
+        camera.set_look_at_y(camera.get_look_at_y() + 2.5);
+
+        if mode.is_isometric() {
+            camera.set_aspect(camera.get_aspect() * 1.1);
+        } else {
+            camera.set_distance(camera.get_distance() * 1.1);
+        }
+    }
+
     fn apply_upside_down_camera_settings(mode: RenderRequestMode, camera: &mut Camera) {
         if mode.is_bust() {
             camera.set_look_at_y(camera.get_look_at_y() - 16.0);
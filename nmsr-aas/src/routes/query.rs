@@ -2,13 +2,27 @@ use crate::{
     error::{RenderRequestError, Result},
     model::{
         armor::VanillaMinecraftArmorMaterialData,
-        request::{entry::RenderRequestEntryModel, RenderRequestFeatures, RenderRequestMode},
+        nametag::MAX_NAMETAG_LENGTH,
+        request::{
+            animation::MAX_ANIMATION_FRAMES, entry::RenderRequestEntryModel, RenderRequestFeatures,
+            RenderRequestFit, RenderRequestFormat, RenderRequestLayout, RenderRequestLighting,
+            RenderRequestMode, RenderRequestOutline, RenderRequestTrim,
+        },
     },
 };
 use enumset::EnumSet;
+use is_empty::IsEmpty;
+use nmsr_rendering::{
+    high_level::{
+        parts::provider::pose::{PlayerBodyPartRotations, PlayerPose},
+        pipeline::scene::{SceneAntiAliasing, SceneAuxPass, SceneShading},
+    },
+    low_level::Vec3,
+};
 use serde::Deserialize;
 use serde_with::TryFromInto;
 use serde_with::{formats::CommaSeparator, serde_as, DisplayFromStr, StringWithSeparator};
+use std::ops::Range;
 
 ///  The options are:
 ///  - `?exclude=<features>` or `?no=<features>`: exclude a feature from the entry (comma-separated, or multiple query strings)
@@ -22,11 +36,55 @@ use serde_with::{formats::CommaSeparator, serde_as, DisplayFromStr, StringWithSe
 ///
 ///  - `?w=<width>` or `?width=<width>`: set the width of the image
 ///  - `?h=<height>` or `?height=<height>`: set the height of the image
+///    (up to 8192 pixels when poster renders are allowed in the configuration, which are rendered in tiles)
+///    (poster renders can't be animated or use `ssaa2`/`ssaa4` anti-aliasing)
 ///  - `?model=<steve|alex|wide|slim>`: set the model of the entry
 ///  - `?alex`: set the model of the entry to alex [compatibility with old URLs]
 ///  - `?steve`: set the model of the entry to steve [compatibility with old URLs]
 ///  - `?process`: process the skin (upgrade skin to 1.8 format, strip alpha from the body regions, apply erase regions if Ears feature is enabled)
 ///  - `?deadmau5ears`: enable deadmau5 ears
+///  - `?elytra` or `?elytra=<texture hash>`: render an elytra instead of the cape (textured with the given texture, the cape, or the default elytra texture if there's none)
+///  - `?gliding` or `?glide`: render the entry gliding with its elytra spread out
+///  - `?wall`: render the skull mounted on a wall instead of standing on the floor (requires using Skull mode)
+///  - `?skull_rotation=<rotation>`: rotate the skull standing on the floor, in sixteenths of a turn from 0 to 15 (requires using Skull mode)
+///  - `?pose=<pose>`: render the entry in a named pose (standing, walking, running, sneaking, sitting, waving, swimming, crawling or t_pose)
+///  - `?outline` or `?outline=<width>[,<color>][,inner]`: draw an outline around the entry, in pixels (defaults to `2,ffffff`)
+///    (the color is written as `RRGGBB` or `RRGGBBAA`, and `inner` also outlines parts standing in front of others)
+///  - `?glow=<strength>`: make the emissive parts of the entry glow, from 0 to 5 (requires Ears)
+///  - `?nametag` or `?nametag=<text>`: render a name tag above the entry, showing its name or the given text
+///    (the text can be colored with formatting codes like `§c` or `&c`, requires a name tag font to be configured)
+///  - `?pass=<depth|normal|part_id|uv>`: render an auxiliary buffer instead of the colors of the entry, without anti-aliasing
+///    (`depth` is split across the red, green and blue channels, `normal` maps each axis to a channel, `part_id` writes the
+///    body part in the red channel and `uv` packs which skin texel is shown like the UV maps of `nmsr-lib`)
+///  - `?layout` or `?layout=<header|json>`: return the bounding box of each body part and named part (like Ears features) in the render,
+///    in output pixels, as JSON in the `X-NMSR-Layout` header (the default) or instead of the render
+///  - `?format=<png|svg|glb>`: pick the format of the render (defaults to `png`), `svg` draws every visible texel as a polygon
+///    (merging runs of texels of the same color) for lossless scalable renders (requires an isometric or front mode,
+///    and can't be used with animations, auxiliary passes, outlines, glows or trimming)
+///    and `glb` exports the posed entry as a binary glTF 2.0 model instead of a Blockbench project (requires using Export mode)
+///  - `?fit` or `?fit=<padding>`: frame the camera around the entry (including Ears features, armor, poses and name tags) instead of
+///    using the framing of the mode, leaving the given fraction of the render empty on each side, from 0 to 0.4 (defaults to `0.05`)
+///  - `?trim`: crop the transparent margins around the entry
+///  - `?padding=<pixels>`: leave the given amount of transparent pixels on each side of the entry once trimmed, from 0 to 256 (implies `?trim`)
+///  - `?square`: pad the trimmed render to a square, with the entry in the middle (implies `?trim`)
+///  - `?pixel_scale=<pixels>`: render every skin texel as exactly the given amount of pixels, from 1 to 32, snapped to the pixel grid
+///    (requires an isometric or front mode, and turns anti-aliasing off unless `?aa` is given)
+///    (the whole model has to fit in the render at that scale, so bigger scales need a bigger size)
+///  - `?aa=<none|smaa|ssaa2|ssaa4>`: smooth the edges of the entry with the given anti-aliasing instead of the default one
+///    (`none` keeps every pixel a single color for pixel art, and `ssaa2` or `ssaa4` render at 2 or 4 times the size and scale the render back down)
+///
+///  - `head=<x>,<y>,<z>`: set the rotation of the head (pitch, yaw and roll, in degrees from -360 to 360) (requires using Custom mode)
+///  - `body=<x>,<y>,<z>`: set the rotation of the body (requires using Custom mode)
+///  - `left_arm=<x>,<y>,<z>` and `right_arm=<x>,<y>,<z>`: set the rotation of the arms (requires using Custom mode)
+///  - `left_leg=<x>,<y>,<z>` and `right_leg=<x>,<y>,<z>`: set the rotation of the legs (requires using Custom mode)
+///    (rotations use the same axes as the game, and replace the rotation given by the pose)
+///
+///  - `animation`: upload a Bedrock `.animation.json` or an Emotecraft emote to pose the entry with (POST only)
+///    (Molang expressions aren't evaluated, so Bedrock animations must only use plain numbers)
+///  - `?animation_name=<name>`: pick which animation of a Bedrock animation file to use (defaults to the first one)
+///  - `?timestamp=<seconds>`: set the time of the animation to render, from 0 (defaults to 0)
+///  - `?frames=<start>,<end>`: render the given range of game ticks of the animation as an animated GIF (at most 100 frames)
+///    (these three options require uploading an animation)
 ///  
 ///  - `?arms=<rotation>` or `arm=<rotation>`: set the rotation of the arms
 ///  - `?dist=<distance>` or `distance=<distance>`: set the distance of the camera
@@ -37,10 +95,21 @@ use serde_with::{formats::CommaSeparator, serde_as, DisplayFromStr, StringWithSe
 ///
 ///  - `pos=<x>,<y>,<z>`: set the position of the camera (requires using Custom mode)
 ///
+///  - `light=<x>,<y>,<z>`: set the direction the light shines towards (e.g. `0,-1,1` shines down on the front of the entry) (requires using Custom mode)
+///  - `light_intensity=<intensity>`: set the intensity of the light, from 0 to 10 (requires using Custom mode)
+///  - `ambient=<ambient>`: set how lit faces that don't face the light are, from 0 to 1 (requires using Custom mode)
+///  - `light_color=<r>,<g>,<b>`: set the color of the light (and of the ambient light) (requires using Custom mode)
+///  - `fill=<x>,<y>,<z>`, `fill_intensity=<intensity>` and `fill_color=<r>,<g>,<b>`: add a second light on top of the first one,
+///    which only brightens the faces it shines on (requires using Custom mode)
+///  - `?shading=<lambert|flat|toon|minecraft>`: pick how faces are lit (defaults to `lambert`), `flat` lights every face fully,
+///    `toon` splits the light into bands and `minecraft` lights each face depending on which way it faces, like blocks in game
+///
 ///  - `?helmet=<helmet>`: set the helmet of the entry
 ///  - `?chestplate=<chestplate>`: set the chestplate of the entry
 ///  - `?leggings=<leggings>`: set the leggings of the entry
 ///  - `?boots=<boots>`: set the boots of the entry
+///    (armor is written as `<material>[_<trim>_<trim material>]...`, custom ids from resource packs are also accepted,
+///    append `+glint` to render the enchantment glint on it)
 #[serde_as]
 #[derive(Debug, Clone, Deserialize)]
 pub struct RenderRequestQueryParams {
@@ -78,6 +147,14 @@ pub struct RenderRequestQueryParams {
     #[serde(alias = "upsidedown")]
     pub upside_down: Option<String>,
 
+    pub elytra: Option<String>,
+    #[serde(alias = "glide")]
+    pub gliding: Option<String>,
+
+    pub wall: Option<String>,
+    #[serde(alias = "skullrotation")]
+    pub skull_rotation: Option<u8>,
+
     #[serde(alias = "arm")]
     pub arms: Option<f32>,
 
@@ -94,6 +171,28 @@ pub struct RenderRequestQueryParams {
     #[serde_as(as = "Option<StringWithSeparator::<CommaSeparator, f32>>")]
     pub pos: Option<Vec<f32>>,
 
+    #[serde_as(as = "Option<StringWithSeparator::<CommaSeparator, f32>>")]
+    #[serde(alias = "light_direction")]
+    pub light: Option<Vec<f32>>,
+    #[serde(alias = "lightintensity")]
+    pub light_intensity: Option<f32>,
+    pub ambient: Option<f32>,
+    #[serde_as(as = "Option<StringWithSeparator::<CommaSeparator, u8>>")]
+    #[serde(alias = "lightcolor", alias = "light_colour")]
+    pub light_color: Option<Vec<u8>>,
+
+    #[serde_as(as = "Option<StringWithSeparator::<CommaSeparator, f32>>")]
+    #[serde(alias = "fill_direction")]
+    pub fill: Option<Vec<f32>>,
+    #[serde(alias = "fillintensity")]
+    pub fill_intensity: Option<f32>,
+    #[serde_as(as = "Option<StringWithSeparator::<CommaSeparator, u8>>")]
+    #[serde(alias = "fillcolor", alias = "fill_colour")]
+    pub fill_color: Option<Vec<u8>>,
+
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    pub shading: Option<SceneShading>,
+
     #[serde_as(as = "Option<TryFromInto<String>>")]
     pub helmet: Option<VanillaMinecraftArmorMaterialData>,
     #[serde_as(as = "Option<TryFromInto<String>>")]
@@ -108,6 +207,58 @@ pub struct RenderRequestQueryParams {
     #[serde(alias = "swing")]
     pub limb_swing: Option<f32>,
 
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    pub pose: Option<PlayerPose>,
+
+    #[serde(alias = "name_tag")]
+    pub nametag: Option<String>,
+
+    #[serde_as(as = "Option<TryFromInto<String>>")]
+    pub outline: Option<RenderRequestOutline>,
+    pub glow: Option<f32>,
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[serde(alias = "aux_pass")]
+    pub pass: Option<SceneAuxPass>,
+    #[serde_as(as = "Option<TryFromInto<String>>")]
+    pub layout: Option<RenderRequestLayout>,
+    #[serde_as(as = "Option<TryFromInto<String>>")]
+    pub format: Option<RenderRequestFormat>,
+    #[serde_as(as = "Option<TryFromInto<String>>")]
+    pub fit: Option<RenderRequestFit>,
+
+    pub trim: Option<String>,
+    pub padding: Option<u32>,
+    pub square: Option<String>,
+
+    #[serde(alias = "pixelscale")]
+    pub pixel_scale: Option<u32>,
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[serde(alias = "anti_aliasing")]
+    pub aa: Option<SceneAntiAliasing>,
+
+    #[serde_as(as = "Option<StringWithSeparator::<CommaSeparator, f32>>")]
+    pub head: Option<Vec<f32>>,
+    #[serde_as(as = "Option<StringWithSeparator::<CommaSeparator, f32>>")]
+    pub body: Option<Vec<f32>>,
+    #[serde_as(as = "Option<StringWithSeparator::<CommaSeparator, f32>>")]
+    #[serde(alias = "leftarm")]
+    pub left_arm: Option<Vec<f32>>,
+    #[serde_as(as = "Option<StringWithSeparator::<CommaSeparator, f32>>")]
+    #[serde(alias = "rightarm")]
+    pub right_arm: Option<Vec<f32>>,
+    #[serde_as(as = "Option<StringWithSeparator::<CommaSeparator, f32>>")]
+    #[serde(alias = "leftleg")]
+    pub left_leg: Option<Vec<f32>>,
+    #[serde_as(as = "Option<StringWithSeparator::<CommaSeparator, f32>>")]
+    #[serde(alias = "rightleg")]
+    pub right_leg: Option<Vec<f32>>,
+
+    #[serde(alias = "animationname")]
+    pub animation_name: Option<String>,
+    pub timestamp: Option<f32>,
+    #[serde_as(as = "Option<StringWithSeparator::<CommaSeparator, u32>>")]
+    pub frames: Option<Vec<u32>>,
+
     #[cfg(feature = "renderdoc")]
     pub capture: Option<String>,
 }
@@ -119,6 +270,7 @@ pub struct RenderRequestMultipartParams {
     #[serde(alias = "texture")]
     pub skin: Vec<u8>,
     pub cape: Option<Vec<u8>>,
+    pub animation: Option<serde_json::Value>,
 }
 
 impl RenderRequestQueryParams {
@@ -152,6 +304,10 @@ impl RenderRequestQueryParams {
             excluded |= RenderRequestFeatures::FlipUpsideDown;
         }
 
+        if self.elytra.is_none() {
+            excluded |= RenderRequestFeatures::Elytra;
+        }
+
         excluded
     }
 
@@ -170,18 +326,102 @@ impl RenderRequestQueryParams {
         alex.or(steve).or(model)
     }
 
-    pub fn validate(&mut self, mode: RenderRequestMode) -> Result<()> {
+    pub fn get_body_part_rotations(&self) -> Option<PlayerBodyPartRotations> {
+        let rotation = |rotation: &Option<Vec<f32>>| rotation.as_deref().map(Vec3::from_slice);
+
+        let rotations = PlayerBodyPartRotations {
+            head: rotation(&self.head),
+            body: rotation(&self.body),
+            left_arm: rotation(&self.left_arm),
+            right_arm: rotation(&self.right_arm),
+            left_leg: rotation(&self.left_leg),
+            right_leg: rotation(&self.right_leg),
+        };
+
+        Some(rotations).filter(|r| !r.is_empty())
+    }
+
+    pub fn get_lighting(&self) -> Option<RenderRequestLighting> {
+        let color = |color: &Option<Vec<u8>>| match color.as_deref() {
+            Some(&[r, g, b]) => Some(Vec3::new(r.into(), g.into(), b.into()) / 255.0),
+            _ => None,
+        };
+
+        let lighting = RenderRequestLighting {
+            direction: self.light.as_deref().map(Vec3::from_slice),
+            intensity: self.light_intensity,
+            ambient: self.ambient,
+            color: color(&self.light_color),
+            fill_direction: self.fill.as_deref().map(Vec3::from_slice),
+            fill_intensity: self.fill_intensity,
+            fill_color: color(&self.fill_color),
+        };
+
+        Some(lighting).filter(|l| !l.is_empty())
+    }
+
+    pub fn get_trim(&self) -> Option<RenderRequestTrim> {
+        if self.trim.is_none() && self.padding.is_none() && self.square.is_none() {
+            return None;
+        }
+
+        Some(RenderRequestTrim {
+            padding: self.padding.unwrap_or_default(),
+            square: self.square.is_some(),
+        })
+    }
+
+    pub fn get_animation_frames(&self) -> Option<Range<u32>> {
+        match self.frames.as_deref() {
+            Some(&[start, end]) => Some(start..end),
+            _ => None,
+        }
+    }
+
+    pub fn validate(&mut self, mode: RenderRequestMode, allow_poster_renders: bool) -> Result<()> {
         fn clamp(value: &mut Option<f32>, min: f32, max: f32) {
             if let Some(value) = value {
                 *value = value.clamp(min, max);
             }
         }
 
-        let [min_w, min_h, max_w, max_h] = mode.size_constraints();
+        let [min_w, min_h, regular_max_w, regular_max_h] = mode.size_constraints();
+        let (mut max_w, mut max_h) = (regular_max_w, regular_max_h);
+
+        // Big renders are rendered in tiles, so they aren't limited by how big a single render can be
+        if allow_poster_renders && mode.uses_rendering_pipeline() {
+            max_w = max_w.max(RenderRequestMode::MAX_POSTER_RENDER_SIZE);
+            max_h = max_h.max(RenderRequestMode::MAX_POSTER_RENDER_SIZE);
+        }
 
         RenderRequestMode::validate_unit("width", self.width, min_w, max_w)?;
         RenderRequestMode::validate_unit("height", self.height, min_h, max_h)?;
 
+        // Every frame of an animation is kept in memory at once, and supersampling renders up to 16 times as many tiles,
+        // which are both too much for poster renders
+        let is_poster_render = self.width.is_some_and(|width| width > regular_max_w)
+            || self.height.is_some_and(|height| height > regular_max_h);
+
+        if is_poster_render {
+            let unsupported_setting = if self.frames.is_some() {
+                Some("animation frames (frames parameter)")
+            } else if self.aa.is_some_and(|aa| aa.get_supersampling_factor() > 1) {
+                Some("supersampled anti-aliasing (aa parameter)")
+            } else {
+                None
+            };
+
+            if let Some(setting) = unsupported_setting {
+                return Err(RenderRequestError::InvalidRenderSettingError(
+                    setting,
+                    format!(
+                        "left out on renders bigger than {regular_max_w}x{regular_max_h} pixels"
+                    ),
+                )
+                .into());
+            }
+        }
+
         RenderRequestMode::wrap_unit(self.yaw.as_mut(), -180.0, 180.0)?;
         RenderRequestMode::wrap_unit(self.pitch.as_mut(), -90.0, 90.0)?;
         RenderRequestMode::wrap_unit(self.roll.as_mut(), -180.0, 360.0)?;
@@ -190,6 +430,8 @@ impl RenderRequestQueryParams {
 
         RenderRequestMode::validate_unit("distance", self.distance, -15.0, 50.0)?;
 
+        RenderRequestMode::validate_unit("skull_rotation", self.skull_rotation, 0, 15)?;
+
         // Clamp yaw, pitch, roll so that there is no weirdness with the camera
         clamp(&mut self.yaw, -180.0, 180.0);
         clamp(&mut self.pitch, -90.0, 90.0);
@@ -227,10 +469,308 @@ impl RenderRequestQueryParams {
             self.z_pos.replace(pos[2]);
         }
 
+        if let Some(nametag) = &self.nametag {
+            if mode.is_face() || mode.is_skull() {
+                return Err(RenderRequestError::InvalidModeSettingSpecifiedError(
+                    "a name tag",
+                    "Name tags are only rendered above whole players, so switch to a mode that shows more than a head.",
+                )
+                .into());
+            }
+
+            if nametag.chars().count() > MAX_NAMETAG_LENGTH {
+                return Err(RenderRequestError::InvalidRenderSettingError(
+                    "name tag text (nametag parameter)",
+                    format!("at most {MAX_NAMETAG_LENGTH} characters long"),
+                )
+                .into());
+            }
+        }
+
+        if self.gliding.is_some() && self.pose.is_some_and(PlayerPose::is_horizontal) {
+            return Err(RenderRequestError::InvalidRenderSettingError(
+                "pose",
+                "a pose other than swimming or crawling when gliding, which already lays the entry down"
+                    .to_string(),
+            )
+            .into());
+        }
+
+        if let Some(elytra) = self.elytra.as_deref().filter(|elytra| !elytra.is_empty()) {
+            let is_texture_hash =
+                (36..=64).contains(&elytra.len()) && elytra.chars().all(|c| c.is_ascii_hexdigit());
+
+            if !is_texture_hash {
+                return Err(RenderRequestError::InvalidRenderSettingError(
+                    "elytra texture (elytra parameter)",
+                    "a texture hash, 36-64 characters long and only made of 0-9 and a-f"
+                        .to_string(),
+                )
+                .into());
+            }
+        }
+
+        self.validate_lighting(mode)?;
+
+        RenderRequestMode::validate_unit(
+            "outline width",
+            self.outline.map(|outline| outline.width),
+            1,
+            RenderRequestOutline::MAX_WIDTH,
+        )?;
+
+        RenderRequestMode::validate_unit("glow", self.glow, 0.0, 5.0)?;
+
+        if !mode.uses_rendering_pipeline() && self.pass.is_some() {
+            return Err(RenderRequestError::InvalidModeSettingSpecifiedError(
+                "auxiliary pass (pass parameter)",
+                "To fix this, switch to a mode that renders the entry in 3D to make use of it.",
+            )
+            .into());
+        }
+
+        RenderRequestMode::validate_unit("pixel scale", self.pixel_scale, 1, 32)?;
+
+        if !mode.is_isometric() && self.pixel_scale.is_some() {
+            return Err(RenderRequestError::InvalidModeSettingSpecifiedError(
+                "pixel scale (pixel_scale parameter)",
+                "To fix this, switch to an isometric or front mode, which don't distort the entry with perspective.",
+            )
+            .into());
+        }
+
+        if !mode.uses_rendering_pipeline() && self.aa.is_some() {
+            return Err(RenderRequestError::InvalidModeSettingSpecifiedError(
+                "anti-aliasing (aa parameter)",
+                "To fix this, switch to a mode that renders the entry in 3D to make use of it.",
+            )
+            .into());
+        }
+
+        RenderRequestMode::validate_unit(
+            "fit padding",
+            self.fit.map(|fit| fit.padding),
+            0.0,
+            RenderRequestFit::MAX_PADDING,
+        )?;
+
+        if (!mode.uses_rendering_pipeline() || mode.is_bust()) && self.fit.is_some() {
+            return Err(RenderRequestError::InvalidModeSettingSpecifiedError(
+                "camera fitting (fit parameter)",
+                "To fix this, switch to a mode that shows the whole entry to make use of it.",
+            )
+            .into());
+        }
+
+        RenderRequestMode::validate_unit(
+            "padding",
+            self.padding,
+            0,
+            RenderRequestTrim::MAX_PADDING,
+        )?;
+
+        if !mode.uses_rendering_pipeline() && self.get_trim().is_some() {
+            return Err(RenderRequestError::InvalidModeSettingSpecifiedError(
+                "trimming (trim, padding or square parameter)",
+                "To fix this, switch to a mode that renders the entry in 3D to make use of it.",
+            )
+            .into());
+        }
+
+        if !mode.uses_rendering_pipeline() && self.layout.is_some() {
+            return Err(RenderRequestError::InvalidModeSettingSpecifiedError(
+                "layout (layout parameter)",
+                "To fix this, switch to a mode that renders the entry in 3D to make use of it.",
+            )
+            .into());
+        }
+
+        if self.format == Some(RenderRequestFormat::Glb) && !mode.is_blockbench_export() {
+            return Err(RenderRequestError::InvalidModeSettingSpecifiedError(
+                "model output (format=glb)",
+                "To fix this, switch to export mode to make use of it.",
+            )
+            .into());
+        }
+
+        if self.format == Some(RenderRequestFormat::Svg) {
+            if !mode.is_isometric() {
+                return Err(RenderRequestError::InvalidModeSettingSpecifiedError(
+                    "vector output (format=svg)",
+                    "To fix this, switch to an isometric or front mode to make use of it.",
+                )
+                .into());
+            }
+
+            // These are drawn by the rendering pipeline, which vector renders don't go through
+            let has_pipeline_effects = self.frames.is_some()
+                || self.pass.is_some()
+                || self.outline.is_some()
+                || self.glow.is_some()
+                || self.get_trim().is_some();
+
+            if has_pipeline_effects {
+                return Err(RenderRequestError::InvalidRenderSettingError(
+                    "format",
+                    "png when rendering animations, auxiliary passes, outlines, glows or trimmed renders".to_string(),
+                )
+                .into());
+            }
+        }
+
+        if !mode.is_skull() && (self.wall.is_some() || self.skull_rotation.is_some()) {
+            return Err(RenderRequestError::InvalidModeSettingSpecifiedError(
+                "skull placement settings",
+                "To fix this, switch to skull mode to make use of these.",
+            )
+            .into());
+        }
+
+        let body_part_rotations = [
+            ("head rotation (head parameter)", &self.head),
+            ("body rotation (body parameter)", &self.body),
+            ("left arm rotation (left_arm parameter)", &self.left_arm),
+            ("right arm rotation (right_arm parameter)", &self.right_arm),
+            ("left leg rotation (left_leg parameter)", &self.left_leg),
+            ("right leg rotation (right_leg parameter)", &self.right_leg),
+        ];
+
+        for (name, rotation) in body_part_rotations {
+            let Some(rotation) = rotation else {
+                continue;
+            };
+
+            if !mode.is_custom() {
+                return Err(RenderRequestError::InvalidModeSettingSpecifiedError(
+                    "body part rotations",
+                    "To fix this, switch to custom mode to make use of these.",
+                )
+                .into());
+            }
+
+            // Comparisons are false for NaN, so check that every angle is within bounds rather than out of them
+            let is_valid = rotation.len() == 3
+                && rotation
+                    .iter()
+                    .all(|angle| (-360.0..=360.0).contains(angle));
+
+            if !is_valid {
+                return Err(RenderRequestError::InvalidRenderSettingError(
+                    name,
+                    "3 angles from -360 to 360 separated by commas".to_string(),
+                )
+                .into());
+            }
+        }
+
+        if self
+            .timestamp
+            .is_some_and(|timestamp| !(timestamp.is_finite() && timestamp >= 0.0))
+        {
+            return Err(RenderRequestError::InvalidRenderSettingError(
+                "animation timestamp (timestamp parameter)",
+                "a positive number of seconds".to_string(),
+            )
+            .into());
+        }
+
+        if let Some(frames) = &self.frames {
+            let is_valid_range = matches!(frames.as_slice(), [start, end] if start < end && end - start <= MAX_ANIMATION_FRAMES);
+
+            if !is_valid_range {
+                return Err(RenderRequestError::InvalidRenderSettingError(
+                    "animation frames (frames parameter)",
+                    format!("a start and end tick separated by commas, at most {MAX_ANIMATION_FRAMES} ticks apart"),
+                )
+                .into());
+            }
+        }
+
         RenderRequestMode::validate_unit("xpos", self.x_pos, -50.0, 50.0)?;
         RenderRequestMode::validate_unit("ypos", self.y_pos, -50.0, 50.0)?;
         RenderRequestMode::validate_unit("zpos", self.z_pos, -50.0, 50.0)?;
 
         Ok(())
     }
+
+    fn validate_lighting(&self, mode: RenderRequestMode) -> Result<()> {
+        let has_lighting = self.light.is_some()
+            || self.light_intensity.is_some()
+            || self.ambient.is_some()
+            || self.light_color.is_some()
+            || self.fill.is_some()
+            || self.fill_intensity.is_some()
+            || self.fill_color.is_some();
+
+        if !has_lighting {
+            return Ok(());
+        }
+
+        if !mode.is_custom() {
+            return Err(RenderRequestError::InvalidModeSettingSpecifiedError(
+                "lighting settings",
+                "To fix this, switch to custom mode to make use of these.",
+            )
+            .into());
+        }
+
+        let directions = [
+            ("light direction (light parameter)", &self.light),
+            ("fill light direction (fill parameter)", &self.fill),
+        ];
+
+        for (name, direction) in directions {
+            let is_valid = direction.as_deref().map_or(true, |direction| {
+                direction.len() == 3
+                    && direction.iter().all(|x| x.is_finite())
+                    && direction.iter().any(|&x| x != 0.0)
+            });
+
+            if !is_valid {
+                return Err(RenderRequestError::InvalidRenderSettingError(
+                    name,
+                    "3 valid numbers separated by commas, which aren't all zero".to_string(),
+                )
+                .into());
+            }
+        }
+
+        let colors = [
+            ("light color (light_color parameter)", &self.light_color),
+            ("fill light color (fill_color parameter)", &self.fill_color),
+        ];
+
+        for (name, color) in colors {
+            if color.as_ref().is_some_and(|color| color.len() != 3) {
+                return Err(RenderRequestError::InvalidRenderSettingError(
+                    name,
+                    "3 numbers from 0 to 255 separated by commas".to_string(),
+                )
+                .into());
+            }
+        }
+
+        let intensities = [
+            ("light_intensity", self.light_intensity),
+            ("ambient", self.ambient),
+            ("fill_intensity", self.fill_intensity),
+        ];
+
+        // NaN isn't out of any bounds, so it has to be rejected before checking them
+        for (name, intensity) in intensities {
+            if intensity.is_some_and(|x| !x.is_finite()) {
+                return Err(RenderRequestError::InvalidRenderSettingError(
+                    name,
+                    "a finite number".to_string(),
+                )
+                .into());
+            }
+        }
+
+        RenderRequestMode::validate_unit("light_intensity", self.light_intensity, 0.0, 10.0)?;
+        RenderRequestMode::validate_unit("ambient", self.ambient, 0.0, 1.0)?;
+        RenderRequestMode::validate_unit("fill_intensity", self.fill_intensity, 0.0, 10.0)?;
+
+        Ok(())
+    }
 }
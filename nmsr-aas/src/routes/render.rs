@@ -1,7 +1,7 @@
 use super::{bbmodel_export::internal_bbmodel_export, NMSRState};
 use crate::{
     error::{RenderRequestError, Result},
-    model::request::{RenderRequest, RenderRequestMode},
+    model::request::{RenderRequest, RenderRequestFormat, RenderRequestLayout, RenderRequestMode},
     routes::render_model::internal_render_model,
     routes::render_skin::internal_render_skin_or_cape,
 };
@@ -9,6 +9,7 @@ use axum::{
     extract::State,
     http::HeaderValue,
     response::{IntoResponse, Response},
+    Json,
 };
 use http::HeaderName;
 use hyper::{
@@ -18,7 +19,10 @@ use hyper::{
 use tracing::instrument;
 use xxhash_rust::xxh3::xxh3_64;
 
-const IMAGE_PNG_MIME: &str = "image/png";
+pub(crate) const IMAGE_PNG_MIME: &str = "image/png";
+pub(crate) const APPLICATION_JSON_MIME: &str = "application/json";
+const IMAGE_GIF_MIME: &str = "image/gif";
+pub(crate) const IMAGE_SVG_MIME: &str = "image/svg+xml";
 
 #[axum::debug_handler]
 pub async fn render_post_warning() -> Result<Response> {
@@ -33,6 +37,8 @@ pub async fn render_get_warning() -> Result<Response> {
 const NMSR_FALLBACK_HEADER: HeaderName = HeaderName::from_static("x-nmsr-fallback");
 const NMSR_FALLBACK_TRUE_VALUE: HeaderValue = HeaderValue::from_static("True");
 
+const NMSR_LAYOUT_HEADER: HeaderName = HeaderName::from_static("x-nmsr-layout");
+
 #[axum::debug_handler]
 #[instrument(skip(state, method))]
 pub async fn render(
@@ -48,18 +54,49 @@ pub async fn render(
         return internal_bbmodel_export(state, method, request).await;
     }
 
+    let layout_mode = request.get_layout();
+
+    let mime = if layout_mode == Some(RenderRequestLayout::Json) {
+        APPLICATION_JSON_MIME
+    } else if request.get_animation_frames().is_some() {
+        IMAGE_GIF_MIME
+    } else if request.get_format() == RenderRequestFormat::Svg {
+        IMAGE_SVG_MIME
+    } else {
+        IMAGE_PNG_MIME
+    };
+
     if method == Method::HEAD {
-        return Ok(([(CONTENT_TYPE, HeaderValue::from_static(IMAGE_PNG_MIME))]).into_response());
+        return Ok(([(CONTENT_TYPE, HeaderValue::from_static(mime))]).into_response());
     }
 
-    let result = match request.mode {
-        RenderRequestMode::Skin | RenderRequestMode::Cape => {
-            internal_render_skin_or_cape(&request, resolved).await
+    let (result, layout) = match request.mode {
+        RenderRequestMode::Skin | RenderRequestMode::Cape => (
+            internal_render_skin_or_cape(&request, resolved).await?,
+            None,
+        ),
+        _ => internal_render_model(&mut request, &state, &resolved).await?,
+    };
+
+    let mut res = match (layout_mode, layout) {
+        (Some(RenderRequestLayout::Json), Some(layout)) => {
+            create_image_response(Json(layout), &state, &request, mime)
         }
-        _ => internal_render_model(&mut request, &state, &resolved).await,
-    }?;
+        (Some(RenderRequestLayout::Header), Some(layout)) => {
+            let mut res = create_image_response(result, &state, &request, mime);
 
-    let mut res = create_image_response(result, &state, &request);
+            let layout_value = serde_json::to_string(&layout)
+                .ok()
+                .and_then(|layout| HeaderValue::from_str(&layout).ok());
+
+            if let Some(layout_value) = layout_value {
+                res.headers_mut().insert(NMSR_LAYOUT_HEADER, layout_value);
+            }
+
+            res
+        }
+        _ => create_image_response(result, &state, &request, mime),
+    };
     let hash = xxh3_64(format!("{request:?}").as_bytes());
 
     if let Ok(etag_value) = HeaderValue::from_str(&format!("{hash:x}")) {
@@ -74,10 +111,11 @@ pub async fn render(
     Ok(res)
 }
 
-fn create_image_response<T>(
+pub(crate) fn create_image_response<T>(
     skin: T,
     State(state): &State<NMSRState>,
     request: &RenderRequest,
+    mime: &'static str,
 ) -> Response
 where
     T: IntoResponse,
@@ -91,7 +129,7 @@ where
 
     response
         .headers_mut()
-        .insert(CONTENT_TYPE, HeaderValue::from_static(IMAGE_PNG_MIME));
+        .insert(CONTENT_TYPE, HeaderValue::from_static(mime));
 
     response
 }
@@ -4,29 +4,38 @@ use nmsr_rendering::{
     errors::NMSRRenderingError,
     high_level::{
         model::{PlayerArmorSlots, PlayerModel},
-        parts::provider::{PlayerMovementContext, PlayerPartProviderContext},
-        pipeline::{pools::SceneContextPoolManager, scene::Scene},
+        parts::provider::{pose::PlayerPose, PlayerMovementContext, PlayerPartProviderContext},
+        pipeline::{
+            pools::SceneContextPoolManager,
+            scene::{GlintInformation, Scene, SceneNametag, ScenePlayer, Size},
+        },
         types::PlayerPartTextureType,
     },
+    low_level::Vec3,
 };
 use tracing::instrument;
 
 use super::NMSRState;
 use crate::{
-    error::Result,
+    error::{RenderRequestError, Result},
     model::{
         armor::VanillaMinecraftArmorMaterialData,
-        request::{RenderRequest, RenderRequestFeatures},
+        layout::RenderLayout,
+        request::{
+            animation::ANIMATION_TICKS_PER_SECOND, entry::RenderRequestEntry, RenderRequest,
+            RenderRequestFeatures, RenderRequestFit, RenderRequestFormat, RenderRequestMode,
+        },
         resolver::{ResolvedRenderEntryTextureType, ResolvedRenderRequest},
     },
-    utils::png::create_png_from_bytes,
+    utils::{gif::create_gif_from_frames, png::create_png_from_bytes, trim::trim_frames},
 };
 
+/// Renders the entry of the request, along with its layout if it was asked for.
 pub(crate) async fn internal_render_model<'a>(
     request: &mut RenderRequest,
     state: &NMSRState<'a>,
     resolved: &ResolvedRenderRequest,
-) -> Result<Vec<u8>> {
+) -> Result<(Vec<u8>, Option<RenderLayout>)> {
     #[cfg(feature = "renderdoc")]
     let mut rd = if !request
         .features
@@ -44,7 +53,9 @@ pub(crate) async fn internal_render_model<'a>(
         }
     }
 
-    let scene_context = state.create_scene_context().await?;
+    let scene_context = state
+        .create_scene_context(request.get_anti_aliasing())
+        .await?;
 
     let mode = request.mode;
     #[allow(unused_mut)] // We use mut when we have ears feature enabled
@@ -78,24 +89,143 @@ pub(crate) async fn internal_render_model<'a>(
         NMSRState::apply_deadmau5ears_camera_settings(mode, &request.features, &mut camera);
     }
 
-    let mut scene = Scene::new(
+    let nametag = create_nametag(state, request, resolved)?;
+
+    if nametag.is_some() {
+        NMSRState::apply_nametag_camera_settings(mode, &mut camera);
+    }
+
+    let player = ScenePlayer {
+        part_context: &part_context,
+        body_parts: &parts,
+        offset: Vec3::ZERO,
+        nametag: nametag.as_ref().map(|(nametag, _)| *nametag),
+    };
+
+    let mut scene = Scene::new_group(
         &state.graphics_context,
         scene_context,
         camera,
         lighting,
         size,
-        &part_context,
-        &parts,
+        &[player],
     );
 
-    load_textures(resolved, state, request, &mut part_context, &mut scene).await?;
+    *scene.outline_mut() = request.get_outline();
+    *scene.glow_strength_mut() = request.get_glow_strength();
+    *scene.aux_pass_mut() = request.get_aux_pass();
+    *scene.keep_texture_images_mut() = request.get_format() == RenderRequestFormat::Svg;
 
-    scene.render(&state.graphics_context)?;
+    // The framing of the built-in modes is made for standing entries, so other poses are framed around their parts
+    let is_posed = part_context.movement.pose != PlayerPose::Standing
+        && !mode.is_custom()
+        && !mode.is_head_or_face();
 
-    let render = scene
-        .copy_output_texture(&state.graphics_context, true)
-        .await?;
-    let render_bytes = create_png_from_bytes((size.width, size.height), &render)?;
+    let fit_padding = request
+        .get_fit_padding()
+        .or_else(|| is_posed.then(|| RenderRequestFit::default().padding));
+
+    if let Some(padding) = fit_padding {
+        scene.fit_camera_to_parts(&state.graphics_context, padding);
+    }
+
+    if let Some(pixel_scale) = request.get_pixel_scale() {
+        if !scene.snap_camera_to_pixel_scale(&state.graphics_context, pixel_scale) {
+            return Err(RenderRequestError::PixelScaleTooLargeError(
+                pixel_scale,
+                size.width,
+                size.height,
+            )
+            .into());
+        }
+    }
+
+    // Auxiliary passes store data in the alpha channel, so it must be kept as is
+    let cleanup_alpha = request.get_aux_pass().is_none();
+
+    load_textures(resolved, state, request, &part_context, &mut scene, 0).await?;
+
+    if let Some((_, nametag_texture)) = &nametag {
+        scene.set_player_texture(
+            &state.graphics_context,
+            0,
+            PlayerPartTextureType::Nametag,
+            nametag_texture,
+        );
+    }
+
+    let animation = request
+        .extra_settings
+        .as_ref()
+        .and_then(|x| x.animation.as_ref());
+
+    let (render_bytes, output_size, offset) = if request.get_format() == RenderRequestFormat::Svg {
+        // Vector renders are drawn from the textures themselves, without going through the rendering pipeline
+        let svg = scene.render_vector().to_svg();
+
+        (svg.into_bytes(), (size.width, size.height), (0, 0))
+    } else {
+        let animation_frames = animation.zip(request.get_animation_frames());
+        let mut rendered_frames = vec![];
+
+        if let Some((animation, frames)) = animation_frames.clone() {
+            let start_time = part_context.movement.time;
+
+            for tick in frames {
+                let time = tick as f32 / ANIMATION_TICKS_PER_SECOND;
+                part_context.movement.animation_pose = Some(animation.animation.sample(time));
+
+                // The entry's own movement (like Ears tails and wings) and the glint keep going along with the animation
+                part_context.movement.time = start_time + tick as f32;
+                *scene.glint_information_mut() = GlintInformation::new(part_context.movement.time);
+                scene.update(&state.graphics_context);
+
+                scene.rebuild_parts(&part_context, &parts);
+
+                rendered_frames.push(
+                    scene
+                        .render_in_tiles(
+                            &state.graphics_context,
+                            RenderRequestMode::MAX_TILE_SIZE,
+                            cleanup_alpha,
+                        )
+                        .await?,
+                );
+            }
+        } else {
+            rendered_frames.push(
+                scene
+                    .render_in_tiles(
+                        &state.graphics_context,
+                        RenderRequestMode::MAX_TILE_SIZE,
+                        cleanup_alpha,
+                    )
+                    .await?,
+            );
+        }
+
+        let (output_size, offset) = match request.get_trim() {
+            Some(trim) => trim_frames((size.width, size.height), &mut rendered_frames, trim)?,
+            None => ((size.width, size.height), (0, 0)),
+        };
+
+        let render_bytes = if animation_frames.is_some() {
+            create_gif_from_frames(output_size, rendered_frames, ANIMATION_TICKS_PER_SECOND)?
+        } else {
+            create_png_from_bytes(output_size, &rendered_frames[0])?
+        };
+
+        (render_bytes, output_size, offset)
+    };
+
+    // Animated renders end on their last frame, so that's the one the layout describes
+    let layout = request
+        .get_layout()
+        .and_then(|_| scene.compute_layout().into_iter().next())
+        .map(|layout| {
+            let (width, height) = output_size;
+            RenderLayout::new(Size { width, height }, offset, layout)
+        });
 
     #[cfg(feature = "renderdoc")]
     {
@@ -104,7 +234,7 @@ pub(crate) async fn internal_render_model<'a>(
         }
     }
 
-    Ok(render_bytes)
+    Ok((render_bytes, layout))
 }
 
 #[cfg(feature = "ears")]
@@ -168,13 +298,15 @@ fn cleanup_invalid_ears_data(
     }
 }
 
+/// Uploads the textures of the given player of the scene.
 #[instrument(skip_all)]
-async fn load_textures<'a>(
+pub(crate) async fn load_textures<'a>(
     resolved: &ResolvedRenderRequest,
     state: &NMSRState<'a>,
     request: &RenderRequest,
-    part_provider: &mut PlayerPartProviderContext<VanillaMinecraftArmorMaterialData>,
+    part_provider: &PlayerPartProviderContext<VanillaMinecraftArmorMaterialData>,
     scene: &mut Scene<Object<SceneContextPoolManager<'a>>>,
+    player: usize,
 ) -> Result<()> {
     for (&texture_type, texture_bytes) in &resolved.textures {
         let expected_size = PlayerPartTextureType::from(texture_type).get_texture_size();
@@ -195,7 +327,12 @@ async fn load_textures<'a>(
             )?;
         }
 
-        scene.set_texture(&state.graphics_context, texture_type.into(), &image_buffer);
+        scene.set_player_texture(
+            &state.graphics_context,
+            player,
+            texture_type.into(),
+            &image_buffer,
+        );
     }
 
     if let (Some(armor_manager), Some(armor_slots)) =
@@ -204,24 +341,112 @@ async fn load_textures<'a>(
         let (main_layer, second_armor_layer) =
             armor_manager.create_armor_texture(armor_slots).await?;
 
-        scene.set_texture(
+        scene.set_player_texture(
             &state.graphics_context,
+            player,
             VanillaMinecraftArmorMaterialData::ARMOR_TEXTURE_ONE,
             &main_layer,
         );
 
         if let Some(second_armor_layer) = second_armor_layer {
-            scene.set_texture(
+            scene.set_player_texture(
                 &state.graphics_context,
+                player,
                 VanillaMinecraftArmorMaterialData::ARMOR_TEXTURE_TWO,
                 &second_armor_layer,
             );
         }
+
+        let has_glint = armor_slots
+            .get_all_materials_in_slots()
+            .iter()
+            .any(|(data, _)| data.glint);
+
+        if has_glint {
+            scene.set_player_texture(
+                &state.graphics_context,
+                player,
+                PlayerPartTextureType::Glint,
+                &armor_manager.create_glint_texture().await?,
+            );
+        }
+    }
+
+    if part_provider.has_elytra {
+        scene.set_player_texture(
+            &state.graphics_context,
+            player,
+            PlayerPartTextureType::Elytra,
+            &load_elytra_texture(resolved, state, request).await?,
+        );
     }
 
     Ok(())
 }
 
+/// Writes the name tag of the request, if it asked for one.
+///
+/// The name tag shows the given text, or the name of the player if none was given.
+pub(crate) fn create_nametag(
+    state: &NMSRState<'_>,
+    request: &RenderRequest,
+    resolved: &ResolvedRenderRequest,
+) -> Result<Option<(SceneNametag, RgbaImage)>> {
+    let (Some(font), Some(text)) = (
+        &state.nametag_font,
+        request
+            .extra_settings
+            .as_ref()
+            .and_then(|x| x.nametag.as_deref()),
+    ) else {
+        return Ok(None);
+    };
+
+    let text = if text.is_empty() {
+        match (&resolved.name, &request.entry) {
+            (Some(name), _) | (None, RenderRequestEntry::MojangPlayerName(name)) => name.as_str(),
+            _ => return Err(RenderRequestError::MissingNametagName.into()),
+        }
+    } else {
+        text
+    };
+
+    Ok(Some(font.render(text)))
+}
+
+/// Loads the texture of the elytra, which is the custom elytra texture if one was asked for,
+/// the player's cape if they have one (like in game), or the default elytra texture otherwise.
+pub(crate) async fn load_elytra_texture(
+    resolved: &ResolvedRenderRequest,
+    state: &NMSRState<'_>,
+    request: &RenderRequest,
+) -> Result<RgbaImage> {
+    let custom_elytra = request
+        .extra_settings
+        .as_ref()
+        .and_then(|x| x.elytra_texture.as_deref());
+
+    if let Some(texture_hash) = custom_elytra {
+        let elytra = state.resolver.resolve_texture_hash(texture_hash).await?;
+
+        return load_image_raw(&elytra);
+    }
+
+    let cape = resolved
+        .textures
+        .get(&ResolvedRenderEntryTextureType::Cape)
+        .filter(|_| request.features.contains(RenderRequestFeatures::Cape));
+
+    if let Some(cape) = cape {
+        return load_image_raw(cape);
+    }
+
+    match &state.armor_manager {
+        Some(armor_manager) => armor_manager.create_elytra_texture().await,
+        None => Err(RenderRequestError::MissingTexture("elytra".to_string()).into()),
+    }
+}
+
 pub(crate) fn load_image_raw(texture: &[u8]) -> Result<RgbaImage> {
     let img = image::load_from_memory_with_format(texture, ImageFormat::Png)
         .map_err(NMSRRenderingError::ImageFromRawError)?;
@@ -284,6 +509,8 @@ pub(crate) fn create_part_context(
         has_cape_feature && (has_cape || (has_ears_feature && has_ears_cape))
     };
 
+    let has_elytra = request.features.contains(RenderRequestFeatures::Elytra);
+
     let shadow_y_pos = request.get_shadow_y_pos();
 
     let player_armor_slots = PlayerArmorSlots::<VanillaMinecraftArmorMaterialData> {
@@ -305,6 +532,19 @@ pub(crate) fn create_part_context(
             .and_then(|x| x.boots.clone()),
     };
 
+    let animation = request
+        .extra_settings
+        .as_ref()
+        .and_then(|x| x.animation.as_ref());
+
+    // Animations take over the whole body, so the named pose is ignored when there's one
+    let pose = request
+        .extra_settings
+        .as_ref()
+        .and_then(|x| x.pose)
+        .filter(|_| animation.is_none())
+        .unwrap_or_default();
+
     let movement = PlayerMovementContext {
         time: request
             .extra_settings
@@ -316,6 +556,16 @@ pub(crate) fn create_part_context(
             .as_ref()
             .and_then(|x| x.limb_swing)
             .unwrap_or(0f32),
+        is_gliding: request.extra_settings.as_ref().is_some_and(|x| x.gliding),
+        is_swimming: pose == PlayerPose::Swimming,
+        is_crawling: pose == PlayerPose::Crawling,
+        pose,
+        body_part_rotations: request
+            .extra_settings
+            .as_ref()
+            .and_then(|x| x.body_part_rotations)
+            .unwrap_or_default(),
+        animation_pose: animation.map(|a| a.animation.sample(a.timestamp)),
         ..Default::default()
     };
 
@@ -327,9 +577,11 @@ pub(crate) fn create_part_context(
         has_deadmau5_ears,
         is_flipped_upside_down,
         has_cape,
+        has_elytra,
         custom_arm_rotation_z,
         shadow_y_pos,
         shadow_is_square: request.mode.is_head() || request.mode.is_head_iso(),
+        skull: request.get_skull_placement(),
         armor_slots: Some(player_armor_slots),
         movement,
         #[cfg(feature = "ears")]
@@ -0,0 +1,299 @@
+use axum::{extract::State, response::Response, Json};
+use nmsr_rendering::{
+    high_level::{
+        camera::Camera,
+        pipeline::scene::{Scene, ScenePlayer, Size},
+        types::PlayerPartTextureType,
+    },
+    low_level::Vec3,
+};
+use serde::Deserialize;
+use tracing::instrument;
+
+use super::{
+    query::RenderRequestQueryParams,
+    render::{create_image_response, IMAGE_PNG_MIME, IMAGE_SVG_MIME},
+    render_model::{create_nametag, create_part_context, load_textures},
+    NMSRState, RenderRequestValidator,
+};
+use crate::{
+    error::{RenderRequestError, Result},
+    model::request::{
+        entry::RenderRequestEntry, RenderRequest, RenderRequestFeatures, RenderRequestFormat,
+        RenderRequestMode,
+    },
+    utils::{png::create_png_from_bytes, trim::trim_frames},
+};
+
+/// A scene with several entries standing next to each other.
+///
+/// The scene options (camera, size, excluded features) are given next to the players,
+/// each player having its own options (model, pose, armor...).
+#[derive(Debug, Clone, Deserialize)]
+pub struct RenderSceneParams {
+    pub mode: String,
+    pub players: Vec<RenderScenePlayerParams>,
+    /// How far apart the players stand from each other (defaults to 20 pixels).
+    pub spacing: Option<f32>,
+
+    #[serde(flatten)]
+    pub query: RenderRequestQueryParams,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct RenderScenePlayerParams {
+    pub entry: String,
+    /// How far the player is moved from its place in the line, in pixels (e.g. to stand on a podium).
+    pub offset: Option<[f32; 3]>,
+
+    #[serde(flatten)]
+    pub query: RenderRequestQueryParams,
+}
+
+impl RenderSceneParams {
+    pub const DEFAULT_SPACING: f32 = 20.0;
+    pub const MAX_SPACING: f32 = 64.0;
+    pub const MAX_PLAYERS: usize = 8;
+
+    /// The size of the room a standing player takes, which we use to fit the players in the render.
+    const PLAYER_WIDTH: f32 = 20.0;
+    const PLAYER_HEIGHT: f32 = 32.0;
+}
+
+/// Render several entries in the same image.
+///
+/// URLs have the following format:
+///  - `POST /scene`, with a JSON body like `{"mode": "fullbody", "players": [{"entry": "<entry>", "pose": "waving"}]}`
+///
+/// Players are lined up from left to right, in the order they're given.
+#[axum::debug_handler]
+#[instrument(skip(state))]
+pub async fn render_scene(
+    state: State<NMSRState<'static>>,
+    Json(params): Json<RenderSceneParams>,
+) -> Result<Response> {
+    let mode = RenderRequestMode::try_from(params.mode.as_str())
+        .ok()
+        .filter(|mode| mode.uses_rendering_pipeline() && state.validate_mode(mode))
+        .ok_or_else(|| RenderRequestError::InvalidRenderMode(params.mode.clone()))?;
+
+    if params.players.is_empty() || params.players.len() > RenderSceneParams::MAX_PLAYERS {
+        return Err(RenderRequestError::InvalidRenderSettingError(
+            "players",
+            format!("between 1 and {}", RenderSceneParams::MAX_PLAYERS),
+        )
+        .into());
+    }
+
+    RenderRequestMode::validate_unit(
+        "spacing",
+        params.spacing,
+        0.0,
+        RenderSceneParams::MAX_SPACING,
+    )?;
+
+    let spacing = params.spacing.unwrap_or(RenderSceneParams::DEFAULT_SPACING);
+
+    // The scene request is only used for the camera, the size and the lighting of the render
+    let scene_entry = RenderRequestEntry::try_from(params.players[0].entry.clone())?;
+    let scene_request =
+        RenderRequest::from_query_params(&*state, mode, scene_entry, params.query, None)?;
+
+    let mut requests = Vec::with_capacity(params.players.len());
+    let mut offsets = Vec::with_capacity(params.players.len());
+
+    let middle = (params.players.len() - 1) as f32 / 2.0;
+
+    for (index, player) in params.players.into_iter().enumerate() {
+        let entry = RenderRequestEntry::try_from(player.entry)?;
+        let request = RenderRequest::from_query_params(&*state, mode, entry, player.query, None)?;
+
+        // Players are lined up from left to right, which is towards -x when looking at them
+        let [x, y, z] = player.offset.unwrap_or_default();
+        offsets.push(Vec3::new((middle - index as f32) * spacing + x, y, z));
+
+        requests.push(request);
+    }
+
+    let mut resolved = Vec::with_capacity(requests.len());
+    for request in &requests {
+        resolved.push(state.resolver.resolve(request).await?);
+    }
+
+    let part_contexts = requests
+        .iter_mut()
+        .zip(&resolved)
+        .map(|(request, resolved)| create_part_context(request, resolved))
+        .collect::<Vec<_>>();
+
+    let nametags = requests
+        .iter()
+        .zip(&resolved)
+        .map(|(request, resolved)| create_nametag(&state, request, resolved))
+        .collect::<Result<Vec<_>>>()?;
+
+    let body_parts = mode.get_body_parts();
+
+    let (mut camera, size) = get_scene_camera_and_size(&scene_request, &offsets);
+    let lighting = scene_request.get_lighting();
+
+    if nametags.iter().any(Option::is_some) {
+        NMSRState::apply_nametag_camera_settings(mode, &mut camera);
+    }
+
+    let players = part_contexts
+        .iter()
+        .zip(&offsets)
+        .zip(&nametags)
+        .map(|((part_context, &offset), nametag)| ScenePlayer {
+            part_context,
+            body_parts: &body_parts,
+            offset,
+            nametag: nametag.as_ref().map(|(nametag, _)| *nametag),
+        })
+        .collect::<Vec<_>>();
+
+    let scene_context = state
+        .create_scene_context(scene_request.get_anti_aliasing())
+        .await?;
+
+    let mut scene = Scene::new_group(
+        &state.graphics_context,
+        scene_context,
+        camera,
+        lighting,
+        size,
+        &players,
+    );
+
+    *scene.outline_mut() = scene_request.get_outline();
+    *scene.glow_strength_mut() = scene_request.get_glow_strength();
+    *scene.aux_pass_mut() = scene_request.get_aux_pass();
+    *scene.keep_texture_images_mut() = scene_request.get_format() == RenderRequestFormat::Svg;
+
+    if let Some(padding) = scene_request.get_fit_padding() {
+        scene.fit_camera_to_parts(&state.graphics_context, padding);
+    }
+
+    if let Some(pixel_scale) = scene_request.get_pixel_scale() {
+        if !scene.snap_camera_to_pixel_scale(&state.graphics_context, pixel_scale) {
+            return Err(RenderRequestError::PixelScaleTooLargeError(
+                pixel_scale,
+                size.width,
+                size.height,
+            )
+            .into());
+        }
+    }
+
+    for (player, ((request, resolved), part_context)) in requests
+        .iter()
+        .zip(&resolved)
+        .zip(&part_contexts)
+        .enumerate()
+    {
+        load_textures(resolved, &state, request, part_context, &mut scene, player).await?;
+    }
+
+    for (player, nametag) in nametags.iter().enumerate() {
+        if let Some((_, nametag_texture)) = nametag {
+            scene.set_player_texture(
+                &state.graphics_context,
+                player,
+                PlayerPartTextureType::Nametag,
+                nametag_texture,
+            );
+        }
+    }
+
+    if scene_request.get_format() == RenderRequestFormat::Svg {
+        let svg = scene.render_vector().to_svg();
+
+        return Ok(create_image_response(
+            svg,
+            &state,
+            &scene_request,
+            IMAGE_SVG_MIME,
+        ));
+    }
+
+    // Auxiliary passes store data in the alpha channel, so it must be kept as is
+    let cleanup_alpha = scene_request.get_aux_pass().is_none();
+
+    let mut render = vec![
+        scene
+            .render_in_tiles(
+                &state.graphics_context,
+                RenderRequestMode::MAX_TILE_SIZE,
+                cleanup_alpha,
+            )
+            .await?,
+    ];
+
+    let output_size = match scene_request.get_trim() {
+        Some(trim) => trim_frames((size.width, size.height), &mut render, trim)?.0,
+        None => (size.width, size.height),
+    };
+
+    let result = create_png_from_bytes(output_size, &render[0])?;
+
+    Ok(create_image_response(
+        result,
+        &state,
+        &scene_request,
+        IMAGE_PNG_MIME,
+    ))
+}
+
+/// Widens the camera of the scene request to fit every player of the scene.
+///
+/// The image gets wider first, and once it can't anymore, the camera moves back instead.
+fn get_scene_camera_and_size(scene_request: &RenderRequest, offsets: &[Vec3]) -> (Camera, Size) {
+    let mode = scene_request.mode;
+    let mut camera = scene_request.get_camera();
+    let mut size = scene_request.get_size();
+
+    if scene_request
+        .features
+        .contains(RenderRequestFeatures::FlipUpsideDown)
+    {
+        NMSRState::apply_upside_down_camera_settings(mode, &mut camera);
+    }
+
+    let (min_x, max_x) = offsets
+        .iter()
+        .fold((0f32, 0f32), |(min, max), o| (min.min(o.x), max.max(o.x)));
+    let (min_y, max_y) = offsets
+        .iter()
+        .fold((0f32, 0f32), |(min, max), o| (min.min(o.y), max.max(o.y)));
+
+    // How many players' worth of room we need, side by side and on top of each other
+    let horizontal_factor = 1.0 + (max_x - min_x) / RenderSceneParams::PLAYER_WIDTH;
+    let vertical_factor = 1.0 + (max_y - min_y) / RenderSceneParams::PLAYER_HEIGHT;
+
+    let wanted_width = (size.width as f32 * horizontal_factor).round() as u32;
+    // Poster renders can already be wider than that, in which case they don't get any wider
+    let width = wanted_width.min(RenderRequestMode::MAX_RENDER_WIDTH.max(size.width));
+    let width_factor = width as f32 / size.width as f32;
+
+    if let Some(mut camera_size) = camera.get_size() {
+        camera_size.width = (camera_size.width as f32 * width_factor).round() as u32;
+        camera.set_size(Some(camera_size));
+    }
+    size.width = width;
+
+    let zoom_factor = (horizontal_factor / width_factor).max(vertical_factor);
+
+    if zoom_factor > 1.0 {
+        if mode.is_isometric() {
+            camera.set_aspect(camera.get_aspect() * zoom_factor);
+        } else {
+            camera.set_distance(camera.get_distance() * zoom_factor);
+        }
+    }
+
+    camera.set_look_at_x(camera.get_look_at_x() + (min_x + max_x) / 2.0);
+    camera.set_look_at_y(camera.get_look_at_y() + (min_y + max_y) / 2.0);
+
+    (camera, size)
+}
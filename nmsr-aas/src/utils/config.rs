@@ -15,8 +15,11 @@ use twelf::config;
 
 use crate::{
     error::ExplainableExt,
-    model::request::{
-        cache::CacheBias, entry::RenderRequestEntry, RenderRequestFeatures, RenderRequestMode,
+    model::{
+        assets::AssetSource,
+        request::{
+            cache::CacheBias, entry::RenderRequestEntry, RenderRequestFeatures, RenderRequestMode,
+        },
     },
 };
 
@@ -29,6 +32,8 @@ pub struct NmsrConfiguration {
     pub mojank: MojankConfiguration,
     pub rendering: Option<RenderingConfiguration>,
     pub features: Option<FeaturesConfiguration>,
+    pub armor: Option<ArmorConfiguration>,
+    pub nametag: Option<NametagConfiguration>,
 }
 
 #[serde_as]
@@ -220,6 +225,48 @@ pub struct FeaturesConfiguration {
     /// Whether to disable downloading armor textures - this makes it so there is no armor rendering support.
     #[serde(default)]
     pub disable_armor_rendering: bool,
+
+    /// Whether to allow renders of up to 8192 pixels on each side (e.g. for print), which are rendered in tiles.
+    #[serde(default)]
+    pub allow_poster_renders: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct ArmorConfiguration {
+    /// The local sources to read armor textures from, in order of priority.
+    /// Each source can be a directory, a resource pack zip or a vanilla client jar.
+    /// Assets found in these sources always take precedence over previously cached ones.
+    pub asset_sources: Vec<AssetSource>,
+
+    /// Whether to download armor textures that aren't present in any of the local sources.
+    /// Disable this if the server has no internet access.
+    pub download_missing_assets: bool,
+}
+
+impl Default for ArmorConfiguration {
+    fn default() -> Self {
+        Self {
+            asset_sources: Vec::new(),
+            download_missing_assets: true,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct NametagConfiguration {
+    /// The font used to write the name tags with.
+    pub font: NametagFontSource,
+}
+
+/// A local location that the name tag font can be read from.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum NametagFontSource {
+    /// The default font of a directory, resource pack zip or client jar (`assets/minecraft/font/default.json`).
+    Assets { source: AssetSource },
+    /// A glyph sheet image, along with the JSON describing it (written like a bitmap provider of the game's fonts).
+    GlyphSheet { image: PathBuf, definition: PathBuf },
 }
 
 impl ModelCacheConfiguration {
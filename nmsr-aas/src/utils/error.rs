@@ -19,6 +19,8 @@ pub enum NMSRaaSError {
     RenderError(#[from] nmsr_rendering::errors::NMSRRenderingError),
     #[error("Armor manager error: {0}")]
     ArmorManagerError(#[from] ArmorManagerError),
+    #[error("Name tag font error: {0}")]
+    NametagFontError(#[from] NametagFontError),
 
     #[error("{0}")]
     ClonedError(String),
@@ -70,8 +72,16 @@ pub enum RenderRequestError {
     InvalidModeSettingSpecifiedError(&'static str, &'static str),
     #[error("Missing render request texture. Did you forget to specify a texture?")]
     MissingRenderRequestEntry,
+    #[error("Unable to read the animation you've uploaded: {0}")]
+    InvalidAnimationError(String),
+    #[error("You've specified {0}, which only applies to an uploaded animation. Did you forget to upload one with a POST request?")]
+    MissingAnimationError(&'static str),
     #[error("Invalid HTTP Method. Did you mean to use \"{1}\" instead of \"{0}\"? This endpoint only supports \"{0}\".")]
     WrongHttpMethodError(&'static str, &'static str),
+    #[error("There's no name to show on the name tag of this entry. Did you forget to specify it with ?nametag=<name>?")]
+    MissingNametagName,
+    #[error("A pixel scale of {0} doesn't fit the whole model in a {1}x{2} render. Use a smaller pixel scale or a bigger size.")]
+    PixelScaleTooLargeError(u32, u32, u32),
 }
 
 impl RenderRequestError {
@@ -86,7 +96,11 @@ impl RenderRequestError {
                 | Self::InvalidRenderSettingError(_, _)
                 | Self::InvalidModeSettingSpecifiedError(_, _)
                 | Self::MissingRenderRequestEntry
+                | Self::InvalidAnimationError(_)
+                | Self::MissingAnimationError(_)
                 | Self::WrongHttpMethodError(_, _)
+                | Self::MissingNametagName
+                | Self::PixelScaleTooLargeError(_, _, _)
         )
     }
 
@@ -182,6 +196,36 @@ pub enum ArmorManagerError {
     UnknownPartialArmorMaterialName(String),
     #[error("Invalid trim count: {0}")]
     InvalidTrimCountError(usize),
+    #[error("Unknown custom armor asset: {0}")]
+    UnknownCustomArmorAsset(String),
+    #[error("Invalid trim palette {0}, expected an image at least 8 pixels wide")]
+    InvalidTrimPaletteError(String),
+    #[error("Unknown armor modifier: {0}")]
+    UnknownArmorModifierError(String),
+}
+
+impl ArmorManagerError {
+    #[must_use]
+    pub const fn is_bad_request(&self) -> bool {
+        matches!(
+            self,
+            Self::EmptyArmorSlotError
+                | Self::UnknownPartialArmorMaterialName(_)
+                | Self::InvalidTrimCountError(_)
+                | Self::UnknownCustomArmorAsset(_)
+                | Self::UnknownArmorModifierError(_)
+        )
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum NametagFontError {
+    #[error("Missing font asset: {0}")]
+    MissingFontAsset(String),
+    #[error("Invalid font definition {0}: {1}")]
+    InvalidFontDefinition(String, serde_json::Error),
+    #[error("Unable to load font image {0}: {1}")]
+    FontImageLoadError(String, image::error::ImageError),
 }
 
 pub(crate) type Result<T> = std::result::Result<T, NMSRaaSError>;
@@ -222,10 +266,10 @@ impl IntoResponse for NMSRaaSError {
     fn into_response(self) -> axum::response::Response {
         let mut res = axum::response::IntoResponse::into_response(self.to_string());
 
-        let is_bad_request = if let Self::RenderRequestError(error) = &self {
-            error.is_bad_request()
-        } else {
-            false
+        let is_bad_request = match &self {
+            Self::RenderRequestError(error) => error.is_bad_request(),
+            Self::ArmorManagerError(error) => error.is_bad_request(),
+            _ => false,
         };
 
         #[rustfmt::skip]
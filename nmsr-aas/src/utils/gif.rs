@@ -0,0 +1,40 @@
+use image::{
+    codecs::gif::{GifEncoder, Repeat},
+    Delay, Frame, RgbaImage,
+};
+use tracing::trace_span;
+
+use crate::error::{NMSRaaSError, Result};
+
+/// Encodes raw RGBA frames into a looping animated GIF, played back at the given frame rate.
+pub(crate) fn create_gif_from_frames(
+    (width, height): (u32, u32),
+    frames: Vec<Vec<u8>>,
+    frames_per_second: f32,
+) -> Result<Vec<u8>> {
+    let mut out = Vec::new();
+
+    let _guard = trace_span!("write_gif_bytes").entered();
+
+    let delay = Delay::from_numer_denom_ms(1000, frames_per_second as u32);
+
+    let frames = frames
+        .into_iter()
+        .map(|bytes| {
+            RgbaImage::from_raw(width, height, bytes)
+                .map(|image| Frame::from_parts(image, 0, 0, delay))
+                .ok_or_else(|| NMSRaaSError::ClonedError("Invalid frame size".to_string()))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    {
+        let mut encoder = GifEncoder::new(&mut out);
+
+        encoder
+            .set_repeat(Repeat::Infinite)
+            .and_then(|_| encoder.encode_frames(frames))
+            .map_err(|e| NMSRaaSError::ClonedError(e.to_string()))?;
+    }
+
+    Ok(out)
+}
@@ -1,6 +1,8 @@
 pub mod caching;
 pub mod config;
 pub mod error;
+pub mod gif;
 pub mod http_client;
 pub mod png;
 pub mod tracing;
+pub mod trim;
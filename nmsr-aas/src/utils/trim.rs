@@ -0,0 +1,126 @@
+use image::{imageops, GenericImageView, RgbaImage};
+use tracing::trace_span;
+
+use crate::{
+    error::{NMSRaaSError, Result},
+    model::request::RenderRequestTrim,
+};
+
+/// Crops the transparent margins around raw RGBA frames, then pads them back as asked.
+///
+/// Every frame is cropped the same way (to the bounds of all of them), so that animations don't jump around.
+/// Returns the new size of the frames, and where the top left corner of the old frames ended up.
+pub(crate) fn trim_frames(
+    (width, height): (u32, u32),
+    frames: &mut [Vec<u8>],
+    trim: RenderRequestTrim,
+) -> Result<((u32, u32), (i32, i32))> {
+    let _guard = trace_span!("trim_frames").entered();
+
+    let images = frames
+        .iter_mut()
+        .map(|bytes| {
+            RgbaImage::from_raw(width, height, std::mem::take(bytes))
+                .ok_or_else(|| NMSRaaSError::ClonedError("Invalid frame size".to_string()))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    // Fully transparent renders are cropped down to nothing, leaving only the padding
+    let (min_x, min_y, max_x, max_y) = images
+        .iter()
+        .flat_map(|image| image.enumerate_pixels())
+        .filter(|(_, _, pixel)| pixel[3] != 0)
+        .fold(
+            (u32::MAX, u32::MAX, 0, 0),
+            |(min_x, min_y, max_x, max_y), (x, y, _)| {
+                (
+                    min_x.min(x),
+                    min_y.min(y),
+                    max_x.max(x + 1),
+                    max_y.max(y + 1),
+                )
+            },
+        );
+
+    let (min_x, min_y) = (min_x.min(max_x), min_y.min(max_y));
+    let (content_width, content_height) = (max_x - min_x, max_y - min_y);
+
+    let mut trimmed_width = content_width + trim.padding * 2;
+    let mut trimmed_height = content_height + trim.padding * 2;
+
+    if trim.square {
+        let side = trimmed_width.max(trimmed_height);
+        (trimmed_width, trimmed_height) = (side, side);
+    }
+
+    // Images can't be empty, so keep at least one (transparent) pixel
+    let (trimmed_width, trimmed_height) = (trimmed_width.max(1), trimmed_height.max(1));
+
+    let content_x = (trimmed_width - content_width) / 2;
+    let content_y = (trimmed_height - content_height) / 2;
+
+    for (bytes, image) in frames.iter_mut().zip(images) {
+        let mut trimmed = RgbaImage::new(trimmed_width, trimmed_height);
+        let content = image.view(min_x, min_y, content_width, content_height);
+
+        imageops::replace(&mut trimmed, &*content, content_x.into(), content_y.into());
+
+        *bytes = trimmed.into_raw();
+    }
+
+    let offset = (
+        content_x as i32 - min_x as i32,
+        content_y as i32 - min_y as i32,
+    );
+
+    Ok(((trimmed_width, trimmed_height), offset))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame_with_pixel(x: u32, y: u32) -> Vec<u8> {
+        let mut image = RgbaImage::new(16, 16);
+        image.put_pixel(x, y, [255; 4].into());
+
+        image.into_raw()
+    }
+
+    #[test]
+    fn trim_crops_to_every_frame() {
+        let mut frames = vec![frame_with_pixel(2, 3), frame_with_pixel(5, 9)];
+
+        let (size, offset) = trim_frames(
+            (16, 16),
+            &mut frames,
+            RenderRequestTrim {
+                padding: 1,
+                square: false,
+            },
+        )
+        .unwrap();
+
+        assert_eq!(size, (6, 9));
+        assert_eq!(offset, (-1, -2));
+        assert_eq!(frames[0].len(), 6 * 9 * 4);
+    }
+
+    #[test]
+    fn trim_pads_to_a_square() {
+        let mut frames = vec![frame_with_pixel(0, 0), frame_with_pixel(0, 9)];
+
+        let (size, offset) = trim_frames(
+            (16, 16),
+            &mut frames,
+            RenderRequestTrim {
+                padding: 0,
+                square: true,
+            },
+        )
+        .unwrap();
+
+        assert_eq!(size, (10, 10));
+        assert_eq!(offset, (4, 0));
+    }
+}
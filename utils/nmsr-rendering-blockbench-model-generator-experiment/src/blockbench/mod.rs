@@ -228,7 +228,7 @@ fn convert_to_raw_project_textures<M: ArmorMaterial, I: ModelProjectImageIO>(
     (ProjectTextureResolution::new(res.x, res.y), textures)
 }
 
-fn get_texture_name(texture: PlayerPartTextureType) -> String {
+pub(crate) fn get_texture_name(texture: PlayerPartTextureType) -> String {
     format!(
         "{}.png",
         match texture {
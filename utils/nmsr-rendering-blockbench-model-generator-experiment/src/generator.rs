@@ -68,11 +68,13 @@ pub fn new_model_generator_without_part_context<I: ModelProjectImageIO>(
         has_hat_layer: layers,
         has_layers: layers,
         has_cape: false,
+        has_elytra: false,
         has_deadmau5_ears: false,
         is_flipped_upside_down: false,
         custom_arm_rotation_z: Some(10.0),
         shadow_y_pos: None,
         shadow_is_square: false,
+        skull: None,
         armor_slots: None,
         movement: Default::default(),
         #[cfg(feature = "ears")]
@@ -212,6 +214,8 @@ impl<M: ArmorMaterial, I: ModelProjectImageIO> ModelGenerationProject<M, I> {
                 ears_rs::utils::strip_alpha(&mut texture);
             } else if texture_type == PlayerPartTextureType::Cape {
                 self.part_context.has_cape = true;
+            } else if texture_type == PlayerPartTextureType::Elytra {
+                self.part_context.has_elytra = true;
             }
         }
 
@@ -246,7 +250,12 @@ impl<M: ArmorMaterial, I: ModelProjectImageIO> ModelGenerationProject<M, I> {
             .filter(|p| !(p.is_layer() || p.is_hat_layer()) || self.part_context.has_layers)
             .collect_vec();
 
-        self.part_context.get_parts(&self.providers, &body_parts)
+        self.part_context
+            .get_parts(&self.providers, &body_parts)
+            .into_iter()
+            // The enchantment glint is purely a rendering effect, Blockbench has no way of representing it
+            .filter(|p| !p.get_texture().is_glint())
+            .collect()
     }
 
     pub(crate) fn get_texture(&self, texture_type: PlayerPartTextureType) -> Option<&RgbaImage> {
@@ -0,0 +1,609 @@
+use glam::Vec3;
+use itertools::Itertools;
+use nmsr_rendering::{
+    high_level::{model::ArmorMaterial, parts::part::Part, utils::parts::primitive_convert},
+    low_level::primitives::part_primitive::PartPrimitive,
+};
+use serde_json::{json, Value};
+
+use crate::{
+    blockbench::get_texture_name,
+    error::{BlockbenchGeneratorError, Result},
+    generator::{ModelGenerationProject, ModelProjectImageIO},
+};
+
+const GLB_MAGIC: u32 = 0x4654_6C67;
+const GLB_VERSION: u32 = 2;
+const GLB_JSON_CHUNK: u32 = 0x4E4F_534A;
+const GLB_BIN_CHUNK: u32 = 0x004E_4942;
+
+const GL_FLOAT: u32 = 5126;
+const GL_UNSIGNED_INT: u32 = 5125;
+const GL_NEAREST: u32 = 9728;
+const GL_CLAMP_TO_EDGE: u32 = 33071;
+const GL_ARRAY_BUFFER: u32 = 34962;
+const GL_ELEMENT_ARRAY_BUFFER: u32 = 34963;
+
+/// Parts are in pixels, while glTF models are in meters (and a block, which is 16 pixels, is a meter).
+const PIXELS_PER_METER: f32 = 16.0;
+
+/// A node of the exported model, which holds the parts of a group (e.g. a body part or an Ears feature).
+struct GltfNode {
+    name: String,
+    parent: Option<usize>,
+    children: Vec<usize>,
+    parts: Vec<Part>,
+    /// Where the node is placed in the model, which its parts are positioned from.
+    pivot: Vec3,
+}
+
+/// The binary chunk of a GLB file, along with the views and accessors describing what's in it.
+#[derive(Default)]
+struct GltfBuffer {
+    bytes: Vec<u8>,
+    buffer_views: Vec<Value>,
+    accessors: Vec<Value>,
+}
+
+impl GltfBuffer {
+    fn push_view(&mut self, data: &[u8], target: Option<u32>) -> usize {
+        // Every view starts on a multiple of 4 bytes, which is the size of the biggest component we store
+        self.bytes.resize(self.bytes.len().next_multiple_of(4), 0);
+
+        let mut view = json!({
+            "buffer": 0,
+            "byteOffset": self.bytes.len(),
+            "byteLength": data.len(),
+        });
+
+        if let Some(target) = target {
+            view["target"] = json!(target);
+        }
+
+        self.bytes.extend_from_slice(data);
+        self.buffer_views.push(view);
+
+        self.buffer_views.len() - 1
+    }
+
+    fn push_floats<const N: usize>(&mut self, values: &[[f32; N]], bounds: bool) -> usize {
+        let data = values
+            .iter()
+            .flatten()
+            .flat_map(|value| value.to_le_bytes())
+            .collect_vec();
+
+        let view = self.push_view(&data, Some(GL_ARRAY_BUFFER));
+
+        let mut accessor = json!({
+            "bufferView": view,
+            "componentType": GL_FLOAT,
+            "count": values.len(),
+            "type": format!("VEC{}", N),
+        });
+
+        // Positions need their bounds, which viewers use to frame the model
+        if bounds {
+            let fold = |init: f32, pick: fn(f32, f32) -> f32| {
+                (0..N)
+                    .map(|axis| values.iter().map(|v| v[axis]).fold(init, pick))
+                    .collect_vec()
+            };
+
+            accessor["min"] = json!(fold(f32::INFINITY, f32::min));
+            accessor["max"] = json!(fold(f32::NEG_INFINITY, f32::max));
+        }
+
+        self.accessors.push(accessor);
+        self.accessors.len() - 1
+    }
+
+    fn push_indices(&mut self, indices: &[u32]) -> usize {
+        let data = indices
+            .iter()
+            .flat_map(|index| index.to_le_bytes())
+            .collect_vec();
+
+        let view = self.push_view(&data, Some(GL_ELEMENT_ARRAY_BUFFER));
+
+        self.accessors.push(json!({
+            "bufferView": view,
+            "componentType": GL_UNSIGNED_INT,
+            "count": indices.len(),
+            "type": "SCALAR",
+        }));
+
+        self.accessors.len() - 1
+    }
+}
+
+/// Exports the parts of the project as a binary glTF 2.0 (`.glb`) model, posed like they'd be rendered.
+///
+/// Every group of parts (e.g. each body part, and each Ears feature within them) gets its own node, nested like the groups,
+/// with a mesh made of one primitive for each texture its parts use. Textures are embedded and sampled with nearest filtering.
+pub fn generate_gltf_binary<M: ArmorMaterial, I: ModelProjectImageIO>(
+    project: ModelGenerationProject<M, I>,
+) -> Result<Vec<u8>> {
+    let nodes = generate_nodes(project.generate_parts());
+
+    let textures = nodes
+        .iter()
+        .flat_map(|node| &node.parts)
+        .map(|part| part.get_texture())
+        .sorted()
+        .dedup()
+        .collect_vec();
+
+    let mut buffer = GltfBuffer::default();
+
+    let mut images = Vec::with_capacity(textures.len());
+    let mut materials = Vec::with_capacity(textures.len());
+
+    for (index, &texture_type) in textures.iter().enumerate() {
+        let texture = project
+            .get_texture(texture_type)
+            .ok_or(BlockbenchGeneratorError::TextureNotFound(texture_type))?;
+
+        let png = project.image_io().write_png(texture)?;
+        let view = buffer.push_view(&png, None);
+
+        images.push(json!({
+            "name": get_texture_name(texture_type),
+            "bufferView": view,
+            "mimeType": "image/png",
+        }));
+
+        let is_translucent = texture.pixels().any(|p| p[3] != 0 && p[3] != u8::MAX);
+
+        materials.push(json!({
+            "name": texture_type.to_string(),
+            "pbrMetallicRoughness": {
+                "baseColorTexture": { "index": index },
+                "metallicFactor": 0.0,
+                "roughnessFactor": 1.0,
+            },
+            "alphaMode": if is_translucent { "BLEND" } else { "MASK" },
+        }));
+    }
+
+    let mut meshes = vec![];
+    let mut gltf_nodes = Vec::with_capacity(nodes.len());
+
+    for node in &nodes {
+        let mut gltf_node = json!({ "name": node.name });
+
+        if !node.children.is_empty() {
+            gltf_node["children"] = json!(node.children);
+        }
+
+        let parent_pivot = node.parent.map_or(Vec3::ZERO, |parent| nodes[parent].pivot);
+        let translation = node.pivot - parent_pivot;
+
+        if translation != Vec3::ZERO {
+            gltf_node["translation"] = json!(translation.to_array());
+        }
+
+        if !node.parts.is_empty() {
+            let primitives = node
+                .parts
+                .iter()
+                .into_group_map_by(|part| part.get_texture())
+                .into_iter()
+                .sorted_by_key(|(texture, _)| *texture)
+                .map(|(texture, parts)| {
+                    let material = textures.binary_search(&texture).unwrap_or_default();
+                    generate_primitive(&mut buffer, &parts, node.pivot, material)
+                })
+                .collect_vec();
+
+            gltf_node["mesh"] = json!(meshes.len());
+            meshes.push(json!({ "name": node.name, "primitives": primitives }));
+        }
+
+        gltf_nodes.push(gltf_node);
+    }
+
+    // Parts face towards -Z with their left side towards -X, while glTF models face towards +Z with their left side towards +X
+    let scale = 1.0 / PIXELS_PER_METER;
+
+    gltf_nodes[0]["rotation"] = json!([0.0, 1.0, 0.0, 0.0]);
+    gltf_nodes[0]["scale"] = json!([scale, scale, scale]);
+
+    let texture_entries = (0..textures.len())
+        .map(|index| json!({ "sampler": 0, "source": index }))
+        .collect_vec();
+
+    buffer
+        .bytes
+        .resize(buffer.bytes.len().next_multiple_of(4), 0);
+
+    let gltf = json!({
+        "asset": { "version": "2.0", "generator": "NMSR" },
+        "scene": 0,
+        "scenes": [{ "nodes": [0] }],
+        "nodes": gltf_nodes,
+        "meshes": meshes,
+        "materials": materials,
+        "textures": texture_entries,
+        "images": images,
+        "samplers": [{
+            "magFilter": GL_NEAREST,
+            "minFilter": GL_NEAREST,
+            "wrapS": GL_CLAMP_TO_EDGE,
+            "wrapT": GL_CLAMP_TO_EDGE,
+        }],
+        "buffers": [{ "byteLength": buffer.bytes.len() }],
+        "bufferViews": buffer.buffer_views,
+        "accessors": buffer.accessors,
+    });
+
+    Ok(write_glb(gltf.to_string().into_bytes(), buffer.bytes))
+}
+
+/// Nests the parts in a node for each of their groups, below a root node holding the parts that aren't in any group.
+fn generate_nodes(parts: Vec<Part>) -> Vec<GltfNode> {
+    let mut nodes = vec![GltfNode {
+        name: "Player".to_string(),
+        parent: None,
+        children: vec![],
+        parts: vec![],
+        pivot: Vec3::ZERO,
+    }];
+
+    for part in parts {
+        let mut current = 0;
+
+        for group in part.get_group() {
+            let existing = nodes[current]
+                .children
+                .iter()
+                .copied()
+                .find(|&child| nodes[child].name == *group);
+
+            current = existing.unwrap_or_else(|| {
+                nodes.push(GltfNode {
+                    name: group.clone(),
+                    parent: Some(current),
+                    children: vec![],
+                    parts: vec![],
+                    pivot: Vec3::ZERO,
+                });
+
+                let child = nodes.len() - 1;
+                nodes[current].children.push(child);
+
+                child
+            });
+        }
+
+        nodes[current].parts.push(part);
+    }
+
+    // Nodes pivot around the point their parts rotate around (like the shoulders for the arms), if they have one.
+    // Parents always come before their children, so their pivots are known by then
+    for index in 1..nodes.len() {
+        let parent_pivot = nodes[nodes[index].parent.unwrap_or_default()].pivot;
+
+        nodes[index].pivot = nodes[index]
+            .parts
+            .iter()
+            .find_map(|part| part.part_tracking_data().last_rotation_origin())
+            .unwrap_or(parent_pivot);
+    }
+
+    nodes
+}
+
+/// Writes the faces of the given parts (which all share a texture) as a triangle list, positioned from the given pivot.
+fn generate_primitive(
+    buffer: &mut GltfBuffer,
+    parts: &[&Part],
+    pivot: Vec3,
+    material: usize,
+) -> Value {
+    let mut positions = vec![];
+    let mut normals = vec![];
+    let mut uvs = vec![];
+    let mut indices = vec![];
+
+    for part in parts {
+        let vertices = primitive_convert(part).get_vertices();
+
+        // Primitives are made of quads (top left, top right, bottom left and bottom right), wound clockwise
+        for quad in vertices.chunks_exact(4) {
+            let start = positions.len() as u32;
+
+            for vertex in quad {
+                positions.push((vertex.position - pivot).to_array());
+                normals.push(vertex.normal.normalize_or_zero().to_array());
+                uvs.push(vertex.uv.to_array());
+            }
+
+            // glTF front faces are wound counter-clockwise
+            indices.extend([2, 3, 0, 0, 3, 1].map(|index| start + index));
+        }
+    }
+
+    json!({
+        "attributes": {
+            "POSITION": buffer.push_floats(&positions, true),
+            "NORMAL": buffer.push_floats(&normals, false),
+            "TEXCOORD_0": buffer.push_floats(&uvs, false),
+        },
+        "indices": buffer.push_indices(&indices),
+        "material": material,
+    })
+}
+
+/// Packs the JSON and binary chunks of a glTF model into a single GLB file.
+fn write_glb(mut json: Vec<u8>, bin: Vec<u8>) -> Vec<u8> {
+    // Chunks are padded to 4 bytes, with spaces for the JSON chunk
+    json.resize(json.len().next_multiple_of(4), b' ');
+
+    let length = 12 + 8 + json.len() + 8 + bin.len();
+    let mut glb = Vec::with_capacity(length);
+
+    for value in [GLB_MAGIC, GLB_VERSION, length as u32] {
+        glb.extend_from_slice(&value.to_le_bytes());
+    }
+
+    for (chunk_type, chunk) in [(GLB_JSON_CHUNK, json), (GLB_BIN_CHUNK, bin)] {
+        glb.extend_from_slice(&(chunk.len() as u32).to_le_bytes());
+        glb.extend_from_slice(&chunk_type.to_le_bytes());
+        glb.extend_from_slice(&chunk);
+    }
+
+    glb
+}
+
+#[cfg(test)]
+mod tests {
+    use image::RgbaImage;
+    use nmsr_rendering::high_level::{
+        model::{ArmorMaterial, PlayerArmorSlot, PlayerArmorSlots, PlayerModel},
+        parts::provider::PlayerPartProviderContext,
+        types::PlayerPartTextureType,
+    };
+    use serde_json::{json, Value};
+
+    use super::generate_gltf_binary;
+    use crate::generator::{
+        new_model_generator_without_part_context, DefaultImageIO, ModelGenerationProject,
+    };
+
+    const ARMOR_TEXTURE: PlayerPartTextureType = PlayerPartTextureType::Custom {
+        key: "test_armor",
+        size: (64, 32),
+        is_emissive: false,
+    };
+
+    #[derive(Debug, Default, Clone, Copy)]
+    struct TestArmor;
+
+    impl ArmorMaterial for TestArmor {
+        fn get_texture_type(_slot: PlayerArmorSlot) -> Option<PlayerPartTextureType> {
+            Some(ARMOR_TEXTURE)
+        }
+    }
+
+    /// Splits a GLB file into its JSON and binary chunks.
+    fn read_glb(glb: &[u8]) -> (Value, &[u8]) {
+        assert_eq!(&glb[0..4], b"glTF");
+        assert_eq!(
+            u32::from_le_bytes(glb[8..12].try_into().unwrap()) as usize,
+            glb.len()
+        );
+
+        let json_length = u32::from_le_bytes(glb[12..16].try_into().unwrap()) as usize;
+        let gltf = serde_json::from_slice(&glb[20..20 + json_length]).unwrap();
+
+        (gltf, &glb[20 + json_length + 8..])
+    }
+
+    fn find_node(gltf: &Value, name: &str) -> usize {
+        gltf["nodes"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .position(|node| node["name"] == name)
+            .unwrap_or_else(|| panic!("{name} has no node"))
+    }
+
+    /// Returns whether the given node is below a node with the given name, at any depth.
+    fn is_nested_under(gltf: &Value, mut node: usize, ancestor: &str) -> bool {
+        let nodes = gltf["nodes"].as_array().unwrap();
+
+        while let Some(parent) = nodes.iter().position(|candidate| {
+            candidate["children"]
+                .as_array()
+                .is_some_and(|children| children.contains(&json!(node)))
+        }) {
+            if nodes[parent]["name"] == ancestor {
+                return true;
+            }
+
+            node = parent;
+        }
+
+        false
+    }
+
+    #[test]
+    fn glb_has_a_node_for_each_body_part() {
+        let mut project =
+            new_model_generator_without_part_context(PlayerModel::Steve, true, DefaultImageIO);
+        project
+            .add_texture(PlayerPartTextureType::Skin, RgbaImage::new(64, 64), false)
+            .unwrap();
+
+        let glb = generate_gltf_binary(project).unwrap();
+        let (gltf, _) = read_glb(&glb);
+
+        let nodes = gltf["nodes"].as_array().unwrap();
+
+        for name in [
+            "Head",
+            "Body",
+            "Left Arm",
+            "Right Arm",
+            "Left Leg",
+            "Right Leg",
+        ] {
+            assert!(
+                nodes
+                    .iter()
+                    .any(|node| node["name"] == name && node["mesh"].is_u64()),
+                "{name} has no node"
+            );
+            assert!(is_nested_under(&gltf, find_node(&gltf, name), "Player"));
+        }
+
+        assert_eq!(gltf["samplers"][0]["magFilter"], 9728);
+    }
+
+    #[test]
+    fn glb_texture_coordinates_match_face_uvs() {
+        let mut project =
+            new_model_generator_without_part_context(PlayerModel::Steve, true, DefaultImageIO);
+        project
+            .add_texture(PlayerPartTextureType::Skin, RgbaImage::new(64, 64), false)
+            .unwrap();
+
+        // The first face of a cube is its back (south) face
+        let head = project
+            .generate_parts()
+            .into_iter()
+            .find(|part| {
+                part.get_group() == ["Head"] && part.get_texture() == PlayerPartTextureType::Skin
+            })
+            .unwrap();
+        let south = head.get_face_uvs().south;
+
+        let glb = generate_gltf_binary(project).unwrap();
+        let (gltf, bin) = read_glb(&glb);
+
+        let mesh = &gltf["meshes"][gltf["nodes"][find_node(&gltf, "Head")]["mesh"]
+            .as_u64()
+            .unwrap() as usize];
+        let primitive = mesh["primitives"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|primitive| {
+                gltf["materials"][primitive["material"].as_u64().unwrap() as usize]["name"]
+                    == "Skin"
+            })
+            .unwrap();
+
+        let accessor =
+            &gltf["accessors"][primitive["attributes"]["TEXCOORD_0"].as_u64().unwrap() as usize];
+        let view = &gltf["bufferViews"][accessor["bufferView"].as_u64().unwrap() as usize];
+        let offset = view["byteOffset"].as_u64().unwrap() as usize;
+
+        let uvs = bin[offset..][..4 * 2 * 4]
+            .chunks_exact(4)
+            .map(|bytes| f32::from_le_bytes(bytes.try_into().unwrap()))
+            .collect::<Vec<_>>();
+
+        let expected = [
+            south.top_left,
+            south.top_right,
+            south.bottom_left,
+            south.bottom_right,
+        ]
+        .into_iter()
+        .flat_map(|point| [point.x as f32 / 64.0, point.y as f32 / 64.0])
+        .collect::<Vec<_>>();
+
+        assert_eq!(uvs, expected);
+    }
+
+    #[test]
+    fn glb_nests_armor_under_its_body_part() {
+        let context = PlayerPartProviderContext::<TestArmor> {
+            armor_slots: Some(PlayerArmorSlots {
+                helmet: Some(TestArmor),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        let mut project = ModelGenerationProject::new_with_part_context(DefaultImageIO, context);
+        project
+            .add_texture(PlayerPartTextureType::Skin, RgbaImage::new(64, 64), false)
+            .unwrap();
+        project
+            .add_texture(ARMOR_TEXTURE, RgbaImage::new(64, 32), false)
+            .unwrap();
+
+        let glb = generate_gltf_binary(project).unwrap();
+        let (gltf, _) = read_glb(&glb);
+
+        let armor_material = gltf["materials"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .position(|material| material["name"] == ARMOR_TEXTURE.to_string())
+            .unwrap();
+
+        let nodes = gltf["nodes"].as_array().unwrap();
+        let armor_nodes = (0..nodes.len())
+            .filter(|&node| {
+                nodes[node]["mesh"].as_u64().is_some_and(|mesh| {
+                    gltf["meshes"][mesh as usize]["primitives"]
+                        .as_array()
+                        .unwrap()
+                        .iter()
+                        .any(|primitive| primitive["material"] == armor_material)
+                })
+            })
+            .collect::<Vec<_>>();
+
+        // The helmet moves along with the head, so it's either in the head's node or below it
+        assert!(!armor_nodes.is_empty());
+        assert!(armor_nodes
+            .into_iter()
+            .all(|node| nodes[node]["name"] == "Head" || is_nested_under(&gltf, node, "Head")));
+    }
+
+    #[cfg(feature = "ears")]
+    #[test]
+    fn glb_nests_ears_features_under_their_body_part() {
+        use ears_rs::{
+            features::{data::leg::LegMode, EarsFeatures},
+            parser::{v0::writer::EarsWriterV0, EarsFeaturesWriter},
+        };
+
+        let mut skin = RgbaImage::new(64, 64);
+        EarsWriterV0::write(
+            &mut skin,
+            &EarsFeatures {
+                leg_mode: LegMode::DigitigradeFull,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        let mut project =
+            new_model_generator_without_part_context(PlayerModel::Steve, false, DefaultImageIO);
+        project
+            .add_texture(PlayerPartTextureType::Skin, skin, true)
+            .unwrap();
+
+        let glb = generate_gltf_binary(project).unwrap();
+        let (gltf, _) = read_glb(&glb);
+
+        let nodes = gltf["nodes"].as_array().unwrap();
+        let legs = (0..nodes.len())
+            .filter(|&node| nodes[node]["name"] == "Legs")
+            .collect::<Vec<_>>();
+
+        assert_eq!(legs.len(), 2);
+        assert!(legs
+            .iter()
+            .any(|&node| is_nested_under(&gltf, node, "Left Leg")));
+        assert!(legs
+            .iter()
+            .any(|&node| is_nested_under(&gltf, node, "Right Leg")));
+    }
+}
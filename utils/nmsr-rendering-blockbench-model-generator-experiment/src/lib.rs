@@ -1,6 +1,7 @@
 pub mod blockbench;
 pub mod error;
 pub mod generator;
+pub mod gltf;
 
 pub use image;
 pub use nmsr_rendering;
@@ -408,11 +408,13 @@ async fn process_group_logic(
         has_hat_layer: parts.iter().any(|p| p.is_hat_layer()),
         has_layers: parts.iter().any(|p| p.is_layer()),
         has_cape: false,
+        has_elytra: false,
         has_deadmau5_ears: false,
         is_flipped_upside_down: false,
         custom_arm_rotation_z: Some(arm_rotation),
         shadow_y_pos,
         shadow_is_square: false,
+        skull: None,
         armor_slots: None,
         movement: Default::default(),
         #[cfg(feature = "ears")]
@@ -125,11 +125,13 @@ fn main() -> anyhow::Result<()> {
         has_hat_layer: true,
         has_layers: true,
         has_cape: false,
+        has_elytra: false,
         is_flipped_upside_down: false,
         has_deadmau5_ears: false,
         custom_arm_rotation_z: Some(0f32),
         shadow_y_pos: None,
         shadow_is_square: false,
+        skull: None,
         armor_slots: None,
         movement: Default::default(),
         #[cfg(feature = "ears")]
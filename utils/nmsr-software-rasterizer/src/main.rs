@@ -43,11 +43,13 @@ fn main() {
         has_hat_layer: true,
         has_layers: true,
         has_cape: false,
+        has_elytra: false,
         has_deadmau5_ears: false,
         is_flipped_upside_down: false,
         custom_arm_rotation_z: Some(10.0),
         shadow_y_pos: None,
         shadow_is_square: false,
+        skull: None,
         armor_slots: None,
         movement: Default::default(),
         #[cfg(feature = "ears")]